@@ -8,7 +8,7 @@ fn main() {
 
     println!("=== Testing Rust highlighting ===");
     let mut highlighter = gitlogue::syntax::Highlighter::new();
-    let success = highlighter.set_language_from_path("test.rs");
+    let success = highlighter.set_language_for_file("test.rs", "");
     println!("Language set: {}", success);
 
     let highlights = highlighter.highlight(test_code);
@@ -41,7 +41,7 @@ fn main() {
 
     println!("\n=== Testing Markdown highlighting ===");
     let mut md_highlighter = gitlogue::syntax::Highlighter::new();
-    let md_success = md_highlighter.set_language_from_path("test.md");
+    let md_success = md_highlighter.set_language_for_file("test.md", "");
     println!("Language set: {}", md_success);
 
     let md_highlights = md_highlighter.highlight(markdown_code);
@@ -1,12 +1,18 @@
+use anyhow::{Context, Result};
 use std::cell::RefCell;
 use std::time::{Duration, Instant};
 
 use globset::{Glob, GlobMatcher};
 use rand::Rng;
+use tree_sitter::{InputEdit, Point};
 use unicode_width::UnicodeWidthStr;
 
-use crate::git::{CommitMetadata, DiffHunk, FileChange, FileStatus, LineChangeType};
-use crate::syntax::Highlighter;
+use crate::git::{CommitMetadata, DiffHunk, FileChange, FileStatus, GraphCommit, LineChangeType};
+use crate::narrative::{Narrative, NarrativeContext};
+use crate::preview::BinaryPreview;
+use crate::split::{SplitLayout, SplitView};
+use crate::syntax::{Highlighter, LanguageRule};
+use crate::ViewMode;
 
 /// A rule that specifies typing speed for files matching a glob pattern
 #[derive(Debug, Clone)]
@@ -36,30 +42,244 @@ impl SpeedRule {
     }
 }
 
-// Duration multipliers relative to typing speed
-const CURSOR_MOVE_PAUSE: f64 = 0.5; // Cursor movement between lines (base speed)
+/// Lines kept in the terminal pane history by default
+pub const DEFAULT_SCROLLBACK: usize = 1000;
+
+/// What a terminal pane line shows, deciding how it is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalLineKind {
+    /// A typed command, starting with the prompt
+    Command,
+    /// Output of a command
+    Output,
+    /// Separates the narratives of two commits, the text being its label
+    Divider,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalLine {
+    pub kind: TerminalLineKind,
+    pub text: String,
+}
+
+// Cursor movement scaling, relative to `Timing::cursor_move`
 const CURSOR_MOVE_SHORT_MULTIPLIER: f64 = 1.0; // Speed for short distances (1-50 lines)
 const CURSOR_MOVE_MEDIUM_MULTIPLIER: f64 = 0.3; // Speed for medium distances (51-200 lines)
 const CURSOR_MOVE_LONG_MULTIPLIER: f64 = 0.05; // Speed for long distances (201+ lines)
 const MAX_SCROLL_STEPS: usize = 60; // Maximum animation steps for any scroll distance
 const MIN_LOG_STEPS: usize = 50; // Minimum steps for logarithmic scaling (aligned with SHORT threshold)
 const LOG_SCALE_FACTOR: f64 = 8.0; // Scaling factor for logarithmic step calculation
-const DELETE_LINE_PAUSE: f64 = 10.0; // After deleting a line
-const INSERT_LINE_PAUSE: f64 = 6.7; // After inserting a line
-const HUNK_PAUSE: f64 = 50.0; // Between hunks
-const CHECKOUT_PAUSE: f64 = 16.7; // After git checkout command
-const CHECKOUT_OUTPUT_PAUSE: f64 = 33.3; // After git checkout output
-const OPEN_FILE_FIRST_PAUSE: f64 = 33.3; // Before opening first file
-const OPEN_FILE_PAUSE: f64 = 50.0; // Before opening subsequent files
-const OPEN_CMD_PAUSE: f64 = 16.7; // After open command
-const FILE_SWITCH_PAUSE: f64 = 26.7; // After switching file
-const GIT_ADD_PAUSE: f64 = 33.3; // Before git add
-const GIT_ADD_CMD_PAUSE: f64 = 16.7; // After git add command
-const GIT_COMMIT_PAUSE: f64 = 26.7; // After git commit command
-const COMMIT_OUTPUT_PAUSE: f64 = 33.3; // After commit output
-const GIT_PUSH_PAUSE: f64 = 16.7; // After git push command
-const PUSH_OUTPUT_PAUSE: f64 = 10.0; // Between push output lines
-const PUSH_FINAL_PAUSE: f64 = 66.7; // After final push output
+
+// Largest time scale tried when fitting a commit to the target duration
+const MAX_FIT_SCALE: f64 = 1e9;
+// Halvings of the scale interval when fitting, enough for millisecond precision
+const FIT_SEARCH_STEPS: usize = 64;
+
+/// Pause durations, as multiples of the typing speed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    /// Cursor movement between lines (base speed)
+    pub cursor_move: f64,
+    /// After deleting a line
+    pub delete_line: f64,
+    /// After inserting a line
+    pub insert_line: f64,
+    /// Between hunks
+    pub hunk: f64,
+    /// Between changed rows revealed in the split view
+    pub split_row: f64,
+    /// While a deleted file's content is selected
+    pub select_all: f64,
+    /// After deleting the selection
+    pub delete_selection: f64,
+    /// Before opening the first file
+    pub open_file_first: f64,
+    /// Before opening subsequent files
+    pub open_file: f64,
+    /// After the "Open File..." dialog appears
+    pub open_dialog: f64,
+    /// After typing the path in the dialog
+    pub open_cmd: f64,
+    /// After switching file
+    pub file_switch: f64,
+    /// While a binary file preview is shown
+    pub binary_preview: f64,
+    /// Before git add
+    pub git_add: f64,
+    /// After git add command
+    pub git_add_cmd: f64,
+    /// After a narrative command
+    pub narrative_command: f64,
+    /// Between narrative output lines
+    pub narrative_output: f64,
+    /// After the last of consecutive output lines
+    pub narrative_output_end: f64,
+    /// After the closing narrative
+    pub narrative_end: f64,
+    /// Between the end of a commit and the next one
+    pub commit_delay: f64,
+    /// Absolute delay between commits, replacing `commit_delay`
+    pub commit_delay_ms: Option<u64>,
+    /// Play every commit in about this long, scaling typing and pauses
+    pub target_duration: Option<Duration>,
+    /// Fastest typing speed of a commit fitted to the target duration, in ms per character
+    pub min_fit_speed_ms: f64,
+    /// Slowest typing speed of a commit fitted to the target duration, in ms per character
+    pub max_fit_speed_ms: f64,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            cursor_move: 0.5,
+            delete_line: 10.0,
+            insert_line: 6.7,
+            hunk: 50.0,
+            split_row: 3.0,
+            select_all: 33.3,
+            delete_selection: 16.7,
+            open_file_first: 33.3,
+            open_file: 50.0,
+            open_dialog: 5.0,
+            open_cmd: 16.7,
+            file_switch: 26.7,
+            binary_preview: 100.0,
+            git_add: 33.3,
+            git_add_cmd: 16.7,
+            narrative_command: 16.7,
+            narrative_output: 10.0,
+            narrative_output_end: 33.3,
+            narrative_end: 66.7,
+            commit_delay: 100.0,
+            commit_delay_ms: None,
+            target_duration: None,
+            min_fit_speed_ms: 2.0,
+            max_fit_speed_ms: 150.0,
+        }
+    }
+}
+
+impl Timing {
+    /// Built-in timing profiles
+    pub fn preset(name: &str) -> Result<Self> {
+        let timing = Self::default();
+        match name {
+            "default" => Ok(timing),
+            // Lingers on every file and command
+            "cinematic" => Ok(timing.scaled(1.5, 2.0, 2.0, 2.0)),
+            "brisk" => Ok(timing.scaled(0.4, 0.4, 0.4, 0.3)),
+            // Normal edits with time to read the terminal
+            "presentation" => Ok(timing.scaled(1.0, 1.5, 3.0, 1.5)),
+            _ => Err(anyhow::anyhow!("Unknown timing preset: {}", name))
+                .context("Available presets: default, cinematic, brisk, presentation"),
+        }
+    }
+
+    /// Sets a pause by its field name, returning false for an unknown name.
+    pub fn set(&mut self, name: &str, multiplier: f64) -> bool {
+        let field = match name {
+            "cursor_move" => &mut self.cursor_move,
+            "delete_line" => &mut self.delete_line,
+            "insert_line" => &mut self.insert_line,
+            "hunk" => &mut self.hunk,
+            "split_row" => &mut self.split_row,
+            "select_all" => &mut self.select_all,
+            "delete_selection" => &mut self.delete_selection,
+            "open_file_first" => &mut self.open_file_first,
+            "open_file" => &mut self.open_file,
+            "open_dialog" => &mut self.open_dialog,
+            "open_cmd" => &mut self.open_cmd,
+            "file_switch" => &mut self.file_switch,
+            "binary_preview" => &mut self.binary_preview,
+            "git_add" => &mut self.git_add,
+            "git_add_cmd" => &mut self.git_add_cmd,
+            "narrative_command" => &mut self.narrative_command,
+            "narrative_output" => &mut self.narrative_output,
+            "narrative_output_end" => &mut self.narrative_output_end,
+            "narrative_end" => &mut self.narrative_end,
+            "commit_delay" => &mut self.commit_delay,
+            _ => return false,
+        };
+        *field = multiplier;
+        true
+    }
+
+    // Scale the editing, file, terminal and between-commit pauses
+    fn scaled(mut self, editing: f64, files: f64, terminal: f64, commits: f64) -> Self {
+        for pause in [
+            &mut self.cursor_move,
+            &mut self.delete_line,
+            &mut self.insert_line,
+            &mut self.hunk,
+            &mut self.split_row,
+            &mut self.select_all,
+            &mut self.delete_selection,
+        ] {
+            *pause *= editing;
+        }
+        for pause in [
+            &mut self.open_file_first,
+            &mut self.open_file,
+            &mut self.open_dialog,
+            &mut self.open_cmd,
+            &mut self.file_switch,
+            &mut self.binary_preview,
+        ] {
+            *pause *= files;
+        }
+        for pause in [
+            &mut self.git_add,
+            &mut self.git_add_cmd,
+            &mut self.narrative_command,
+            &mut self.narrative_output,
+            &mut self.narrative_output_end,
+            &mut self.narrative_end,
+        ] {
+            *pause *= terminal;
+        }
+        self.commit_delay *= commits;
+        self
+    }
+
+    /// Wait between commits at a typing speed
+    pub fn commit_delay(&self, speed_ms: u64) -> Duration {
+        match self.commit_delay_ms {
+            Some(ms) => Duration::from_millis(ms),
+            None => Duration::from_millis((speed_ms as f64 * self.commit_delay) as u64),
+        }
+    }
+}
+
+/// How a buffer line differs from the file as it was opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineStatus {
+    #[default]
+    Unchanged,
+    Added,
+    Modified,
+}
+
+/// Gutter state of a buffer line
+#[derive(Debug, Clone, Default)]
+pub struct LineMark {
+    pub status: LineStatus,
+    /// Number of lines of the opened file deleted right above this one
+    pub deleted_above: usize,
+    /// When text was last typed into the line
+    pub typed_at: Option<Instant>,
+    /// A hunk that has not been typed yet starts at this line
+    pub pending_hunk: bool,
+}
+
+impl LineMark {
+    fn typed(status: LineStatus) -> Self {
+        Self {
+            status,
+            typed_at: Some(Instant::now()),
+            ..Self::default()
+        }
+    }
+}
 
 /// Represents the current state of the editor buffer
 #[derive(Debug, Clone)]
@@ -68,16 +288,16 @@ pub struct EditorBuffer {
     pub cursor_line: usize,
     pub cursor_col: usize,
     pub scroll_offset: usize,
+    /// Selected line range (inclusive), shown with a highlighted background
+    pub selection: Option<(usize, usize)>,
+    /// Highlights of the buffer text, refreshed at most once per frame
     pub cached_highlights: Vec<crate::syntax::HighlightSpan>,
-    /// Pre-calculated highlights for old and new content
-    pub old_highlights: Vec<crate::syntax::HighlightSpan>,
-    pub new_highlights: Vec<crate::syntax::HighlightSpan>,
-    /// Store old and new content for byte offset calculation
-    pub old_content_lines: Vec<String>,
-    pub new_content_lines: Vec<String>,
-    /// Pre-calculated byte offsets for each line (handles CRLF correctly)
-    pub old_content_line_offsets: Vec<usize>,
-    pub new_content_line_offsets: Vec<usize>,
+    /// Byte offset of each line in the highlighted text
+    pub line_offsets: Vec<usize>,
+    /// Edits made since the last highlight refresh, in the order they were applied
+    pub pending_edits: Vec<InputEdit>,
+    /// Gutter state of each line, plus a trailing entry for lines deleted at the end
+    pub marks: Vec<LineMark>,
 }
 
 impl EditorBuffer {
@@ -88,13 +308,11 @@ impl EditorBuffer {
             cursor_line: 0,
             cursor_col: 0,
             scroll_offset: 0,
+            selection: None,
             cached_highlights: Vec::new(),
-            old_highlights: Vec::new(),
-            new_highlights: Vec::new(),
-            old_content_lines: Vec::new(),
-            new_content_lines: Vec::new(),
-            old_content_line_offsets: Vec::new(),
-            new_content_line_offsets: Vec::new(),
+            line_offsets: Vec::new(),
+            pending_edits: Vec::new(),
+            marks: vec![LineMark::default(); 2],
         }
     }
 
@@ -105,27 +323,51 @@ impl EditorBuffer {
         } else {
             content.lines().map(|s| s.to_string()).collect()
         };
+        let marks = vec![LineMark::default(); lines.len() + 1];
 
         Self {
             lines,
             cursor_line: 0,
             cursor_col: 0,
             scroll_offset: 0,
+            selection: None,
             cached_highlights: Vec::new(),
-            old_highlights: Vec::new(),
-            new_highlights: Vec::new(),
-            old_content_lines: Vec::new(),
-            new_content_lines: Vec::new(),
-            old_content_line_offsets: Vec::new(),
-            new_content_line_offsets: Vec::new(),
+            line_offsets: Vec::new(),
+            pending_edits: Vec::new(),
+            marks,
+        }
+    }
+
+    /// Marks where each hunk of the opened file starts, until its first line is edited.
+    pub fn mark_pending_hunks(&mut self, hunks: &[DiffHunk]) {
+        for hunk in hunks {
+            // Skip the leading context to the first deleted line or insertion point
+            let begin = if hunk.old_lines == 0 {
+                hunk.old_start
+            } else {
+                hunk.old_start - 1
+            };
+            let context = hunk
+                .lines
+                .iter()
+                .take_while(|line| matches!(line.change_type, LineChangeType::Context))
+                .count();
+            let line = (begin + context).min(self.lines.len());
+            self.marks[line].pending_hunk = true;
         }
     }
 
+    /// Returns the buffer text as it is highlighted, with lines joined by `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
     /// Inserts a character at the specified line and column position.
     pub fn insert_char(&mut self, line: usize, col: usize, ch: char) {
         if line >= self.lines.len() {
-            self.lines.resize(line + 1, String::new());
+            self.pad_lines(line + 1);
         }
+        let start_byte = self.line_start_byte(line);
         let line_str = &mut self.lines[line];
 
         // Convert char index to byte index
@@ -136,24 +378,190 @@ impl EditorBuffer {
             .unwrap_or_else(|| line_str.len());
 
         line_str.insert(byte_idx, ch);
+
+        let mark = &mut self.marks[line];
+        if mark.status == LineStatus::Unchanged {
+            mark.status = LineStatus::Modified;
+        }
+        mark.typed_at = Some(Instant::now());
+
+        let start = Point::new(line, byte_idx);
+        self.record_edit(
+            start_byte + byte_idx,
+            start_byte + byte_idx,
+            start_byte + byte_idx + ch.len_utf8(),
+            start,
+            start,
+            Point::new(line, byte_idx + ch.len_utf8()),
+        );
     }
 
     /// Inserts a new line with the given content at the specified position.
     pub fn insert_line(&mut self, line: usize, content: String) {
         if line > self.lines.len() {
-            self.lines.resize(line, String::new());
+            self.pad_lines(line);
+        }
+
+        if line < self.lines.len() {
+            // `content\n` goes in front of the current line
+            let start_byte = self.line_start_byte(line);
+            let start = Point::new(line, 0);
+            self.record_edit(
+                start_byte,
+                start_byte,
+                start_byte + content.len() + 1,
+                start,
+                start,
+                Point::new(line + 1, 0),
+            );
+        } else {
+            // `\ncontent` goes after the last line
+            let start_byte = self.text_len();
+            let start = self.end_point();
+            self.record_edit(
+                start_byte,
+                start_byte,
+                start_byte + content.len() + 1,
+                start,
+                start,
+                Point::new(line, content.len()),
+            );
         }
         self.lines.insert(line, content);
+
+        // A line typed where old lines were deleted replaces one of them
+        let below = &mut self.marks[line];
+        below.pending_hunk = false;
+        let status = if below.deleted_above > 0 {
+            below.deleted_above -= 1;
+            LineStatus::Modified
+        } else {
+            LineStatus::Added
+        };
+        self.marks.insert(line, LineMark::typed(status));
+    }
+
+    /// Removes all lines in the current selection.
+    pub fn delete_selection(&mut self) {
+        let Some((start, end)) = self.selection.take() else {
+            return;
+        };
+        let end = end.min(self.lines.len().saturating_sub(1));
+        if start <= end {
+            self.delete_lines(start, end);
+        }
     }
 
     /// Deletes the line at the specified position.
     pub fn delete_line(&mut self, line: usize) {
         if line < self.lines.len() {
-            self.lines.remove(line);
+            self.delete_lines(line, line);
         }
+    }
+
+    // Removes lines `start..=end`, which must be in bounds, keeping at least one line
+    fn delete_lines(&mut self, start: usize, end: usize) {
+        let last = self.lines.len() - 1;
+        let (start_byte, start_point, old_end_byte, old_end_point) = if end < last {
+            // Remove up to the beginning of the line after the range
+            (
+                self.line_start_byte(start),
+                Point::new(start, 0),
+                self.line_start_byte(end + 1),
+                Point::new(end + 1, 0),
+            )
+        } else if start > 0 {
+            // Remove the range together with the newline that precedes it
+            (
+                self.line_start_byte(start) - 1,
+                Point::new(start - 1, self.lines[start - 1].len()),
+                self.text_len(),
+                self.end_point(),
+            )
+        } else {
+            // Every line goes, leaving a single empty one
+            (0, Point::new(0, 0), self.text_len(), self.end_point())
+        };
+
+        self.lines.drain(start..=end);
+        let deleted: usize = self
+            .marks
+            .drain(start..=end)
+            .map(|mark| mark.deleted_above + usize::from(mark.status != LineStatus::Added))
+            .sum();
+        self.marks[start].deleted_above += deleted;
         if self.lines.is_empty() {
             self.lines.push(String::new());
+            let deleted_above = std::mem::take(&mut self.marks[0].deleted_above);
+            self.marks.insert(
+                0,
+                LineMark {
+                    deleted_above,
+                    ..LineMark::default()
+                },
+            );
         }
+        self.record_edit(
+            start_byte,
+            old_end_byte,
+            start_byte,
+            start_point,
+            old_end_point,
+            start_point,
+        );
+    }
+
+    // Appends empty lines until the buffer has `len` lines
+    fn pad_lines(&mut self, len: usize) {
+        let old_len = self.lines.len();
+        let added = len - old_len;
+        let start_byte = self.text_len();
+        let start = self.end_point();
+        self.lines.resize(len, String::new());
+        self.marks.splice(
+            old_len..old_len,
+            (old_len..len).map(|_| LineMark::typed(LineStatus::Added)),
+        );
+        self.record_edit(
+            start_byte,
+            start_byte,
+            start_byte + added,
+            start,
+            start,
+            Point::new(len - 1, 0),
+        );
+    }
+
+    fn line_start_byte(&self, line: usize) -> usize {
+        self.lines[..line].iter().map(|l| l.len() + 1).sum()
+    }
+
+    fn text_len(&self) -> usize {
+        self.line_start_byte(self.lines.len()) - 1
+    }
+
+    fn end_point(&self) -> Point {
+        let last = self.lines.len() - 1;
+        Point::new(last, self.lines[last].len())
+    }
+
+    fn record_edit(
+        &mut self,
+        start_byte: usize,
+        old_end_byte: usize,
+        new_end_byte: usize,
+        start_position: Point,
+        old_end_position: Point,
+        new_end_position: Point,
+    ) {
+        self.pending_edits.push(InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position,
+            old_end_position,
+            new_end_position,
+        });
     }
 }
 
@@ -196,6 +604,19 @@ pub enum AnimationStep {
     TerminalOutput {
         text: String,
     },
+    /// Separates a commit's narrative from the previous one
+    TerminalDivider {
+        label: String,
+    },
+    SelectAll,
+    DeleteSelection,
+    ShowPreview {
+        file_index: usize,
+    },
+    ShowSplit {
+        file_index: usize,
+    },
+    RevealSplitRow,
     ResetState,
 }
 
@@ -225,17 +646,21 @@ pub struct AnimationEngine {
     base_speed_ms: u64,
     next_step_delay: u64,
     pause_until: Option<Instant>,
+    /// When playback was held by the user, until resumed
+    held_at: Option<Instant>,
     pub cursor_visible: bool,
     cursor_blink_timer: Instant,
     viewport_height: usize,
     content_width: usize,
     pub current_file_index: usize,
     pub current_file_path: Option<String>,
-    pub terminal_lines: Vec<String>,
+    pub terminal_lines: Vec<TerminalLine>,
+    /// Terminal lines kept, dropping the oldest ones
+    scrollback: usize,
+    /// Terminal lines scrolled back from the latest one
+    pub terminal_scroll: usize,
     pub active_pane: ActivePane,
     pub highlighter: RefCell<Highlighter>,
-    /// Track cumulative line offset from old_content (insertions - deletions)
-    pub line_offset: isize,
     /// Target frames per second for rendering
     #[allow(dead_code)]
     target_fps: u64,
@@ -247,12 +672,32 @@ pub struct AnimationEngine {
     pub dialog_title: Option<String>,
     /// Text being typed in the dialog
     pub dialog_typing_text: String,
+    /// Preview shown instead of the buffer for binary files
+    pub preview: Option<BinaryPreview>,
+    /// Side-by-side diff shown instead of the buffer in split view mode
+    pub split: Option<SplitView>,
     /// Current metadata being displayed
     current_metadata: Option<CommitMetadata>,
     /// Pending metadata to be applied on ResetState
     pending_metadata: Option<CommitMetadata>,
     /// Speed rules for different file patterns
     speed_rules: Vec<SpeedRule>,
+    /// Open deleted files and delete their content in the editor
+    animate_deletions: bool,
+    /// Whether changes are typed out or shown side by side
+    view_mode: ViewMode,
+    /// Show the buffer overview column in the editor
+    minimap: bool,
+    /// Tint freshly typed lines in the editor
+    highlight_typed_lines: bool,
+    /// Terminal story told around each commit's files
+    narrative: Narrative,
+    /// Pause durations
+    timing: Timing,
+    /// Factor applied to every delay, below 1 to play faster
+    time_scale: f64,
+    /// Bounds of every scaled typing speed while fitted to the target duration
+    speed_bounds: Option<(f64, f64)>,
 }
 
 impl AnimationEngine {
@@ -271,6 +716,7 @@ impl AnimationEngine {
             base_speed_ms: speed_ms,
             next_step_delay: speed_ms,
             pause_until: None,
+            held_at: None,
             cursor_visible: true,
             cursor_blink_timer: now,
             viewport_height: 20, // Default, will be updated from UI
@@ -278,17 +724,28 @@ impl AnimationEngine {
             current_file_index: 0,
             current_file_path: None,
             terminal_lines: Vec::new(),
+            scrollback: DEFAULT_SCROLLBACK,
+            terminal_scroll: 0,
             active_pane: ActivePane::Terminal, // Start with terminal (git checkout)
             highlighter: RefCell::new(Highlighter::new()),
-            line_offset: 0,
             target_fps,
             frame_interval_ms,
             last_frame: now,
             dialog_title: None,
             dialog_typing_text: String::new(),
+            preview: None,
+            split: None,
             current_metadata: None,
             pending_metadata: None,
             speed_rules: Vec::new(),
+            animate_deletions: false,
+            view_mode: ViewMode::default(),
+            highlight_typed_lines: false,
+            minimap: false,
+            narrative: Narrative::load("fun").unwrap_or_default(),
+            timing: Timing::default(),
+            time_scale: 1.0,
+            speed_bounds: None,
         }
     }
 
@@ -297,6 +754,140 @@ impl AnimationEngine {
         self.speed_rules = rules;
     }
 
+    /// Set user rules mapping file globs to languages
+    pub fn set_language_rules(&mut self, rules: Vec<LanguageRule>) {
+        self.highlighter.borrow_mut().set_language_rules(rules);
+    }
+
+    /// Show deleted files being opened and emptied in the editor
+    pub fn set_animate_deletions(&mut self, enabled: bool) {
+        self.animate_deletions = enabled;
+    }
+
+    /// Type changes out in the editor or reveal them in a side-by-side diff
+    pub fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+    }
+
+    /// Tint freshly typed lines with a background that fades out
+    pub fn set_highlight_typed_lines(&mut self, enabled: bool) {
+        self.highlight_typed_lines = enabled;
+    }
+
+    /// Whether freshly typed lines are tinted
+    pub fn highlight_typed_lines(&self) -> bool {
+        self.highlight_typed_lines
+    }
+
+    /// Show an overview of the buffer with the viewport and hunks next to the editor
+    pub fn set_minimap(&mut self, enabled: bool) {
+        self.minimap = enabled;
+    }
+
+    /// Whether the buffer overview column is shown
+    pub fn minimap(&self) -> bool {
+        self.minimap
+    }
+
+    /// Terminal commands and output played before and after each commit's files
+    pub fn set_narrative(&mut self, narrative: Narrative) {
+        self.narrative = narrative;
+    }
+
+    /// Pause durations of every animation step and between commits
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    /// Scales every typing delay and pause, e.g. 0.5 to play twice as fast, without speed bounds
+    pub fn set_time_scale(&mut self, scale: f64) {
+        self.time_scale = scale;
+        self.speed_bounds = None;
+    }
+
+    /// Time the remaining steps take at the configured speeds, before scaling
+    pub fn nominal_duration(&self) -> Duration {
+        Duration::from_millis(self.steps_duration_ms(|speed_ms| speed_ms as f64) as u64)
+    }
+
+    /// Time the remaining steps take at the current scale
+    pub fn scaled_duration(&self) -> Duration {
+        Duration::from_millis(self.steps_duration_ms(|speed_ms| self.scaled_speed(speed_ms)) as u64)
+    }
+
+    // Sum the delays of the remaining steps, given each step's typing speed
+    fn steps_duration_ms(&self, speed: impl Fn(u64) -> f64) -> f64 {
+        let mut speed_ms = speed(self.speed_ms);
+        let mut total_ms = 0.0;
+        for step in self.steps.iter().skip(self.current_step) {
+            total_ms += match step {
+                AnimationStep::DialogTypeChar { .. } => speed_ms * 2.0,
+                AnimationStep::Pause { multiplier } => speed_ms * multiplier,
+                // The file's speed applies from the next step
+                AnimationStep::SwitchFile { path, .. } => {
+                    std::mem::replace(&mut speed_ms, speed(self.get_speed_for_file(path)))
+                }
+                _ => speed_ms,
+            };
+        }
+        total_ms
+    }
+
+    /// Scales the loaded commit to play in about the target duration, keeping every
+    /// scaled typing speed between `min_fit_speed_ms` and `max_fit_speed_ms`
+    pub fn fit_to_target(&mut self) {
+        self.set_time_scale(1.0);
+        let Some(target) = self.timing.target_duration else {
+            return;
+        };
+        if self.nominal_duration().is_zero() {
+            return;
+        }
+        let (min, max) = (self.timing.min_fit_speed_ms, self.timing.max_fit_speed_ms);
+        self.speed_bounds = Some((min, max.max(min)));
+
+        // Bounded speeds make the duration grow with the scale until every step is at
+        // the slowest speed, so search the scale that reaches the target
+        let target_ms = target.as_secs_f64() * 1000.0;
+        let duration_ms = |engine: &mut Self, scale: f64| {
+            engine.time_scale = scale;
+            engine.steps_duration_ms(|speed_ms| engine.scaled_speed(speed_ms))
+        };
+        let (mut low, mut high) = (0.0, 1.0);
+        while duration_ms(self, high) < target_ms && high < MAX_FIT_SCALE {
+            low = high;
+            high *= 2.0;
+        }
+        for _ in 0..FIT_SEARCH_STEPS {
+            let middle = (low + high) / 2.0;
+            if duration_ms(self, middle) < target_ms {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        self.time_scale = high;
+    }
+
+    // A typing speed at the current scale, within the bounds while fitted to the target
+    fn scaled_speed(&self, speed_ms: u64) -> f64 {
+        let speed = speed_ms as f64 * self.time_scale;
+        match self.speed_bounds {
+            Some((min, max)) => speed.max(min).min(max),
+            None => speed,
+        }
+    }
+
+    /// Wait before playing the next commit
+    pub fn commit_delay(&self) -> Duration {
+        self.timing.commit_delay(self.base_speed_ms)
+    }
+
+    /// Number of terminal lines kept in the history
+    pub fn set_scrollback(&mut self, lines: usize) {
+        self.scrollback = lines.max(1);
+    }
+
     /// Get the speed for a given file path based on speed rules
     /// Returns the first matching rule's speed, or the base speed if no match
     fn get_speed_for_file(&self, path: &str) -> u64 {
@@ -343,8 +934,40 @@ impl AnimationEngine {
         }
     }
 
-    /// Load a commit and generate animation steps
-    pub fn load_commit(&mut self, metadata: &CommitMetadata) {
+    /// Type the commands of narrative lines and print their output
+    fn add_narrative_steps(&mut self, lines: &[String], context: &NarrativeContext) {
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(command) = line.strip_prefix("$ ") {
+                self.add_terminal_command(&context.expand(command));
+                self.steps.push(AnimationStep::Pause {
+                    multiplier: self.timing.narrative_command,
+                });
+                continue;
+            }
+            // Output lines come in quick succession, with a longer pause after the last one
+            let last_output = lines.get(i + 1).is_none_or(|next| next.starts_with("$ "));
+            let texts = context.expand_lines(line);
+            let count = texts.len();
+            for (j, text) in texts.into_iter().enumerate() {
+                self.steps.push(AnimationStep::TerminalOutput { text });
+                self.steps.push(AnimationStep::Pause {
+                    multiplier: if last_output && j + 1 == count {
+                        self.timing.narrative_output_end
+                    } else {
+                        self.timing.narrative_output
+                    },
+                });
+            }
+        }
+    }
+
+    /// Whether the narrative prints recent history, which `load_commit` then needs
+    pub fn narrative_uses_log(&self) -> bool {
+        self.narrative.uses_log()
+    }
+
+    /// Load a commit and generate animation steps, with its recent history for the narrative
+    pub fn load_commit(&mut self, metadata: &CommitMetadata, history: &[GraphCommit]) {
         // Store pending metadata to be applied on ResetState
         self.pending_metadata = Some(metadata.clone());
 
@@ -357,62 +980,61 @@ impl AnimationEngine {
         // Check if this is a working tree diff (not a real commit)
         let is_working_tree = metadata.hash == "working-tree";
 
-        if is_working_tree {
-            // Simplified intro for working tree diffs
-            self.add_terminal_command("git diff --stat");
-            self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!("📝 {}", metadata.message),
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!(
-                    "📁 {} file{} changed",
-                    metadata.changes.len(),
-                    if metadata.changes.len() == 1 { "" } else { "s" }
-                ),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_OUTPUT_PAUSE,
-            });
+        let label = if is_working_tree {
+            metadata.message.clone()
         } else {
-            // Time travel to commit date
-            let datetime_str = metadata.date.format("%Y-%m-%d %H:%M:%S").to_string();
-            self.add_terminal_command(&format!("time-travel {}", datetime_str));
-            self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "⚡ Initializing temporal displacement field...".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_OUTPUT_PAUSE * 0.5,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "✨ Warping through spacetime...".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_OUTPUT_PAUSE * 0.5,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!("🕰️  Arrived at {}", datetime_str),
-            });
+            let summary = metadata.message.lines().next().unwrap_or("");
+            format!("{} {}", &metadata.hash[..7], summary)
+        };
+        self.steps.push(AnimationStep::TerminalDivider { label });
+
+        let context = NarrativeContext::new(metadata, history);
+        let (intro, outro) = if is_working_tree {
+            (
+                self.narrative.working_tree_intro.clone(),
+                self.narrative.working_tree_outro.clone(),
+            )
+        } else {
+            (self.narrative.intro.clone(), self.narrative.outro.clone())
+        };
+        self.add_narrative_steps(&intro, &context);
+
+        // Apply new metadata after intro animation
+        self.steps.push(AnimationStep::ResetState);
+
+        // Note files dropped by the per-commit size limits
+        if !metadata.omitted_files.is_empty() {
+            const LISTED_FILES: usize = 5;
+            let mut listed = metadata
+                .omitted_files
+                .iter()
+                .take(LISTED_FILES)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            if metadata.omitted_files.len() > LISTED_FILES {
+                listed.push_str(&format!(
+                    " (+{} more)",
+                    metadata.omitted_files.len() - LISTED_FILES
+                ));
+            }
             self.steps.push(AnimationStep::TerminalOutput {
                 text: format!(
-                    "📍 Location: commit {} by {}",
-                    &metadata.hash[..7],
-                    metadata.author
+                    "✂️  Omitted {} file{} over the commit size limit: {}",
+                    metadata.omitted_files.len(),
+                    if metadata.omitted_files.len() == 1 {
+                        ""
+                    } else {
+                        "s"
+                    },
+                    listed
                 ),
             });
             self.steps.push(AnimationStep::Pause {
-                multiplier: CHECKOUT_OUTPUT_PAUSE,
+                multiplier: self.timing.open_cmd,
             });
         }
 
-        // Apply new metadata after intro animation
-        self.steps.push(AnimationStep::ResetState);
-
         // Sort file changes to match FileTree display order (directory -> filename)
         let sorted_indices = metadata.sorted_file_indices();
 
@@ -433,7 +1055,7 @@ impl AnimationEngine {
                     });
 
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_FILE_PAUSE,
+                        multiplier: self.timing.open_file,
                     });
                     let reason = change
                         .exclusion_reason
@@ -443,7 +1065,67 @@ impl AnimationEngine {
                         text: format!("📦 {} (skipped - {})", change.path, reason),
                     });
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_CMD_PAUSE,
+                        multiplier: self.timing.open_cmd,
+                    });
+                }
+                // Binary files show an image preview or hex dump instead of typed edits
+                (false, status) if change.binary.is_some() => {
+                    if let (FileStatus::Renamed, Some(old_path)) = (status, &change.old_path) {
+                        self.steps.push(AnimationStep::Pause {
+                            multiplier: self.timing.git_add,
+                        });
+                        self.add_terminal_command(&format!("mv {} {}", old_path, change.path));
+                        self.steps.push(AnimationStep::Pause {
+                            multiplier: self.timing.git_add_cmd,
+                        });
+                    }
+
+                    self.add_open_file_steps(index, change);
+                    self.steps
+                        .push(AnimationStep::ShowPreview { file_index: index });
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.binary_preview,
+                    });
+
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add,
+                    });
+                    if *status == FileStatus::Deleted {
+                        self.add_terminal_command(&format!("rm {}", change.path));
+                        self.steps.push(AnimationStep::Pause {
+                            multiplier: self.timing.git_add_cmd,
+                        });
+                    }
+                    self.add_terminal_command(&format!("git add {}", change.path));
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add_cmd,
+                    });
+                }
+                // Optionally open deleted files and delete their whole content in the editor
+                (false, FileStatus::Deleted)
+                    if self.animate_deletions && change.old_content.is_some() =>
+                {
+                    self.add_open_file_steps(index, change);
+
+                    self.steps.push(AnimationStep::SelectAll);
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.select_all,
+                    });
+                    self.steps.push(AnimationStep::DeleteSelection);
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.delete_selection,
+                    });
+
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add,
+                    });
+                    self.add_terminal_command(&format!("rm {}", change.path));
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add_cmd,
+                    });
+                    self.add_terminal_command(&format!("git add {}", change.path));
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add_cmd,
                     });
                 }
                 // For deleted files, skip editor animation and only run rm + git add
@@ -458,18 +1140,41 @@ impl AnimationEngine {
                     });
 
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_PAUSE,
+                        multiplier: self.timing.git_add,
                     });
                     self.add_terminal_command(&format!("rm {}", change.path));
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_CMD_PAUSE,
+                        multiplier: self.timing.git_add_cmd,
+                    });
+                    self.add_terminal_command(&format!("git add {}", change.path));
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add_cmd,
+                    });
+                }
+                // For renamed files with edits, run mv and then replay the edits on the new path
+                (false, FileStatus::Renamed) if !change.hunks.is_empty() => {
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add,
+                    });
+                    if let Some(old_path) = &change.old_path {
+                        self.add_terminal_command(&format!("mv {} {}", old_path, change.path));
+                        self.steps.push(AnimationStep::Pause {
+                            multiplier: self.timing.git_add_cmd,
+                        });
+                    }
+
+                    self.add_open_file_steps(index, change);
+                    self.generate_edit_steps(index, change);
+
+                    self.steps.push(AnimationStep::Pause {
+                        multiplier: self.timing.git_add,
                     });
                     self.add_terminal_command(&format!("git add {}", change.path));
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_CMD_PAUSE,
+                        multiplier: self.timing.git_add_cmd,
                     });
                 }
-                // For renamed/moved files, skip editor animation and only run mv + git add
+                // For pure renames/moves, skip editor animation and only run mv + git add
                 (false, FileStatus::Renamed) => {
                     // Switch to the renamed file to show in file tree
                     let old_content = change.old_content.clone().unwrap_or_default();
@@ -482,145 +1187,113 @@ impl AnimationEngine {
                     });
 
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_PAUSE,
+                        multiplier: self.timing.git_add,
                     });
                     if let Some(old_path) = &change.old_path {
                         self.add_terminal_command(&format!("mv {} {}", old_path, change.path));
                         self.steps.push(AnimationStep::Pause {
-                            multiplier: GIT_ADD_CMD_PAUSE,
+                            multiplier: self.timing.git_add_cmd,
                         });
                     }
                     self.add_terminal_command(&format!("git add {}", change.path));
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_CMD_PAUSE,
+                        multiplier: self.timing.git_add_cmd,
                     });
                 }
                 // Normal files (Added, Modified, etc.) - full editor animation
                 (false, _) => {
-                    // Open file in editor
-                    if index == 0 {
-                        self.steps.push(AnimationStep::Pause {
-                            multiplier: OPEN_FILE_FIRST_PAUSE,
-                        });
-                    } else {
-                        self.steps.push(AnimationStep::Pause {
-                            multiplier: OPEN_FILE_PAUSE,
-                        });
-                    }
-                    // Show "Open File..." dialog and type the file path
-                    self.steps.push(AnimationStep::OpenFileDialogStart);
-                    self.steps.push(AnimationStep::Pause { multiplier: 5.0 });
-
-                    // Type each character of the file path
-                    for ch in change.path.chars() {
-                        self.steps.push(AnimationStep::DialogTypeChar { ch });
-                    }
-
-                    self.steps.push(AnimationStep::Pause {
-                        multiplier: OPEN_CMD_PAUSE,
-                    });
-
-                    // Add file switch step with both old and new content
-                    let old_content = change.old_content.clone().unwrap_or_default();
-                    let new_content = change.new_content.clone().unwrap_or_default();
-                    self.steps.push(AnimationStep::SwitchFile {
-                        file_index: index,
-                        old_content,
-                        new_content,
-                        path: change.path.clone(),
-                    });
-
-                    // Add pause before starting file animation
-                    self.steps.push(AnimationStep::Pause {
-                        multiplier: FILE_SWITCH_PAUSE,
-                    });
+                    self.add_open_file_steps(index, change);
 
                     // Generate animation steps for this file
-                    self.generate_steps_for_file(change);
+                    self.generate_edit_steps(index, change);
 
                     // Git add this file after editing
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_PAUSE,
+                        multiplier: self.timing.git_add,
                     });
                     self.add_terminal_command(&format!("git add {}", change.path));
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: GIT_ADD_CMD_PAUSE,
+                        multiplier: self.timing.git_add_cmd,
                     });
                 }
             }
         }
 
-        // Skip git commit/push animation for working tree diffs
-        if is_working_tree {
-            // Just add a final pause for working tree mode
+        self.add_narrative_steps(&outro, &context);
+        self.steps.push(AnimationStep::Pause {
+            multiplier: self.timing.narrative_end,
+        });
+
+        // Start with empty editor (no file opened yet)
+        self.buffer = EditorBuffer::new();
+    }
+
+    /// Open a file through the "Open File..." dialog and switch the editor to it
+    fn add_open_file_steps(&mut self, index: usize, change: &FileChange) {
+        if index == 0 {
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_FINAL_PAUSE,
+                multiplier: self.timing.open_file_first,
             });
         } else {
-            // Git commit
-            let parent_hash = format!("{}^", &metadata.hash[..7]);
-            let commit_message = metadata.message.lines().next().unwrap_or("Update");
-            self.add_terminal_command(&format!("git commit -m \"{}\"", commit_message));
-            self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_COMMIT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!("💾 [main {}] {}", &metadata.hash[..7], commit_message),
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!(
-                    "📝 {} file{} changed - immortalized forever!",
-                    metadata.changes.len(),
-                    if metadata.changes.len() == 1 { "" } else { "s" }
-                ),
-            });
             self.steps.push(AnimationStep::Pause {
-                multiplier: COMMIT_OUTPUT_PAUSE,
+                multiplier: self.timing.open_file,
             });
+        }
+        // Show "Open File..." dialog and type the file path
+        self.steps.push(AnimationStep::OpenFileDialogStart);
+        self.steps.push(AnimationStep::Pause {
+            multiplier: self.timing.open_dialog,
+        });
+
+        // Type each character of the file path
+        for ch in change.path.chars() {
+            self.steps.push(AnimationStep::DialogTypeChar { ch });
+        }
 
-            // Git push
-            self.add_terminal_command("git push origin main");
-            self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_PUSH_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "🚀 Launching code into the cloud...".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "📦 Compressing digital dreams: 100% (5/5)".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "✍️  Signing with invisible ink: done.".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: GIT_PUSH_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: "📡 Beaming to origin/main via satellite...".to_string(),
-            });
-            self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_OUTPUT_PAUSE,
-            });
-            self.steps.push(AnimationStep::TerminalOutput {
-                text: format!(
-                    "   {}..{} ✨ SUCCESS",
-                    &parent_hash[..7],
-                    &metadata.hash[..7]
-                ),
-            });
+        self.steps.push(AnimationStep::Pause {
+            multiplier: self.timing.open_cmd,
+        });
+
+        // Add file switch step with both old and new content
+        let old_content = change.old_content.clone().unwrap_or_default();
+        let new_content = change.new_content.clone().unwrap_or_default();
+        self.steps.push(AnimationStep::SwitchFile {
+            file_index: index,
+            old_content,
+            new_content,
+            path: change.path.clone(),
+        });
+
+        // Add pause before starting file animation
+        self.steps.push(AnimationStep::Pause {
+            multiplier: self.timing.file_switch,
+        });
+    }
+
+    /// Generate the editor steps of a file change for the current view mode
+    fn generate_edit_steps(&mut self, index: usize, change: &FileChange) {
+        match self.view_mode {
+            ViewMode::Typing => self.generate_steps_for_file(change),
+            ViewMode::Split => self.generate_split_steps(index, change),
+        }
+    }
+
+    /// Generate steps that reveal a file change hunk by hunk in the split view
+    fn generate_split_steps(&mut self, index: usize, change: &FileChange) {
+        self.steps
+            .push(AnimationStep::ShowSplit { file_index: index });
+
+        for &rows in SplitLayout::from_change(change).hunk_sizes() {
+            for _ in 0..rows {
+                self.steps.push(AnimationStep::RevealSplitRow);
+                self.steps.push(AnimationStep::Pause {
+                    multiplier: self.timing.split_row,
+                });
+            }
             self.steps.push(AnimationStep::Pause {
-                multiplier: PUSH_FINAL_PAUSE,
+                multiplier: self.timing.hunk,
             });
         }
-
-        // Start with empty editor (no file opened yet)
-        self.buffer = EditorBuffer::new();
     }
 
     /// Generate animation steps for a file change
@@ -674,7 +1347,7 @@ impl AnimationEngine {
 
             // Add pause between hunks
             self.steps.push(AnimationStep::Pause {
-                multiplier: HUNK_PAUSE,
+                multiplier: self.timing.hunk,
             });
         }
     }
@@ -732,7 +1405,7 @@ impl AnimationEngine {
         }
 
         // Generate movement steps
-        let pause_multiplier = (CURSOR_MOVE_PAUSE * base_speed_multiplier).max(0.01);
+        let pause_multiplier = (self.timing.cursor_move * base_speed_multiplier).max(0.01);
 
         for line in positions {
             if line != from_line {
@@ -780,7 +1453,7 @@ impl AnimationEngine {
                     self.steps
                         .push(AnimationStep::DeleteLine { line: buffer_line });
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: DELETE_LINE_PAUSE,
+                        multiplier: self.timing.delete_line,
                     });
                     cursor_line = buffer_line;
                     // After deletion, buffer_line stays the same
@@ -810,7 +1483,7 @@ impl AnimationEngine {
                     buffer_line += 1; // Move to next line after insertion
 
                     self.steps.push(AnimationStep::Pause {
-                        multiplier: INSERT_LINE_PAUSE,
+                        multiplier: self.timing.insert_line,
                     });
                 }
                 LineChangeType::Context => {
@@ -827,7 +1500,7 @@ impl AnimationEngine {
                             col,
                         });
                         self.steps.push(AnimationStep::Pause {
-                            multiplier: CURSOR_MOVE_PAUSE,
+                            multiplier: self.timing.cursor_move,
                         });
                     }
                     cursor_line = buffer_line;
@@ -839,11 +1512,84 @@ impl AnimationEngine {
         (cursor_line, buffer_line)
     }
 
+    /// Holds playback until `resume`, e.g. while the user scrolls the editor.
+    pub fn hold(&mut self) {
+        if self.held_at.is_none() {
+            self.held_at = Some(Instant::now());
+        }
+    }
+
+    /// Continues playback held by `hold`, with the rest of any running pause.
+    pub fn resume(&mut self) {
+        if let Some(held_at) = self.held_at.take() {
+            let held = held_at.elapsed();
+            if let Some(pause_until) = &mut self.pause_until {
+                *pause_until += held;
+            }
+            self.last_update += held;
+        }
+        self.terminal_scroll = 0;
+    }
+
+    /// Whether playback is held by the user.
+    pub fn is_held(&self) -> bool {
+        self.held_at.is_some()
+    }
+
+    /// Holds playback and scrolls the editor buffer by `delta` lines.
+    pub fn scroll_buffer(&mut self, delta: isize) {
+        self.hold();
+        let max_offset = self
+            .buffer
+            .lines
+            .len()
+            .saturating_sub(self.viewport_height)
+            .max(self.buffer.scroll_offset);
+        self.buffer.scroll_offset = self
+            .buffer
+            .scroll_offset
+            .saturating_add_signed(delta)
+            .min(max_offset);
+    }
+
+    /// Holds playback and scrolls the terminal history by `delta` lines, negative going back,
+    /// keeping a pane of `height` lines filled.
+    pub fn scroll_terminal(&mut self, delta: isize, height: usize) {
+        self.hold();
+        self.terminal_scroll = self
+            .terminal_scroll
+            .saturating_add_signed(-delta)
+            .min(self.terminal_lines.len().saturating_sub(height));
+    }
+
+    /// Continues playback from the moment a file of the current commit is opened.
+    /// Returns false if the file is not played (yet), e.g. during the commit's intro.
+    pub fn jump_to_file(&mut self, file_index: usize) -> bool {
+        // Until the intro ends, the displayed files belong to the previous commit
+        if self.pending_metadata.is_some() {
+            return false;
+        }
+        let Some(step) = self.steps.iter().position(|step| {
+            matches!(step, AnimationStep::SwitchFile { file_index: index, .. } if *index == file_index)
+        }) else {
+            return false;
+        };
+
+        self.current_step = step;
+        self.state = AnimationState::Playing;
+        self.pause_until = None;
+        self.held_at = None;
+        self.next_step_delay = 0;
+        self.dialog_title = None;
+        self.dialog_typing_text.clear();
+        true
+    }
+
     /// Updates animation state and returns true if display needs refresh.
     pub fn tick(&mut self) -> bool {
         self.update_cursor_blink();
 
-        if self.is_paused() {
+        if self.is_held() || self.is_paused() {
             return true;
         }
 
@@ -857,6 +1603,9 @@ impl AnimationEngine {
         }
 
         let executed = self.execute_batch_steps(now);
+        if !self.buffer.pending_edits.is_empty() {
+            self.refresh_highlights();
+        }
 
         if self.current_step >= self.steps.len() {
             self.state = AnimationState::Finished;
@@ -865,6 +1614,17 @@ impl AnimationEngine {
         executed
     }
 
+    /// Re-highlights the buffer, re-parsing and re-querying only what the pending edits touched.
+    fn refresh_highlights(&mut self) {
+        let text = self.buffer.text();
+        let mut highlighter = self.highlighter.borrow_mut();
+        for edit in self.buffer.pending_edits.drain(..) {
+            highlighter.edit(&edit);
+        }
+        self.buffer.cached_highlights = highlighter.highlight(&text);
+        self.buffer.line_offsets = Self::calculate_line_offsets(&text);
+    }
+
     fn update_cursor_blink(&mut self) {
         if self.cursor_blink_timer.elapsed() >= Duration::from_millis(500) {
             self.cursor_visible = !self.cursor_visible;
@@ -929,12 +1689,12 @@ impl AnimationEngine {
             AnimationStep::InsertChar { .. } | AnimationStep::TerminalTypeChar { .. } => {
                 // Add 70-130% variation to typing speed
                 let variation = rng.random_range(0.7..=1.3);
-                ((self.speed_ms as f64) * variation) as u64
+                (self.scaled_speed(self.speed_ms) * variation) as u64
             }
             AnimationStep::DialogTypeChar { .. } => {
                 // Dialog typing is slower (2x speed with variation)
                 let variation = rng.random_range(0.7..=1.3);
-                ((self.speed_ms as f64) * 2.0 * variation) as u64
+                (self.scaled_speed(self.speed_ms) * 2.0 * variation) as u64
             }
             AnimationStep::Pause { .. } => {
                 // Pause timing is driven by `pause_until`; don't add extra delay
@@ -942,7 +1702,7 @@ impl AnimationEngine {
             }
             _ => {
                 // Other steps use base speed
-                self.speed_ms
+                self.scaled_speed(self.speed_ms) as u64
             }
         };
 
@@ -959,9 +1719,6 @@ impl AnimationEngine {
                 self.buffer.insert_line(line, content);
                 self.buffer.cursor_line = line;
                 self.buffer.cursor_col = content_len;
-
-                // Track line offset for old_highlights mapping
-                self.line_offset += 1;
             }
             AnimationStep::DeleteLine { line } => {
                 self.active_pane = ActivePane::Editor;
@@ -974,9 +1731,6 @@ impl AnimationEngine {
                     .get(line)
                     .map(|l| l.chars().take_while(|c| c.is_whitespace()).count())
                     .unwrap_or(0);
-
-                // Track line offset for old_highlights mapping
-                self.line_offset -= 1;
             }
             AnimationStep::MoveCursor { line, col } => {
                 self.active_pane = ActivePane::Editor;
@@ -984,7 +1738,7 @@ impl AnimationEngine {
                 self.buffer.cursor_col = col;
             }
             AnimationStep::Pause { multiplier } => {
-                let duration_ms = (self.speed_ms as f64 * multiplier) as u64;
+                let duration_ms = (self.scaled_speed(self.speed_ms) * multiplier) as u64;
                 self.pause_until = Some(Instant::now() + Duration::from_millis(duration_ms));
             }
             AnimationStep::OpenFileDialogStart => {
@@ -1007,57 +1761,92 @@ impl AnimationEngine {
                 // Switch to new file
                 self.current_file_index = file_index;
                 self.current_file_path = Some(path.clone());
+                self.preview = None;
+                self.split = None;
                 self.buffer = EditorBuffer::from_content(&old_content);
+                if let Some(change) = self
+                    .current_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.changes.get(file_index))
+                {
+                    self.buffer.mark_pending_hunks(&change.hunks);
+                }
 
                 // Update typing speed based on file-specific rules
                 self.speed_ms = self.get_speed_for_file(&path);
 
                 // Update syntax highlighter for new file
                 // This will clear language settings if not supported
-                self.highlighter.borrow_mut().set_language_from_path(&path);
-
-                // Pre-calculate highlights for both old and new content
-                self.buffer.old_highlights = self.highlighter.borrow_mut().highlight(&old_content);
-                self.buffer.new_highlights = self.highlighter.borrow_mut().highlight(&new_content);
-
-                // Store content lines for byte offset calculation
-                self.buffer.old_content_lines = if old_content.is_empty() {
-                    vec![String::new()]
+                // Deleted files have no new content, so sniff the old one instead
+                let sniffed = if new_content.is_empty() {
+                    &old_content
                 } else {
-                    old_content.lines().map(|s| s.to_string()).collect()
+                    &new_content
                 };
-                self.buffer.new_content_lines = if new_content.is_empty() {
-                    vec![String::new()]
-                } else {
-                    new_content.lines().map(|s| s.to_string()).collect()
-                };
-
-                // Pre-calculate line byte offsets (handles CRLF correctly)
-                self.buffer.old_content_line_offsets = Self::calculate_line_offsets(&old_content);
-                self.buffer.new_content_line_offsets = Self::calculate_line_offsets(&new_content);
+                self.highlighter
+                    .borrow_mut()
+                    .set_language_for_file(&path, sniffed);
 
-                // Initialize cached_highlights with old_highlights
-                self.buffer.cached_highlights = self.buffer.old_highlights.clone();
-
-                // Reset line offset
-                self.line_offset = 0;
+                // Parse the whole buffer once; later edits re-parse incrementally
+                self.refresh_highlights();
+            }
+            AnimationStep::SelectAll => {
+                self.active_pane = ActivePane::Editor;
+                let last_line = self.buffer.lines.len().saturating_sub(1);
+                self.buffer.selection = Some((0, last_line));
+                self.buffer.cursor_line = last_line;
+                self.buffer.cursor_col = self.buffer.lines[last_line].chars().count();
+            }
+            AnimationStep::ShowPreview { file_index } => {
+                self.active_pane = ActivePane::Editor;
+                self.preview = self
+                    .current_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.changes.get(file_index))
+                    .and_then(BinaryPreview::from_change);
+            }
+            AnimationStep::ShowSplit { file_index } => {
+                self.active_pane = ActivePane::Editor;
+                let mut highlighter = self.highlighter.borrow_mut();
+                self.split = self
+                    .current_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.changes.get(file_index))
+                    .map(|change| SplitView::new(change, &mut highlighter));
+            }
+            AnimationStep::RevealSplitRow => {
+                if let Some(split) = &mut self.split {
+                    split.reveal_row();
+                }
+            }
+            AnimationStep::DeleteSelection => {
+                self.active_pane = ActivePane::Editor;
+                self.buffer.delete_selection();
+                self.buffer.cursor_line = 0;
+                self.buffer.cursor_col = 0;
             }
             AnimationStep::TerminalPrompt => {
                 self.active_pane = ActivePane::Terminal;
                 // Start a new command line with prompt
-                self.terminal_lines.push("~ ".to_string());
+                self.push_terminal_line(TerminalLineKind::Command, "~ ".to_string());
             }
             AnimationStep::TerminalTypeChar { ch } => {
                 self.active_pane = ActivePane::Terminal;
                 // Add character to the last terminal line
                 if let Some(last_line) = self.terminal_lines.last_mut() {
-                    last_line.push(ch);
+                    last_line.text.push(ch);
                 }
             }
             AnimationStep::TerminalOutput { text } => {
                 self.active_pane = ActivePane::Terminal;
                 // Add output line
-                self.terminal_lines.push(text);
+                self.push_terminal_line(TerminalLineKind::Output, text);
+            }
+            AnimationStep::TerminalDivider { label } => {
+                // The first commit needs no separation
+                if !self.terminal_lines.is_empty() {
+                    self.push_terminal_line(TerminalLineKind::Divider, label);
+                }
             }
             AnimationStep::ResetState => {
                 // Apply pending metadata and reset UI state after time-travel animation
@@ -1067,6 +1856,8 @@ impl AnimationEngine {
                 self.current_file_index = 0;
                 // Keep terminal_lines to preserve time-travel command and output
                 self.buffer = EditorBuffer::new();
+                self.preview = None;
+                self.split = None;
                 self.current_file_path = None;
                 self.active_pane = ActivePane::Terminal;
             }
@@ -1076,6 +1867,15 @@ impl AnimationEngine {
         self.update_scroll();
     }
 
+    // Add a terminal line, dropping the oldest ones beyond the scrollback
+    fn push_terminal_line(&mut self, kind: TerminalLineKind, text: String) {
+        self.terminal_lines.push(TerminalLine { kind, text });
+        if self.terminal_lines.len() > self.scrollback {
+            let excess = self.terminal_lines.len() - self.scrollback;
+            self.terminal_lines.drain(..excess);
+        }
+    }
+
     fn calculate_line_display_height(&self, line: &str) -> usize {
         if self.content_width == 0 {
             return 1;
@@ -1143,8 +1943,366 @@ impl AnimationEngine {
         self.buffer.scroll_offset = logical_offset;
     }
 
+    /// Fraction of the current commit's steps that have been played, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        if self.steps.is_empty() {
+            return 0.0;
+        }
+        self.current_step.min(self.steps.len()) as f64 / self.steps.len() as f64
+    }
+
     /// Returns true if the animation has completed.
     pub fn is_finished(&self) -> bool {
         self.state == AnimationState::Finished
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::TestRepo;
+    use crate::git::GitRepository;
+    use crate::syntax::Highlighter;
+
+    // Steps of the loaded commit as typed commands, opened files and editor actions,
+    // with consecutive edits merged into one entry
+    fn outline(engine: &AnimationEngine) -> Vec<String> {
+        let mut outline: Vec<String> = Vec::new();
+        for step in &engine.steps {
+            let entry = match step {
+                AnimationStep::TerminalPrompt => "$ ".to_string(),
+                AnimationStep::OpenFileDialogStart => "open ".to_string(),
+                AnimationStep::TerminalTypeChar { ch } | AnimationStep::DialogTypeChar { ch } => {
+                    outline.last_mut().unwrap().push(*ch);
+                    continue;
+                }
+                AnimationStep::SwitchFile { path, .. } => format!("switch {}", path),
+                AnimationStep::InsertChar { .. }
+                | AnimationStep::InsertLine { .. }
+                | AnimationStep::DeleteLine { .. } => "edit".to_string(),
+                AnimationStep::SelectAll => "select all".to_string(),
+                AnimationStep::DeleteSelection => "delete selection".to_string(),
+                _ => continue,
+            };
+            if !(entry == "edit" && outline.last().is_some_and(|last| last == "edit")) {
+                outline.push(entry);
+            }
+        }
+        outline
+    }
+
+    fn load_head(test_repo: &TestRepo, animate_deletions: bool) -> Vec<String> {
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut engine = AnimationEngine::new(10);
+        engine.set_narrative(Narrative::default());
+        engine.set_animate_deletions(animate_deletions);
+        engine.load_commit(&metadata, &[]);
+        outline(&engine)
+    }
+
+    #[test]
+    fn test_renamed_file_with_edits_is_moved_then_edited() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("old.txt", "one\ntwo\nthree\nfour\nfive\n", "Add");
+        test_repo.commit_changes(
+            &[("new.txt", "one\ntwo\n3\nfour\nfive\n")],
+            &["old.txt"],
+            "Rename and edit",
+        );
+
+        assert_eq!(
+            load_head(&test_repo, false),
+            vec![
+                "$ mv old.txt new.txt",
+                "open new.txt",
+                "switch new.txt",
+                "edit",
+                "$ git add new.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deleted_file_is_only_removed_by_default() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("a.txt", "a\nb\n", "Add");
+        test_repo.commit_changes(&[], &["a.txt"], "Delete");
+
+        assert_eq!(
+            load_head(&test_repo, false),
+            vec!["switch a.txt", "$ rm a.txt", "$ git add a.txt"]
+        );
+    }
+
+    #[test]
+    fn test_deleted_file_content_is_selected_and_deleted() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("a.txt", "a\nb\n", "Add");
+        test_repo.commit_changes(&[], &["a.txt"], "Delete");
+
+        assert_eq!(
+            load_head(&test_repo, true),
+            vec![
+                "open a.txt",
+                "switch a.txt",
+                "select all",
+                "delete selection",
+                "$ rm a.txt",
+                "$ git add a.txt",
+            ]
+        );
+    }
+
+    // Every node of a tree with its kind and position
+    fn nodes(tree: &tree_sitter::Tree) -> Vec<(String, usize, usize, Point, Point)> {
+        let mut nodes = Vec::new();
+        let mut cursor = tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            nodes.push((
+                node.kind().to_string(),
+                node.start_byte(),
+                node.end_byte(),
+                node.start_position(),
+                node.end_position(),
+            ));
+            if cursor.goto_first_child() || cursor.goto_next_sibling() {
+                continue;
+            }
+            while cursor.goto_parent() {
+                if cursor.goto_next_sibling() {
+                    continue 'walk;
+                }
+            }
+            return nodes;
+        }
+    }
+
+    fn highlighted(path: &str, content: &str) -> (EditorBuffer, Highlighter) {
+        let buffer = EditorBuffer::from_content(content);
+        let mut highlighter = Highlighter::new();
+        assert!(highlighter.set_language_for_file(path, content));
+        highlighter.highlight(&buffer.text());
+        (buffer, highlighter)
+    }
+
+    // Applies the buffer's edits like a frame would and compares the result with a fresh parse
+    fn assert_matches_fresh_parse(
+        path: &str,
+        buffer: &mut EditorBuffer,
+        highlighter: &mut Highlighter,
+    ) {
+        for edit in buffer.pending_edits.drain(..) {
+            highlighter.edit(&edit);
+        }
+        let text = buffer.text();
+        let spans = highlighter.highlight(&text);
+
+        let mut fresh = Highlighter::new();
+        fresh.set_language_for_file(path, &text);
+        let fresh_spans = fresh.highlight(&text);
+
+        assert_eq!(
+            nodes(highlighter.tree().unwrap()),
+            nodes(fresh.tree().unwrap()),
+            "edited tree differs for {:?}",
+            text
+        );
+        assert_eq!(spans, fresh_spans, "highlights differ for {:?}", text);
+    }
+
+    #[test]
+    fn test_typed_characters_update_tree_like_a_fresh_parse() {
+        let (mut buffer, mut highlighter) =
+            highlighted("main.rs", "fn main() {\n    let x = 1;\n}\n");
+
+        for (col, ch) in "let s = \"héllo 日本\";".chars().enumerate() {
+            buffer.insert_char(1, 4 + col, ch);
+        }
+        assert_matches_fresh_parse("main.rs", &mut buffer, &mut highlighter);
+
+        // One frame may apply several edits before highlighting
+        buffer.insert_char(0, 3, 'é');
+        buffer.insert_char(2, 0, '/');
+        buffer.insert_char(2, 1, '/');
+        assert_matches_fresh_parse("main.rs", &mut buffer, &mut highlighter);
+    }
+
+    #[test]
+    fn test_inserted_and_padded_lines_update_tree_like_a_fresh_parse() {
+        let (mut buffer, mut highlighter) = highlighted("lib.rs", "fn a() {}\n");
+
+        buffer.insert_line(0, "/// Ünïcode docs".to_string());
+        buffer.insert_line(2, "fn b() -> &'static str { \"ß\" }".to_string());
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+
+        // Typing past the end pads the buffer with empty lines first
+        buffer.insert_char(5, 0, '}');
+        buffer.insert_line(7, "const C: u8 = 1;".to_string());
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+    }
+
+    #[test]
+    fn test_deleted_lines_update_tree_like_a_fresh_parse() {
+        let (mut buffer, mut highlighter) = highlighted(
+            "lib.rs",
+            "fn a() {}\nfn ü() {}\n/* 日本\nstill */\nfn c() {}\nfn d() {}\n",
+        );
+
+        buffer.delete_line(1);
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+
+        // The last line goes together with the newline before it
+        buffer.delete_line(buffer.lines.len() - 1);
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+
+        buffer.selection = Some((1, buffer.lines.len() - 1));
+        buffer.delete_selection();
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+
+        buffer.delete_line(0);
+        assert_eq!(buffer.lines, vec![String::new()]);
+        assert_matches_fresh_parse("lib.rs", &mut buffer, &mut highlighter);
+    }
+
+    #[test]
+    fn test_edits_in_injections_update_highlights_like_a_fresh_parse() {
+        let (mut buffer, mut highlighter) = highlighted(
+            "README.md",
+            "# Title\n\n```rust\nfn main() {}\n```\n\nSome *text*.\n",
+        );
+
+        for (col, ch) in "let é = 1; ".chars().enumerate() {
+            buffer.insert_char(3, 11 + col, ch);
+        }
+        assert_matches_fresh_parse("README.md", &mut buffer, &mut highlighter);
+
+        buffer.insert_line(6, "More **bold** `code`".to_string());
+        buffer.delete_line(3);
+        assert_matches_fresh_parse("README.md", &mut buffer, &mut highlighter);
+    }
+
+    // Executes steps without waiting, refreshing highlights like each frame would
+    fn run_until(engine: &mut AnimationEngine, end: usize) {
+        while engine.current_step < end {
+            let step = engine.steps[engine.current_step].clone();
+            engine.execute_step(step);
+            engine.current_step += 1;
+            if !engine.buffer.pending_edits.is_empty() {
+                engine.refresh_highlights();
+            }
+        }
+    }
+
+    #[test]
+    fn test_jump_back_to_earlier_file_replays_it_from_its_old_content() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_changes(
+            &[("a.rs", "fn a() {}\n"), ("b.rs", "fn b() {}\n")],
+            &[],
+            "Add",
+        );
+        test_repo.commit_changes(
+            &[
+                ("a.rs", "fn a() {\n    let x = \"é\";\n}\n"),
+                ("b.rs", "fn b() -> u8 {\n    1\n}\n"),
+            ],
+            &[],
+            "Edit",
+        );
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut engine = AnimationEngine::new(10);
+        engine.set_narrative(Narrative::default());
+        engine.load_commit(&metadata, &[]);
+        engine.pending_metadata = None;
+
+        let switches: Vec<(usize, usize)> = engine
+            .steps
+            .iter()
+            .enumerate()
+            .filter_map(|(step, s)| match s {
+                AnimationStep::SwitchFile { file_index, .. } => Some((step, *file_index)),
+                _ => None,
+            })
+            .collect();
+        let [(first_switch, first_file), (second_switch, _)] = switches[..] else {
+            panic!("expected two opened files, got {:?}", switches);
+        };
+
+        // Play the first file and remember how it ended, then play the rest
+        run_until(&mut engine, second_switch);
+        let lines = engine.buffer.lines.clone();
+        let highlights = engine.buffer.cached_highlights.clone();
+        let steps = engine.steps.len();
+        run_until(&mut engine, steps);
+        assert_ne!(engine.buffer.lines, lines);
+
+        assert!(engine.jump_to_file(first_file));
+        assert_eq!(engine.current_step, first_switch);
+        run_until(&mut engine, first_switch + 1);
+        let old_content = &metadata.changes[first_file].old_content;
+        let old_content = old_content.as_deref().unwrap_or_default();
+        assert_eq!(engine.current_file_index, first_file);
+        assert_eq!(
+            engine.buffer.lines,
+            EditorBuffer::from_content(old_content).lines
+        );
+
+        run_until(&mut engine, second_switch);
+        assert_eq!(engine.buffer.lines, lines);
+        assert_eq!(engine.buffer.cached_highlights, highlights);
+    }
+
+    fn fitted_engine(target: Duration) -> AnimationEngine {
+        let test_repo = TestRepo::new();
+        test_repo.commit_changes(&[("a.rs", "fn a() {}\n"), ("b.slow", "b\n")], &[], "Add");
+        test_repo.commit_changes(
+            &[
+                ("a.rs", "fn a() {\n    let x = 1;\n}\n"),
+                ("b.slow", "b\nslow line\n"),
+            ],
+            &[],
+            "Edit",
+        );
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut engine = AnimationEngine::new(10);
+        engine.set_speed_rules(vec![SpeedRule::parse("*.slow:1000").unwrap()]);
+        engine.set_narrative(Narrative::default());
+        engine.set_timing(Timing {
+            target_duration: Some(target),
+            ..Timing::default()
+        });
+        engine.load_commit(&metadata, &[]);
+        engine.fit_to_target();
+        engine
+    }
+
+    #[test]
+    fn test_commit_is_fitted_to_the_target_duration() {
+        let target = Duration::from_secs(60);
+        let engine = fitted_engine(target);
+        let played = engine.scaled_duration().as_secs_f64();
+        assert!((played - 60.0).abs() < 0.01, "played {}s", played);
+
+        // The slow file's speed is bounded while the others make up the difference
+        let max = engine.timing.max_fit_speed_ms;
+        assert_eq!(engine.scaled_speed(1000), max);
+        assert!(engine.scaled_speed(10) < max);
+        assert!(engine.scaled_speed(10) > engine.timing.min_fit_speed_ms);
+    }
+
+    #[test]
+    fn test_commit_fitted_to_a_short_target_plays_at_the_fastest_speed() {
+        let engine = fitted_engine(Duration::from_millis(1));
+        let min = engine.timing.min_fit_speed_ms;
+        assert_eq!(engine.scaled_speed(10), min);
+        assert_eq!(engine.scaled_speed(1000), min);
+        assert_eq!(
+            engine.scaled_duration().as_millis(),
+            engine.steps_duration_ms(|_| min) as u128
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use git2::{ObjectType, Oid};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Header line identifying the on-disk format (bump when the layout changes)
+const CACHE_HEADER: &str = "gitlogue-commit-index v1";
+
+/// Commit metadata needed to select playback candidates without loading the commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedCommit {
+    pub oid: Oid,
+    pub author_name: String,
+    pub author_email: String,
+    pub time: i64,
+    pub parent_count: usize,
+}
+
+impl IndexedCommit {
+    pub fn from_commit(commit: &git2::Commit) -> Self {
+        let author = commit.author();
+        Self {
+            oid: commit.id(),
+            author_name: author.name().unwrap_or("").to_string(),
+            author_email: author.email().unwrap_or("").to_string(),
+            time: author.when().seconds(),
+            parent_count: commit.parent_count(),
+        }
+    }
+}
+
+/// Persistent commit index stored under the user cache directory.
+///
+/// One file is kept per repository and ref, recording the tip commit the index
+/// was built from so that it can be extended incrementally when new commits appear.
+#[derive(Debug, Clone)]
+pub struct CommitIndexCache {
+    path: PathBuf,
+}
+
+impl CommitIndexCache {
+    /// Creates a cache backed by the given file.
+    pub fn at<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the cache for a repository and ref, or `None` if no cache directory is available.
+    pub fn for_repository(repo_path: &Path, ref_name: &str) -> Option<Self> {
+        let key = format!("{}\n{}", repo_path.display(), ref_name);
+        let file_name = Oid::hash_object(ObjectType::Blob, key.as_bytes()).ok()?;
+        let dir = Self::cache_dir().ok()?;
+        Some(Self::at(dir.join(format!("{}.idx", file_name))))
+    }
+
+    /// Directory holding all commit index files (`$XDG_CACHE_HOME/gitlogue/commits` or `~/.cache/gitlogue/commits`)
+    pub fn cache_dir() -> Result<PathBuf> {
+        let base = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+            _ => dirs::home_dir()
+                .context("Failed to determine home directory")?
+                .join(".cache"),
+        };
+        Ok(base.join("gitlogue").join("commits"))
+    }
+
+    /// Loads the cached tip and commits (newest first), or `None` if missing or unreadable.
+    pub fn load(&self) -> Option<(Oid, Vec<IndexedCommit>)> {
+        let contents = fs::read_to_string(&self.path).ok()?;
+        let mut lines = contents.lines();
+
+        if lines.next()? != CACHE_HEADER {
+            return None;
+        }
+        let tip = Oid::from_str(lines.next()?.strip_prefix("tip ")?).ok()?;
+
+        let mut commits = Vec::new();
+        for line in lines {
+            let mut fields = line.splitn(5, '\t');
+            let oid = Oid::from_str(fields.next()?).ok()?;
+            let parent_count = fields.next()?.parse().ok()?;
+            let time = fields.next()?.parse().ok()?;
+            let author_name = fields.next()?.to_string();
+            let author_email = fields.next()?.to_string();
+            commits.push(IndexedCommit {
+                oid,
+                author_name,
+                author_email,
+                time,
+                parent_count,
+            });
+        }
+
+        Some((tip, commits))
+    }
+
+    /// Writes the index atomically so that concurrent readers never see a partial file.
+    pub fn save(&self, tip: Oid, commits: &[IndexedCommit]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+
+        let mut contents = String::with_capacity(commits.len() * 96);
+        contents.push_str(CACHE_HEADER);
+        contents.push('\n');
+        contents.push_str(&format!("tip {}\n", tip));
+        for commit in commits {
+            contents.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                commit.oid,
+                commit.parent_count,
+                commit.time,
+                sanitize_field(&commit.author_name),
+                sanitize_field(&commit.author_email)
+            ));
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, contents)
+            .with_context(|| format!("Failed to write cache file: {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write cache file: {}", self.path.display()))
+    }
+}
+
+// Tabs and newlines would break the line-based format
+fn sanitize_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -19,6 +20,130 @@ pub struct Config {
     pub ignore_patterns: Vec<String>,
     #[serde(default)]
     pub speed_rules: Vec<String>,
+    #[serde(default = "default_commit_cache")]
+    pub commit_cache: bool,
+    #[serde(default = "default_weighting")]
+    pub weighting: String,
+    #[serde(default)]
+    pub weights: WeightsConfig,
+    #[serde(default = "default_max_blob_size")]
+    pub max_blob_size: usize,
+    #[serde(default = "default_max_change_lines")]
+    pub max_change_lines: usize,
+    #[serde(default)]
+    pub max_files_per_commit: Option<usize>,
+    #[serde(default)]
+    pub max_lines_per_commit: Option<usize>,
+    #[serde(default = "default_oversize_policy")]
+    pub oversize_policy: String,
+    #[serde(default)]
+    pub animate_deletions: bool,
+    #[serde(default = "default_view")]
+    pub view: String,
+    #[serde(default)]
+    pub highlight_typed_lines: bool,
+    #[serde(default)]
+    pub minimap: bool,
+    #[serde(default = "default_file_tree")]
+    pub file_tree: String,
+    #[serde(default)]
+    pub icons: bool,
+    #[serde(default)]
+    pub commit_graph: bool,
+    #[serde(default = "default_scrollback")]
+    pub scrollback: usize,
+    /// Built-in narrative name, user narrative name, or path to a template file
+    #[serde(default = "default_narrative")]
+    pub narrative: String,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// Extra mailmap file, taking priority over the repository's `.mailmap`
+    #[serde(default)]
+    pub mailmap: Option<String>,
+    /// Pause durations (`[timing]` table)
+    #[serde(default)]
+    pub timing: TimingConfig,
+    /// Glob pattern to language name (`[languages]` table)
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+    /// File name or extension to file tree icon (`[file_icons]` table)
+    #[serde(default)]
+    pub file_icons: BTreeMap<String, FileIconConfig>,
+}
+
+/// A file tree icon: a glyph, or a glyph with a color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FileIconConfig {
+    Glyph(String),
+    Styled {
+        icon: String,
+        #[serde(default)]
+        color: Option<String>,
+    },
+}
+
+/// A timing preset with individual pauses overridden
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimingConfig {
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Milliseconds between commits, instead of a multiple of the typing speed
+    #[serde(default)]
+    pub commit_delay_ms: Option<u64>,
+    /// Play every commit in about this long, e.g. "60s"
+    #[serde(default)]
+    pub target_duration: Option<String>,
+    /// Fastest typing speed, in milliseconds per character, of a commit fitted to the target
+    #[serde(default)]
+    pub min_fit_speed_ms: Option<f64>,
+    /// Slowest typing speed, in milliseconds per character, of a commit fitted to the target
+    #[serde(default)]
+    pub max_fit_speed_ms: Option<f64>,
+    /// Pause name to multiple of the typing speed
+    #[serde(flatten)]
+    pub pauses: BTreeMap<String, f64>,
+}
+
+/// Tunable parameters for each random selection strategy (`[weights.*]` tables)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeightsConfig {
+    #[serde(default)]
+    pub size: SizeWeightConfig,
+    #[serde(default)]
+    pub recency: RecencyWeightConfig,
+    #[serde(default)]
+    pub author_balance: AuthorBalanceWeightConfig,
+    #[serde(default)]
+    pub shuffle: ShuffleWeightConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeWeightConfig {
+    #[serde(default = "default_size_target_lines")]
+    pub target_lines: usize,
+    #[serde(default = "default_size_spread")]
+    pub spread: f64,
+    #[serde(default = "default_size_sample")]
+    pub sample: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecencyWeightConfig {
+    #[serde(default = "default_recency_half_life_days")]
+    pub half_life_days: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorBalanceWeightConfig {
+    #[serde(default = "default_author_balance_key")]
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShuffleWeightConfig {
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 fn default_theme() -> String {
@@ -33,6 +158,26 @@ fn default_background() -> bool {
     true
 }
 
+fn default_view() -> String {
+    "typing".to_string()
+}
+
+fn default_file_tree() -> String {
+    "changes".to_string()
+}
+
+fn default_scrollback() -> usize {
+    crate::animation::DEFAULT_SCROLLBACK
+}
+
+fn default_narrative() -> String {
+    "fun".to_string()
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d %H:%M:%S".to_string()
+}
+
 fn default_order() -> String {
     "random".to_string()
 }
@@ -45,6 +190,72 @@ fn default_ignore_patterns() -> Vec<String> {
     Vec::new()
 }
 
+fn default_commit_cache() -> bool {
+    true
+}
+
+fn default_max_blob_size() -> usize {
+    crate::git::DEFAULT_MAX_BLOB_SIZE
+}
+
+fn default_max_change_lines() -> usize {
+    crate::git::DEFAULT_MAX_CHANGE_LINES
+}
+
+fn default_oversize_policy() -> String {
+    "skip".to_string()
+}
+
+fn default_weighting() -> String {
+    "uniform".to_string()
+}
+
+fn default_size_target_lines() -> usize {
+    60
+}
+
+fn default_size_spread() -> f64 {
+    1.0
+}
+
+fn default_size_sample() -> usize {
+    12
+}
+
+fn default_recency_half_life_days() -> f64 {
+    180.0
+}
+
+fn default_author_balance_key() -> String {
+    "name".to_string()
+}
+
+impl Default for SizeWeightConfig {
+    fn default() -> Self {
+        Self {
+            target_lines: default_size_target_lines(),
+            spread: default_size_spread(),
+            sample: default_size_sample(),
+        }
+    }
+}
+
+impl Default for RecencyWeightConfig {
+    fn default() -> Self {
+        Self {
+            half_life_days: default_recency_half_life_days(),
+        }
+    }
+}
+
+impl Default for AuthorBalanceWeightConfig {
+    fn default() -> Self {
+        Self {
+            key: default_author_balance_key(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -55,6 +266,28 @@ impl Default for Config {
             loop_playback: default_loop(),
             ignore_patterns: default_ignore_patterns(),
             speed_rules: Vec::new(),
+            commit_cache: default_commit_cache(),
+            weighting: default_weighting(),
+            weights: WeightsConfig::default(),
+            max_blob_size: default_max_blob_size(),
+            max_change_lines: default_max_change_lines(),
+            max_files_per_commit: None,
+            max_lines_per_commit: None,
+            oversize_policy: default_oversize_policy(),
+            animate_deletions: false,
+            view: default_view(),
+            highlight_typed_lines: false,
+            minimap: false,
+            file_tree: default_file_tree(),
+            icons: false,
+            commit_graph: false,
+            scrollback: default_scrollback(),
+            narrative: default_narrative(),
+            date_format: default_date_format(),
+            mailmap: None,
+            timing: TimingConfig::default(),
+            languages: BTreeMap::new(),
+            file_icons: BTreeMap::new(),
         }
     }
 }
@@ -191,7 +424,34 @@ impl Config {
         Ok(config_dir.join("config.toml"))
     }
 
-    #[allow(dead_code)]
+    /// Directory for grammar libraries loaded at runtime
+    pub fn grammars_dir() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .context("Failed to determine home directory")?
+            .join(".config")
+            .join("gitlogue")
+            .join("grammars"))
+    }
+
+    /// Directory for user highlight and injection query overrides
+    pub fn queries_dir() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .context("Failed to determine home directory")?
+            .join(".config")
+            .join("gitlogue")
+            .join("queries"))
+    }
+
+    /// Directory for user narrative templates (`<name>.toml`)
+    pub fn narratives_dir() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .context("Failed to determine home directory")?
+            .join(".config")
+            .join("gitlogue")
+            .join("narratives"))
+    }
+
+    /// Directory for user theme files (`<name>.toml`)
     pub fn themes_dir() -> Result<PathBuf> {
         let config_dir = dirs::home_dir()
             .context("Failed to determine home directory")?
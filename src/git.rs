@@ -1,22 +1,77 @@
+use crate::cache::{CommitIndexCache, IndexedCommit};
+use crate::weighting::{CommitSelector, CommitWeighting};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use chrono_english::{parse_date_string, Dialect};
-use git2::{Commit as Git2Commit, Delta, DiffOptions, Oid, Repository};
+use git2::{
+    Commit as Git2Commit, Delta, DiffOptions, Mailmap, ObjectType, Oid, Repository, Signature,
+    Sort, TreeWalkMode, TreeWalkResult,
+};
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use rand::Rng;
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::OnceLock;
 
 // Thread-safe global pattern matcher for user-defined ignore patterns
 static USER_PATTERNS: OnceLock<GlobSet> = OnceLock::new();
 
-// Maximum blob size to read (500KB)
-const MAX_BLOB_SIZE: usize = 500 * 1024;
+// Default maximum blob size to read (500KB)
+pub const DEFAULT_MAX_BLOB_SIZE: usize = 500 * 1024;
 
-// Maximum number of changed lines per file to animate
+// Default maximum number of changed lines per file to animate
 // Files with more changes will be skipped to prevent performance issues
-const MAX_CHANGE_LINES: usize = 2000;
+pub const DEFAULT_MAX_CHANGE_LINES: usize = 2000;
+
+// Commits walked from the branch tips looking for the commit at the center of the graph
+const GRAPH_WALK_LIMIT: usize = 10_000;
+
+// Later tags checked for containing a commit without a tag, closest in time first
+const CONTAINING_TAG_LIMIT: usize = 32;
+
+/// What to do with commits that exceed the per-commit file or line limits
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OversizePolicy {
+    #[default]
+    Skip, // Never select the commit for playback
+    Trim, // Play only the most interesting files that fit the limits
+}
+
+/// Size guards applied when selecting and loading commits
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitLimits {
+    /// Files larger than this many bytes are not loaded
+    pub max_blob_size: usize,
+    /// Files with more changed lines than this are skipped
+    pub max_change_lines: usize,
+    pub max_files_per_commit: Option<usize>,
+    pub max_lines_per_commit: Option<usize>,
+    pub oversize_policy: OversizePolicy,
+}
+
+impl Default for CommitLimits {
+    fn default() -> Self {
+        Self {
+            max_blob_size: DEFAULT_MAX_BLOB_SIZE,
+            max_change_lines: DEFAULT_MAX_CHANGE_LINES,
+            max_files_per_commit: None,
+            max_lines_per_commit: None,
+            oversize_policy: OversizePolicy::default(),
+        }
+    }
+}
+
+impl CommitLimits {
+    fn exceeded_by(&self, files: usize, lines: usize) -> bool {
+        self.max_files_per_commit.is_some_and(|max| files > max)
+            || self.max_lines_per_commit.is_some_and(|max| lines > max)
+    }
+
+    // Whether a commit of this size is never played
+    fn skips(&self, files: usize, lines: usize) -> bool {
+        self.oversize_policy == OversizePolicy::Skip && self.exceeded_by(files, lines)
+    }
+}
 
 /// Specifies which working tree changes to show in diff mode
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -26,6 +81,19 @@ pub enum DiffMode {
     Unstaged, // Only unstaged changes (workdir vs index)
 }
 
+// Pair deleted and added files into renames, so that a moved file is played as a move
+// plus its edits. Without it the diff stays as it was.
+fn find_renames(diff: &mut git2::Diff) {
+    let _ = diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)));
+}
+
+// Patch of every delta in a diff, `None` where it can't be generated
+fn diff_patches<'a>(diff: &git2::Diff<'a>) -> Vec<Option<git2::Patch<'a>>> {
+    (0..diff.deltas().len())
+        .map(|i| git2::Patch::from_diff(diff, i).ok().flatten())
+        .collect()
+}
+
 // Files to exclude from diff animation (lock files and generated files)
 const EXCLUDED_FILES: &[&str] = &[
     // JavaScript/Node.js
@@ -130,16 +198,35 @@ pub fn should_exclude_file(path: &str) -> bool {
     false
 }
 
-// Check if a commit matches the author filter pattern (case-insensitive partial match)
-fn matches_author(commit: &Git2Commit, pattern: &str) -> bool {
-    let author = commit.author();
-    let name = author.name().unwrap_or("");
-    let email = author.email().unwrap_or("");
+// Check if an author matches the filter pattern (case-insensitive partial match)
+fn matches_author(name: &str, email: &str, pattern: &str) -> bool {
     let pattern_lower = pattern.to_lowercase();
 
     name.to_lowercase().contains(&pattern_lower) || email.to_lowercase().contains(&pattern_lower)
 }
 
+// Collect the `Co-authored-by:` trailers of a commit message
+fn parse_co_authors(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            let value = value.trim();
+            (key.eq_ignore_ascii_case("co-authored-by") && !value.is_empty())
+                .then(|| value.to_string())
+        })
+        .collect()
+}
+
+// Format an identity as `Name <email>`, or just the name without an email
+fn format_identity(name: &str, email: &str) -> String {
+    if email.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} <{}>", name, email)
+    }
+}
+
 // Parse a date string using chrono-english (supports Git-like formats)
 pub fn parse_date(input: &str) -> Result<DateTime<Utc>> {
     let now = Local::now();
@@ -149,13 +236,12 @@ pub fn parse_date(input: &str) -> Result<DateTime<Utc>> {
         .with_context(|| format!("Invalid date format: '{}'. Use formats like '2024-01-01', '1 week ago', 'yesterday'", input))
 }
 
-// Check if a commit date is within the specified date range
+// Check if a commit timestamp is within the specified date range
 fn matches_date_filter(
-    commit: &Git2Commit,
+    timestamp: i64,
     before: Option<&DateTime<Utc>>,
     after: Option<&DateTime<Utc>>,
 ) -> Result<bool> {
-    let timestamp = commit.author().when().seconds();
     let commit_date = DateTime::from_timestamp(timestamp, 0).context("Invalid commit timestamp")?;
 
     if let Some(before_date) = before {
@@ -175,14 +261,25 @@ fn matches_date_filter(
 
 pub struct GitRepository {
     repo: Repository,
-    commit_cache: RefCell<Option<Vec<Oid>>>,
+    commit_cache: RefCell<Option<Vec<IndexedCommit>>>,
     // Shared index for both cache-based playback (asc/desc) and range playback.
     // These modes are mutually exclusive based on CLI arguments.
     commit_index: RefCell<usize>,
-    commit_range: RefCell<Option<Vec<Oid>>>,
+    commit_range: RefCell<Option<Vec<IndexedCommit>>>,
     author_filter: Option<String>,
     before_filter: Option<DateTime<Utc>>,
     after_filter: Option<DateTime<Utc>>,
+    index_cache: Option<CommitIndexCache>,
+    selector: RefCell<CommitSelector>,
+    limits: CommitLimits,
+    // Animated files and changed lines of the commits measured so far
+    commit_sizes: RefCell<HashMap<Oid, (usize, usize)>>,
+    // Branch and tag tips, read on first use
+    ref_tips: OnceCell<RefTips>,
+    // Refs of the commits loaded so far
+    commit_refs: RefCell<HashMap<Oid, Vec<CommitRef>>>,
+    // Mailmaps normalizing author identities, the first match winning
+    mailmaps: Vec<Mailmap>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -233,20 +330,15 @@ pub enum LineChangeType {
 pub struct LineChange {
     pub change_type: LineChangeType,
     pub content: String,
-    #[allow(dead_code)]
     pub old_line_no: Option<usize>,
-    #[allow(dead_code)]
     pub new_line_no: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DiffHunk {
     pub old_start: usize,
-    #[allow(dead_code)]
     pub old_lines: usize,
-    #[allow(dead_code)]
     pub new_start: usize,
-    #[allow(dead_code)]
     pub new_lines: usize,
     pub lines: Vec<LineChange>,
 }
@@ -254,28 +346,106 @@ pub struct DiffHunk {
 #[derive(Debug, Clone)]
 pub struct FileChange {
     pub path: String,
-    #[allow(dead_code)]
     pub old_path: Option<String>,
     pub status: FileStatus,
-    #[allow(dead_code)]
     pub is_binary: bool,
+    /// Raw contents of both sides, only set for binary files
+    pub binary: Option<BinaryContent>,
     pub is_excluded: bool,
     pub exclusion_reason: Option<String>,
     pub old_content: Option<String>,
-    #[allow(dead_code)]
     pub new_content: Option<String>,
     pub hunks: Vec<DiffHunk>,
     #[allow(dead_code)]
     pub diff: String,
 }
 
+/// Size and raw bytes of one side of a binary file change
+#[derive(Debug, Clone)]
+pub struct BinaryBlob {
+    pub size: usize,
+    /// `None` when the file exceeds the maximum blob size
+    pub data: Option<Vec<u8>>,
+}
+
+/// Old and new sides of a binary file change (`None` when the file is added or deleted)
+#[derive(Debug, Clone, Default)]
+pub struct BinaryContent {
+    pub old: Option<BinaryBlob>,
+    pub new: Option<BinaryBlob>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    Branch,
+    Tag,
+}
+
+// Local branches and tags with the commits they point at, read once per session
+struct RefTips {
+    branches: Vec<(String, Oid)>,
+    /// Tags with the time of their commit, oldest first
+    tags: Vec<(String, Oid, i64)>,
+}
+
+/// A branch or tag pointing at a commit or at one of its descendants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRef {
+    pub name: String,
+    pub kind: RefKind,
+    /// Whether the ref points at the commit itself
+    pub exact: bool,
+}
+
+/// A commit of the history around the playing commit, or before it
+#[derive(Debug, Clone)]
+pub struct GraphCommit {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub summary: String,
+    /// Branches and tags pointing at the commit
+    pub refs: Vec<CommitRef>,
+}
+
+/// Commits around the playing commit, newest first
+#[derive(Debug, Clone, Default)]
+pub struct CommitGraph {
+    pub commits: Vec<GraphCommit>,
+    /// Index of the playing commit (HEAD for working tree diffs)
+    pub current: Option<usize>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CommitMetadata {
     pub hash: String,
     pub author: String,
+    pub author_email: String,
+    /// Committer as `Name <email>`, when different from the author
+    pub committer: Option<String>,
+    /// Identities from `Co-authored-by:` trailers
+    pub co_authors: Vec<String>,
     pub date: DateTime<Utc>,
+    /// Author's timezone as recorded in the commit
+    pub utc_offset: FixedOffset,
     pub message: String,
+    /// First parent's hash, or HEAD's for a working tree diff
+    pub parent: Option<String>,
+    /// Local branches containing the commit, and the tags at or closest after it
+    pub refs: Vec<CommitRef>,
     pub changes: Vec<FileChange>,
+    /// Paths left out because the commit exceeded the per-commit limits
+    pub omitted_files: Vec<String>,
+}
+
+impl FileChange {
+    /// Number of added and deleted lines across all hunks
+    pub fn changed_lines(&self) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| !matches!(line.change_type, LineChangeType::Context))
+            .count()
+    }
 }
 
 impl CommitMetadata {
@@ -303,6 +473,8 @@ impl CommitMetadata {
 impl GitRepository {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let repo = Repository::open(path).context("Failed to open Git repository")?;
+        // `.mailmap` and the `mailmap.file` / `mailmap.blob` settings
+        let mailmaps = repo.mailmap().into_iter().collect();
         Ok(Self {
             repo,
             commit_cache: RefCell::new(None),
@@ -311,6 +483,13 @@ impl GitRepository {
             author_filter: None,
             before_filter: None,
             after_filter: None,
+            index_cache: None,
+            selector: RefCell::new(CommitSelector::new(CommitWeighting::Uniform)),
+            limits: CommitLimits::default(),
+            commit_sizes: RefCell::new(HashMap::new()),
+            ref_tips: OnceCell::new(),
+            commit_refs: RefCell::new(HashMap::new()),
+            mailmaps,
         })
     }
 
@@ -322,21 +501,20 @@ impl GitRepository {
 
         let commit = obj.peel_to_commit().context("Object is not a commit")?;
 
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.extract_metadata_with_changes(&commit)
     }
 
     pub fn random_commit(&self) -> Result<CommitMetadata> {
         self.populate_cache()?;
 
-        let cache = self.commit_cache.borrow();
-        let candidates = cache.as_ref().unwrap();
-
-        let selected_oid = candidates
-            .get(rand::rng().random_range(0..candidates.len()))
-            .context("Failed to select random commit")?;
+        let mut cache = self.commit_cache.borrow_mut();
+        let candidates = cache.as_mut().unwrap();
+        if candidates.is_empty() {
+            anyhow::bail!("No non-merge commits found in repository");
+        }
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+        self.pick_within_limits(candidates)?
+            .context("No commits found within the commit size limits")
     }
 
     pub fn next_asc_commit(&self) -> Result<CommitMetadata> {
@@ -350,20 +528,24 @@ impl GitRepository {
             anyhow::bail!("No non-merge commits found in repository");
         }
 
-        if *index >= candidates.len() {
-            anyhow::bail!("All commits have been played");
-        }
+        loop {
+            if *index >= candidates.len() {
+                anyhow::bail!("All commits have been played");
+            }
 
-        // Asc order: oldest first (reverse of cache order)
-        let asc_index = candidates.len() - 1 - *index;
-        let selected_oid = candidates
-            .get(asc_index)
-            .context("Failed to select commit")?;
+            // Asc order: oldest first (reverse of cache order)
+            let asc_index = candidates.len() - 1 - *index;
+            let selected_oid = candidates
+                .get(asc_index)
+                .context("Failed to select commit")?
+                .oid;
 
-        *index += 1;
+            *index += 1;
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+            if let Some(metadata) = self.load_candidate(selected_oid)? {
+                return Ok(metadata);
+            }
+        }
     }
 
     pub fn next_desc_commit(&self) -> Result<CommitMetadata> {
@@ -377,23 +559,57 @@ impl GitRepository {
             anyhow::bail!("No non-merge commits found in repository");
         }
 
-        if *index >= candidates.len() {
-            anyhow::bail!("All commits have been played");
-        }
+        loop {
+            if *index >= candidates.len() {
+                anyhow::bail!("All commits have been played");
+            }
 
-        // Desc order: newest first (same as cache order)
-        let selected_oid = candidates.get(*index).context("Failed to select commit")?;
+            // Desc order: newest first (same as cache order)
+            let selected_oid = candidates
+                .get(*index)
+                .context("Failed to select commit")?
+                .oid;
 
-        *index += 1;
+            *index += 1;
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+            if let Some(metadata) = self.load_candidate(selected_oid)? {
+                return Ok(metadata);
+            }
+        }
     }
 
     pub fn reset_index(&self) {
         *self.commit_index.borrow_mut() = 0;
     }
 
+    /// Adds a mailmap file whose entries take priority over the repository's own mailmap
+    pub fn add_mailmap_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read mailmap file: {}", path.display()))?;
+        let mailmap = Mailmap::from_buffer(&content)
+            .with_context(|| format!("Failed to parse mailmap file: {}", path.display()))?;
+        self.mailmaps.insert(0, mailmap);
+        Ok(())
+    }
+
+    // Canonical name and email of an identity according to the mailmaps
+    fn resolve_identity(&self, name: &str, email: &str) -> (String, String) {
+        // Identities a signature cannot hold (e.g. an empty name) are kept as they are
+        if let Ok(signature) = Signature::new(name, email, &git2::Time::new(0, 0)) {
+            for mailmap in &self.mailmaps {
+                let Ok(resolved) = mailmap.resolve_signature(&signature) else {
+                    continue;
+                };
+                let resolved_name = resolved.name().unwrap_or(name);
+                let resolved_email = resolved.email().unwrap_or(email);
+                if resolved_name != name || resolved_email != email {
+                    return (resolved_name.to_string(), resolved_email.to_string());
+                }
+            }
+        }
+        (name.to_string(), email.to_string())
+    }
+
     pub fn set_author_filter(&mut self, author: Option<String>) {
         self.author_filter = author;
     }
@@ -406,6 +622,25 @@ impl GitRepository {
         self.after_filter = after;
     }
 
+    pub fn set_weighting(&mut self, weighting: CommitWeighting) {
+        self.selector = RefCell::new(CommitSelector::new(weighting));
+    }
+
+    pub fn set_limits(&mut self, limits: CommitLimits) {
+        self.limits = limits;
+    }
+
+    /// Persist the commit index for HEAD under the user cache directory between runs
+    pub fn enable_index_cache(&mut self) {
+        let ref_name = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.name().map(String::from))
+            .unwrap_or_else(|| "HEAD".to_string());
+        self.index_cache = CommitIndexCache::for_repository(self.repo.path(), &ref_name);
+    }
+
     pub fn set_commit_range(&self, range: &str) -> Result<()> {
         let commits = self.parse_commit_range(range)?;
         *self.commit_range.borrow_mut() = Some(commits);
@@ -422,15 +657,18 @@ impl GitRepository {
             anyhow::bail!("No commits in range");
         }
 
-        if *index >= commits.len() {
-            anyhow::bail!("All commits in range have been played");
-        }
+        loop {
+            if *index >= commits.len() {
+                anyhow::bail!("All commits in range have been played");
+            }
 
-        let selected_oid = commits.get(*index).context("Failed to select commit")?;
-        *index += 1;
+            let selected_oid = commits.get(*index).context("Failed to select commit")?.oid;
+            *index += 1;
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+            if let Some(metadata) = self.load_candidate(selected_oid)? {
+                return Ok(metadata);
+            }
+        }
     }
 
     pub fn next_range_commit_desc(&self) -> Result<CommitMetadata> {
@@ -442,33 +680,128 @@ impl GitRepository {
             anyhow::bail!("No commits in range");
         }
 
-        if *index >= commits.len() {
-            anyhow::bail!("All commits in range have been played");
-        }
+        loop {
+            if *index >= commits.len() {
+                anyhow::bail!("All commits in range have been played");
+            }
 
-        // Desc order: newest first (reverse of asc)
-        let desc_index = commits.len() - 1 - *index;
-        let selected_oid = commits.get(desc_index).context("Failed to select commit")?;
-        *index += 1;
+            // Desc order: newest first (reverse of asc)
+            let desc_index = commits.len() - 1 - *index;
+            let selected_oid = commits
+                .get(desc_index)
+                .context("Failed to select commit")?
+                .oid;
+            *index += 1;
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+            if let Some(metadata) = self.load_candidate(selected_oid)? {
+                return Ok(metadata);
+            }
+        }
     }
 
     pub fn random_range_commit(&self) -> Result<CommitMetadata> {
-        let range = self.commit_range.borrow();
-        let commits = range.as_ref().context("Commit range not set")?;
+        let mut range = self.commit_range.borrow_mut();
+        let commits = range.as_mut().context("Commit range not set")?;
 
         if commits.is_empty() {
             anyhow::bail!("No commits in range");
         }
 
-        let selected_oid = commits
-            .get(rand::rng().random_range(0..commits.len()))
-            .context("Failed to select random commit")?;
+        self.pick_within_limits(commits)?
+            .context("No commits in range found within the commit size limits")
+    }
 
-        let commit = self.repo.find_commit(*selected_oid)?;
-        Self::extract_metadata_with_changes(&self.repo, &commit)
+    // Pick random candidates using the configured weighting strategy until one fits the
+    // commit limits, removing those that don't from the pool
+    fn pick_within_limits(
+        &self,
+        candidates: &mut Vec<IndexedCommit>,
+    ) -> Result<Option<CommitMetadata>> {
+        while !candidates.is_empty() {
+            let index = self
+                .selector
+                .borrow_mut()
+                .select(candidates, |oid| self.count_changed_lines(oid))
+                .context("Failed to select random commit")?;
+            if let Some(metadata) = self.load_candidate(candidates[index].oid)? {
+                return Ok(Some(metadata));
+            }
+            candidates.remove(index);
+            self.selector.borrow_mut().remove(index);
+        }
+        Ok(None)
+    }
+
+    // Load a commit for playback, or `None` when the skip policy rules it out
+    fn load_candidate(&self, oid: Oid) -> Result<Option<CommitMetadata>> {
+        let known_oversized = self
+            .commit_sizes
+            .borrow()
+            .get(&oid)
+            .is_some_and(|&(files, lines)| self.limits.skips(files, lines));
+        if known_oversized {
+            return Ok(None);
+        }
+
+        let commit = self.repo.find_commit(oid)?;
+        self.extract_metadata(&commit, true)
+    }
+
+    // Total added and deleted lines of a commit against its first parent
+    fn count_changed_lines(&self, oid: Oid) -> Option<usize> {
+        self.commit_size(oid).map(|(_, lines)| lines)
+    }
+
+    // Number of files and changed lines a commit would animate
+    fn commit_size(&self, oid: Oid) -> Option<(usize, usize)> {
+        if let Some(&size) = self.commit_sizes.borrow().get(&oid) {
+            return Some(size);
+        }
+
+        let commit = self.repo.find_commit(oid).ok()?;
+        let tree = commit.tree().ok()?;
+        let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+        let mut diff = self
+            .repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .ok()?;
+        find_renames(&mut diff);
+        let patches = diff_patches(&diff);
+        Some(self.record_size(oid, &diff, &patches))
+    }
+
+    // Measure and remember the size of a commit from its patches. Lock files and files
+    // over `max_change_lines` are not animated, so they don't count.
+    fn record_size(
+        &self,
+        oid: Oid,
+        diff: &git2::Diff,
+        patches: &[Option<git2::Patch>],
+    ) -> (usize, usize) {
+        let mut files = 0;
+        let mut lines = 0;
+        for (delta, patch) in diff.deltas().zip(patches) {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .and_then(|p| p.to_str())
+                .unwrap_or("");
+            if should_exclude_file(path) {
+                continue;
+            }
+            let changed = patch
+                .as_ref()
+                .and_then(|patch| patch.line_stats().ok())
+                .map_or(0, |(_, additions, deletions)| additions + deletions);
+            if changed > self.limits.max_change_lines {
+                continue;
+            }
+            files += 1;
+            lines += changed;
+        }
+        self.commit_sizes.borrow_mut().insert(oid, (files, lines));
+        (files, lines)
     }
 
     // Collect non-merge commits from a revwalk, applying author and date filters if set
@@ -476,26 +809,50 @@ impl GitRepository {
         &self,
         revwalk: git2::Revwalk,
         context: &str,
-    ) -> Result<Vec<Oid>> {
+    ) -> Result<Vec<IndexedCommit>> {
+        let index = self.index_revwalk(revwalk);
+        self.filter_candidates(&index, context)
+    }
+
+    // Read the metadata needed for candidate selection for every commit in a revwalk
+    fn index_revwalk(&self, revwalk: git2::Revwalk) -> Vec<IndexedCommit> {
+        revwalk
+            .filter_map(|oid| oid.ok())
+            .filter_map(|oid| self.repo.find_commit(oid).ok())
+            .map(|commit| IndexedCommit::from_commit(&commit))
+            .collect()
+    }
+
+    // Select non-merge commits from an index, applying author and date filters if set.
+    // Authors are normalized with the mailmaps, for both filtering and author-balanced selection.
+    fn filter_candidates(
+        &self,
+        index: &[IndexedCommit],
+        context: &str,
+    ) -> Result<Vec<IndexedCommit>> {
         let mut commits = Vec::new();
-        for oid in revwalk.filter_map(|oid| oid.ok()) {
-            if let Ok(commit) = self.repo.find_commit(oid) {
-                if commit.parent_count() <= 1 {
-                    if let Some(ref pattern) = self.author_filter {
-                        if !matches_author(&commit, pattern) {
-                            continue;
-                        }
-                    }
-                    if !matches_date_filter(
-                        &commit,
-                        self.before_filter.as_ref(),
-                        self.after_filter.as_ref(),
-                    )? {
-                        continue;
-                    }
-                    commits.push(oid);
+        for entry in index {
+            if entry.parent_count > 1 {
+                continue;
+            }
+            let mut entry = entry.clone();
+            if !self.mailmaps.is_empty() {
+                (entry.author_name, entry.author_email) =
+                    self.resolve_identity(&entry.author_name, &entry.author_email);
+            }
+            if let Some(ref pattern) = self.author_filter {
+                if !matches_author(&entry.author_name, &entry.author_email, pattern) {
+                    continue;
                 }
             }
+            if !matches_date_filter(
+                entry.time,
+                self.before_filter.as_ref(),
+                self.after_filter.as_ref(),
+            )? {
+                continue;
+            }
+            commits.push(entry);
         }
 
         if commits.is_empty() {
@@ -511,7 +868,7 @@ impl GitRepository {
         Ok(commits)
     }
 
-    fn parse_commit_range(&self, range: &str) -> Result<Vec<Oid>> {
+    fn parse_commit_range(&self, range: &str) -> Result<Vec<IndexedCommit>> {
         // Reject symmetric difference operator (not supported)
         if range.contains("...") {
             anyhow::bail!(
@@ -558,43 +915,243 @@ impl GitRepository {
     fn populate_cache(&self) -> Result<()> {
         let mut cache = self.commit_cache.borrow_mut();
         if cache.is_none() {
-            let mut revwalk = self.repo.revwalk()?;
-            revwalk.push_head()?;
-
-            let candidates = self.collect_commits_from_revwalk(revwalk, "in repository")?;
+            let index = self.load_head_index()?;
+            let candidates = self.filter_candidates(&index, "in repository")?;
             *cache = Some(candidates);
         }
         Ok(())
     }
 
-    fn extract_metadata_with_changes(
-        repo: &Repository,
+    // Index all commits reachable from HEAD (newest first).
+    // With the on-disk cache enabled, only commits added since the cached tip are read;
+    // the index is rebuilt from scratch if the cached tip is no longer an ancestor of HEAD.
+    fn load_head_index(&self) -> Result<Vec<IndexedCommit>> {
+        let head = self.repo.head()?.peel_to_commit()?.id();
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(head)?;
+
+        let Some(index_cache) = &self.index_cache else {
+            return Ok(self.index_revwalk(revwalk));
+        };
+
+        let cached = index_cache.load().filter(|(tip, _)| {
+            *tip == head || self.repo.graph_descendant_of(head, *tip).unwrap_or(false)
+        });
+
+        let index = match cached {
+            Some((tip, commits)) if tip == head => return Ok(commits),
+            Some((tip, mut commits)) => {
+                revwalk.hide(tip)?;
+                let mut index = self.index_revwalk(revwalk);
+                index.append(&mut commits);
+                index
+            }
+            None => self.index_revwalk(revwalk),
+        };
+
+        // A stale or unwritable cache only costs speed, never correctness
+        let _ = index_cache.save(head, &index);
+        Ok(index)
+    }
+
+    fn extract_metadata_with_changes(&self, commit: &Git2Commit) -> Result<CommitMetadata> {
+        self.extract_metadata(commit, false)?
+            .context("Commit exceeds the commit size limits")
+    }
+
+    // Metadata and changes of a commit. With `skip_oversized`, `None` is returned without
+    // loading any file when the commit exceeds the limits under the skip policy.
+    fn extract_metadata(
+        &self,
         commit: &Git2Commit,
-    ) -> Result<CommitMetadata> {
+        skip_oversized: bool,
+    ) -> Result<Option<CommitMetadata>> {
+        let Some(mut changes) = self.extract_changes(commit, skip_oversized)? else {
+            return Ok(None);
+        };
         let hash = commit.id().to_string();
         let author = commit.author();
-        let author_name = author.name().unwrap_or("Unknown").to_string();
+        let (author_name, author_email) = self.resolve_identity(
+            author.name().unwrap_or("Unknown"),
+            author.email().unwrap_or(""),
+        );
+        let committer = commit.committer();
+        let committer = self.resolve_identity(
+            committer.name().unwrap_or("Unknown"),
+            committer.email().unwrap_or(""),
+        );
+        let committer = format_identity(&committer.0, &committer.1);
+        let committer =
+            (committer != format_identity(&author_name, &author_email)).then_some(committer);
         let timestamp = author.when().seconds();
         let date = DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
+        let utc_offset = FixedOffset::east_opt(author.when().offset_minutes() * 60)
+            .unwrap_or(FixedOffset::east_opt(0).unwrap());
         let message = commit.message().unwrap_or("").trim().to_string();
+        let co_authors = parse_co_authors(&message);
+        let parent = commit.parent_id(0).ok().map(|id| id.to_string());
+        let refs = self.commit_refs(commit);
 
-        let changes = Self::extract_changes(repo, commit)?;
+        let omitted_files = if self.limits.oversize_policy == OversizePolicy::Trim {
+            self.trim_changes(&mut changes)
+        } else {
+            Vec::new()
+        };
 
-        Ok(CommitMetadata {
+        Ok(Some(CommitMetadata {
             hash,
             author: author_name,
+            author_email,
+            committer,
+            co_authors,
             date,
+            utc_offset,
             message,
+            parent,
+            refs,
             changes,
+            omitted_files,
+        }))
+    }
+
+    // Local branches at or containing the commit, tags pointing at it, and otherwise
+    // the earliest tag containing it (like `git describe --contains`)
+    fn commit_refs(&self, commit: &Git2Commit) -> Vec<CommitRef> {
+        let oid = commit.id();
+        if let Some(refs) = self.commit_refs.borrow().get(&oid) {
+            return refs.clone();
+        }
+
+        let tips = self.ref_tips();
+        let mut refs = Vec::new();
+        for (name, tip) in &tips.branches {
+            let exact = *tip == oid;
+            if exact || self.repo.graph_descendant_of(*tip, oid).unwrap_or(false) {
+                refs.push(CommitRef {
+                    name: name.clone(),
+                    kind: RefKind::Branch,
+                    exact,
+                });
+            }
+        }
+
+        for (name, _, _) in tips.tags.iter().filter(|(_, target, _)| *target == oid) {
+            refs.push(CommitRef {
+                name: name.clone(),
+                kind: RefKind::Tag,
+                exact: true,
+            });
+        }
+
+        if !refs.iter().any(|r| r.kind == RefKind::Tag) {
+            // Older tags can only contain the commit with skewed clocks
+            let time = commit.time().seconds();
+            let containing = tips
+                .tags
+                .iter()
+                .filter(|(_, _, tag_time)| *tag_time >= time)
+                .take(CONTAINING_TAG_LIMIT)
+                .find(|(_, target, _)| {
+                    self.repo.graph_descendant_of(*target, oid).unwrap_or(false)
+                });
+            if let Some((name, _, _)) = containing {
+                refs.push(CommitRef {
+                    name: name.clone(),
+                    kind: RefKind::Tag,
+                    exact: false,
+                });
+            }
+        }
+
+        self.commit_refs.borrow_mut().insert(oid, refs.clone());
+        refs
+    }
+
+    fn ref_tips(&self) -> &RefTips {
+        self.ref_tips.get_or_init(|| {
+            let mut branches = Vec::new();
+            if let Ok(local) = self.repo.branches(Some(git2::BranchType::Local)) {
+                for (branch, _) in local.flatten() {
+                    if let (Ok(Some(name)), Some(tip)) = (branch.name(), branch.get().target()) {
+                        branches.push((name.to_string(), tip));
+                    }
+                }
+            }
+
+            let mut tags = Vec::new();
+            let tag_names = self.repo.tag_names(None);
+            for name in tag_names.iter().flat_map(|names| names.iter().flatten()) {
+                let target = self
+                    .repo
+                    .revparse_single(&format!("refs/tags/{}", name))
+                    .and_then(|object| object.peel_to_commit());
+                if let Ok(target) = target {
+                    tags.push((name.to_string(), target.id(), target.time().seconds()));
+                }
+            }
+            tags.sort_by_key(|(_, _, time)| *time);
+
+            RefTips { branches, tags }
         })
     }
 
-    fn extract_changes(repo: &Repository, commit: &Git2Commit) -> Result<Vec<FileChange>> {
+    // Keep only the most interesting files that fit the per-commit limits.
+    // Returns the paths of the files that were removed.
+    fn trim_changes(&self, changes: &mut Vec<FileChange>) -> Vec<String> {
+        // Excluded files are not animated, so they don't count and are always listed
+        let animated: Vec<usize> = (0..changes.len())
+            .filter(|&i| !changes[i].is_excluded)
+            .collect();
+        let total_lines: usize = animated.iter().map(|&i| changes[i].changed_lines()).sum();
+        if !self.limits.exceeded_by(animated.len(), total_lines) {
+            return Vec::new();
+        }
+
+        // Text changes first, largest diffs first
+        let mut ranked = animated;
+        ranked.sort_by_key(|&i| {
+            let change = &changes[i];
+            (change.is_binary, std::cmp::Reverse(change.changed_lines()))
+        });
+
+        let max_files = self.limits.max_files_per_commit.unwrap_or(usize::MAX);
+        let max_lines = self.limits.max_lines_per_commit.unwrap_or(usize::MAX);
+        let mut keep: Vec<bool> = changes.iter().map(|change| change.is_excluded).collect();
+        let mut kept_files = 0;
+        let mut kept_lines = 0;
+        for i in ranked {
+            let lines = changes[i].changed_lines();
+            if kept_files < max_files && kept_lines + lines <= max_lines {
+                keep[i] = true;
+                kept_files += 1;
+                kept_lines += lines;
+            }
+        }
+
+        let mut omitted = Vec::new();
+        let mut index = 0;
+        changes.retain(|change| {
+            let kept = keep[index];
+            index += 1;
+            if !kept {
+                omitted.push(change.path.clone());
+            }
+            kept
+        });
+        omitted
+    }
+
+    fn extract_changes(
+        &self,
+        commit: &Git2Commit,
+        skip_oversized: bool,
+    ) -> Result<Option<Vec<FileChange>>> {
+        let repo = &self.repo;
         let commit_tree = commit.tree().context("Failed to get commit tree")?;
         let parent_tree = if commit.parent_count() > 0 {
             match commit.parent(0).and_then(|p| p.tree()) {
                 Ok(tree) => Some(tree),
-                Err(_) => return Ok(Vec::new()), // Skip if parent tree unavailable
+                Err(_) => return Ok(Some(Vec::new())), // Skip if parent tree unavailable
             }
         } else {
             None
@@ -603,21 +1160,26 @@ impl GitRepository {
         let mut diff_opts = DiffOptions::new();
         diff_opts.context_lines(3);
 
-        let diff = match repo.diff_tree_to_tree(
+        let mut diff = match repo.diff_tree_to_tree(
             parent_tree.as_ref(),
             Some(&commit_tree),
             Some(&mut diff_opts),
         ) {
             Ok(d) => d,
-            Err(_) => return Ok(Vec::new()), // Skip if diff fails
+            Err(_) => return Ok(Some(Vec::new())), // Skip if diff fails
         };
+        find_renames(&mut diff);
+
+        // Every patch is needed for the size, and then reused for the hunks
+        let mut patches = diff_patches(&diff);
+        let (files, lines) = self.record_size(commit.id(), &diff, &patches);
+        if skip_oversized && self.limits.skips(files, lines) {
+            return Ok(None);
+        }
 
         let mut changes = Vec::new();
 
-        for i in 0..diff.deltas().len() {
-            let Some(delta) = diff.get_delta(i) else {
-                continue;
-            };
+        for (delta, patch) in diff.deltas().zip(patches.iter_mut()) {
             let status = FileStatus::from(delta.status());
 
             let path = delta
@@ -647,7 +1209,7 @@ impl GitRepository {
                         .ok()
                         .and_then(|entry| repo.find_blob(entry.id()).ok())
                         .and_then(|blob| {
-                            if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+                            if !blob.is_binary() && blob.size() <= self.limits.max_blob_size {
                                 Some(String::from_utf8_lossy(blob.content()).to_string())
                             } else {
                                 None
@@ -666,7 +1228,7 @@ impl GitRepository {
                     .ok()
                     .and_then(|entry| repo.find_blob(entry.id()).ok())
                     .and_then(|blob| {
-                        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+                        if !blob.is_binary() && blob.size() <= self.limits.max_blob_size {
                             Some(String::from_utf8_lossy(blob.content()).to_string())
                         } else {
                             None
@@ -678,11 +1240,14 @@ impl GitRepository {
 
             let mut hunks = Vec::new();
             let mut diff_text = String::new();
+            let mut is_binary = is_binary;
 
-            if let Ok(Some(mut patch)) = git2::Patch::from_diff(&diff, i) {
+            if let Some(mut patch) = patch.take() {
                 if let Ok(patch_str) = patch.to_buf() {
                     diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
                 }
+                // Binary detection needs the file contents, which the patch has loaded
+                is_binary |= patch.delta().flags().is_binary();
 
                 if !is_binary {
                     for hunk_idx in 0..patch.num_hunks() {
@@ -750,7 +1315,7 @@ impl GitRepository {
             // Determine exclusion reason
             let (is_excluded, exclusion_reason) = if should_exclude_file(&path) {
                 (true, Some("lock/generated file".to_string()))
-            } else if total_changed_lines > MAX_CHANGE_LINES {
+            } else if total_changed_lines > self.limits.max_change_lines {
                 (
                     true,
                     Some(format!("too many changes ({} lines)", total_changed_lines)),
@@ -759,11 +1324,14 @@ impl GitRepository {
                 (false, None)
             };
 
+            let binary = is_binary.then(|| self.load_binary_content(&delta));
+
             changes.push(FileChange {
                 path,
                 old_path,
                 status,
                 is_binary,
+                binary,
                 is_excluded,
                 exclusion_reason,
                 old_content,
@@ -773,7 +1341,165 @@ impl GitRepository {
             });
         }
 
-        Ok(changes)
+        Ok(Some(changes))
+    }
+
+    /// Paths of every file in the tree of a commit, or of HEAD for a working tree diff
+    pub fn tree_paths(&self, metadata: &CommitMetadata) -> Result<Vec<String>> {
+        let commit = if metadata.hash == "working-tree" {
+            match self.repo.head() {
+                Ok(head) => head.peel_to_commit()?,
+                // Nothing committed yet
+                Err(_) => return Ok(Vec::new()),
+            }
+        } else {
+            self.repo
+                .find_commit(Oid::from_str(&metadata.hash)?)
+                .context("Commit not found")?
+        };
+        let tree = commit.tree().context("Failed to read commit tree")?;
+
+        let mut paths = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            // Submodules are listed like files
+            if matches!(entry.kind(), Some(ObjectType::Blob | ObjectType::Commit)) {
+                if let Some(name) = entry.name() {
+                    paths.push(format!("{}{}", root, name));
+                }
+            }
+            TreeWalkResult::Ok
+        })
+        .context("Failed to walk commit tree")?;
+        Ok(paths)
+    }
+
+    /// Commits of all local branches around a commit, at most `context` on each side of it.
+    pub fn commit_graph(&self, metadata: &CommitMetadata, context: usize) -> Result<CommitGraph> {
+        let Some(oid) = self.commit_oid(metadata)? else {
+            return Ok(CommitGraph::default());
+        };
+
+        // Time order streams commits, unlike a topological walk of the whole history.
+        // Starting from the tips only, a commit always comes after one of its children.
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(Sort::TIME)?;
+        revwalk.push_glob("refs/heads/*")?;
+        if self.repo.head_detached().unwrap_or(false) {
+            revwalk.push_head()?;
+        }
+
+        let mut oids = Vec::new();
+        let mut position = None;
+        for id in revwalk {
+            let id = id?;
+            if id == oid {
+                position = Some(oids.len());
+            }
+            oids.push(id);
+            match position {
+                Some(position) if oids.len() > position + context => break,
+                None if oids.len() >= GRAPH_WALK_LIMIT => break,
+                _ => {}
+            }
+        }
+
+        let start = match position {
+            Some(position) => position.saturating_sub(context),
+            None => {
+                // Too far behind the branch tips, or not on a branch: show only the commit's ancestors
+                let mut revwalk = self.repo.revwalk()?;
+                revwalk.set_sorting(Sort::TIME)?;
+                revwalk.push(oid)?;
+                oids = revwalk.take(context + 1).collect::<Result<_, _>>()?;
+                0
+            }
+        };
+
+        let refs = self.refs_by_commit();
+        let commits = oids[start..]
+            .iter()
+            .map(|id| self.graph_commit(*id, &refs))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Skewed commit times can put a parent before its child
+        let mut remaining = commits;
+        let mut commits = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let next = remaining
+                .iter()
+                .position(|commit| {
+                    !remaining
+                        .iter()
+                        .any(|other| other.parents.contains(&commit.hash))
+                })
+                .unwrap_or(0);
+            commits.push(remaining.remove(next));
+        }
+
+        let current = commits.iter().position(|c| c.hash == oid.to_string());
+        Ok(CommitGraph { commits, current })
+    }
+
+    /// The commit and its most recent ancestors, newest first, like `git log`.
+    pub fn recent_commits(
+        &self,
+        metadata: &CommitMetadata,
+        count: usize,
+    ) -> Result<Vec<GraphCommit>> {
+        let Some(oid) = self.commit_oid(metadata)? else {
+            return Ok(Vec::new());
+        };
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(Sort::TIME)?;
+        revwalk.push(oid)?;
+
+        let refs = self.refs_by_commit();
+        revwalk
+            .take(count)
+            .map(|id| self.graph_commit(id?, &refs))
+            .collect()
+    }
+
+    // The playing commit, or HEAD for a working tree diff (`None` before the first commit)
+    fn commit_oid(&self, metadata: &CommitMetadata) -> Result<Option<Oid>> {
+        if metadata.hash == "working-tree" {
+            Ok(self.repo.head().ok().and_then(|head| head.target()))
+        } else {
+            Ok(Some(Oid::from_str(&metadata.hash)?))
+        }
+    }
+
+    fn graph_commit(&self, id: Oid, refs: &HashMap<Oid, Vec<CommitRef>>) -> Result<GraphCommit> {
+        let commit = self.repo.find_commit(id)?;
+        Ok(GraphCommit {
+            hash: id.to_string(),
+            parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            refs: refs.get(&id).cloned().unwrap_or_default(),
+        })
+    }
+
+    // Local branches and tags by the commit they point at
+    fn refs_by_commit(&self) -> HashMap<Oid, Vec<CommitRef>> {
+        let tips = self.ref_tips();
+        let branches = tips
+            .branches
+            .iter()
+            .map(|(name, tip)| (name, *tip, RefKind::Branch));
+        let tags = tips
+            .tags
+            .iter()
+            .map(|(name, target, _)| (name, *target, RefKind::Tag));
+
+        let mut refs: HashMap<Oid, Vec<CommitRef>> = HashMap::new();
+        for (name, target, kind) in branches.chain(tags) {
+            refs.entry(target).or_default().push(CommitRef {
+                name: name.clone(),
+                kind,
+                exact: true,
+            });
+        }
+        refs
     }
 
     /// Get working tree diff as CommitMetadata for animation
@@ -781,22 +1507,49 @@ impl GitRepository {
     /// DiffMode::Staged - Only staged changes (index vs HEAD)
     /// DiffMode::Unstaged - Only unstaged changes (workdir vs index)
     pub fn get_working_tree_diff(&self, mode: DiffMode) -> Result<CommitMetadata> {
-        let changes = match mode {
+        let mut changes = match mode {
             DiffMode::Staged => self.extract_staged_changes()?,
             DiffMode::Unstaged => self.extract_unstaged_changes()?,
         };
+        // There is nothing else to play, so oversized changes are trimmed whatever the policy
+        let omitted_files = self.trim_changes(&mut changes);
 
         let message = match mode {
             DiffMode::Staged => "Staged changes",
             DiffMode::Unstaged => "Unstaged changes",
         };
 
+        // The checked out branch, which the changes would be committed to
+        let head = self.repo.head().ok();
+        let refs = head
+            .as_ref()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand())
+            .map(|name| {
+                vec![CommitRef {
+                    name: name.to_string(),
+                    kind: RefKind::Branch,
+                    exact: true,
+                }]
+            })
+            .unwrap_or_default();
+
         Ok(CommitMetadata {
             hash: "working-tree".to_string(),
             author: "Working Tree".to_string(),
+            author_email: String::new(),
+            committer: None,
+            co_authors: Vec::new(),
             date: Utc::now(),
+            utc_offset: *Local::now().offset(),
             message: message.to_string(),
+            parent: head
+                .as_ref()
+                .and_then(|head| head.target())
+                .map(|id| id.to_string()),
+            refs,
             changes,
+            omitted_files,
         })
     }
 
@@ -816,10 +1569,11 @@ impl GitRepository {
         let mut diff_opts = DiffOptions::new();
         diff_opts.context_lines(3);
 
-        let diff = self
+        let mut diff = self
             .repo
             .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_opts))
             .context("Failed to diff tree to index")?;
+        find_renames(&mut diff);
 
         self.extract_changes_from_diff(&diff, head_tree.as_ref(), None)
     }
@@ -912,9 +1666,9 @@ impl GitRepository {
                 None
             };
 
-            let is_binary = delta.new_file().is_binary() || delta.old_file().is_binary();
             let (old_content, new_content) = get_content(&delta);
-            let (hunks, diff_text) = self.extract_hunks_from_diff(diff, i, is_binary)?;
+            let (hunks, diff_text, is_binary) = self.extract_hunks_from_diff(diff, i)?;
+            let binary = is_binary.then(|| self.load_binary_content(&delta));
 
             // Calculate total changed lines
             let total_changed_lines: usize = hunks
@@ -925,7 +1679,7 @@ impl GitRepository {
 
             let (is_excluded, exclusion_reason) = if should_exclude_file(&path) {
                 (true, Some("lock/generated file".to_string()))
-            } else if total_changed_lines > MAX_CHANGE_LINES {
+            } else if total_changed_lines > self.limits.max_change_lines {
                 (
                     true,
                     Some(format!("too many changes ({} lines)", total_changed_lines)),
@@ -939,6 +1693,7 @@ impl GitRepository {
                 old_path,
                 status,
                 is_binary,
+                binary,
                 is_excluded,
                 exclusion_reason,
                 old_content,
@@ -960,27 +1715,30 @@ impl GitRepository {
         let path = path?;
         let entry = tree.get_path(path).ok()?;
         let blob = self.repo.find_blob(entry.id()).ok()?;
-        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+        if !blob.is_binary() && blob.size() <= self.limits.max_blob_size {
             Some(String::from_utf8_lossy(blob.content()).to_string())
         } else {
             None
         }
     }
 
-    /// Extract hunks from a diff at given delta index
+    /// Extract hunks from a diff at given delta index, along with whether the file is binary
     fn extract_hunks_from_diff(
         &self,
         diff: &git2::Diff,
         delta_idx: usize,
-        is_binary: bool,
-    ) -> Result<(Vec<DiffHunk>, String)> {
+    ) -> Result<(Vec<DiffHunk>, String, bool)> {
         let mut hunks = Vec::new();
         let mut diff_text = String::new();
+        let mut is_binary = diff
+            .get_delta(delta_idx)
+            .is_some_and(|delta| delta.new_file().is_binary() || delta.old_file().is_binary());
 
         if let Ok(Some(mut patch)) = git2::Patch::from_diff(diff, delta_idx) {
             if let Ok(patch_str) = patch.to_buf() {
                 diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
             }
+            is_binary |= patch.delta().flags().is_binary();
 
             if !is_binary {
                 for hunk_idx in 0..patch.num_hunks() {
@@ -1037,7 +1795,44 @@ impl GitRepository {
             }
         }
 
-        Ok((hunks, diff_text))
+        Ok((hunks, diff_text, is_binary))
+    }
+
+    /// Load both sides of a binary file change for previews
+    fn load_binary_content(&self, delta: &git2::DiffDelta) -> BinaryContent {
+        let (has_old, has_new) = match delta.status() {
+            Delta::Added | Delta::Untracked => (false, true),
+            Delta::Deleted => (true, false),
+            _ => (true, true),
+        };
+        BinaryContent {
+            old: has_old
+                .then(|| self.load_binary_blob(&delta.old_file()))
+                .flatten(),
+            new: has_new
+                .then(|| self.load_binary_blob(&delta.new_file()))
+                .flatten(),
+        }
+    }
+
+    fn load_binary_blob(&self, file: &git2::DiffFile) -> Option<BinaryBlob> {
+        if !file.id().is_zero() {
+            if let Ok(blob) = self.repo.find_blob(file.id()) {
+                let size = blob.size();
+                let data = (size <= self.limits.max_blob_size).then(|| blob.content().to_vec());
+                return Some(BinaryBlob { size, data });
+            }
+        }
+
+        // Working tree files are not stored as blobs yet
+        let full_path = self.repo.workdir()?.join(file.path()?);
+        let size = std::fs::metadata(&full_path).ok()?.len() as usize;
+        let data = if size <= self.limits.max_blob_size {
+            std::fs::read(&full_path).ok()
+        } else {
+            None
+        };
+        Some(BinaryBlob { size, data })
     }
 
     /// Get file content from the current index
@@ -1057,7 +1852,7 @@ impl GitRepository {
         let entry = index.get_path(path, 0)?;
         let blob = self.repo.find_blob(entry.id).ok()?;
 
-        if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+        if !blob.is_binary() && blob.size() <= self.limits.max_blob_size {
             Some(String::from_utf8_lossy(blob.content()).to_string())
         } else {
             None
@@ -1070,21 +1865,21 @@ impl GitRepository {
     /// - Path is not provided
     /// - Repository is bare (no working directory)
     /// - File cannot be read (missing, permissions, binary/non-UTF8)
-    /// - File size exceeds MAX_BLOB_SIZE (500KB)
+    /// - File size exceeds the configured maximum blob size (500KB by default)
     fn get_workdir_content(&self, path: Option<&std::path::Path>) -> Option<String> {
         let path = path?;
         let workdir = self.repo.workdir()?;
         let full_path = workdir.join(path);
 
         match std::fs::read_to_string(&full_path) {
-            Ok(content) if content.len() <= MAX_BLOB_SIZE => Some(content),
+            Ok(content) if content.len() <= self.limits.max_blob_size => Some(content),
             _ => None,
         }
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     #[test]
@@ -1214,8 +2009,8 @@ mod tests {
     }
 
     // RAII guard for temporary git repository - auto-cleans on drop
-    struct TestRepo {
-        path: std::path::PathBuf,
+    pub(crate) struct TestRepo {
+        pub(crate) path: std::path::PathBuf,
         repo: git2::Repository,
     }
 
@@ -1226,7 +2021,7 @@ mod tests {
     }
 
     impl TestRepo {
-        fn new() -> Self {
+        pub(crate) fn new() -> Self {
             use std::sync::atomic::{AtomicU64, Ordering};
             use std::time::{SystemTime, UNIX_EPOCH};
             static COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -1255,6 +2050,74 @@ mod tests {
 
             Self { path, repo }
         }
+
+        // Write a file and commit it on top of HEAD
+        pub(crate) fn commit_file(&self, name: &str, content: &str, message: &str) -> Oid {
+            self.commit_files(&[(name, content)], message)
+        }
+
+        // Write several files and commit them together on top of HEAD
+        pub(crate) fn commit_files(&self, files: &[(&str, &str)], message: &str) -> Oid {
+            self.commit_changes(files, &[], message)
+        }
+
+        // Write some files, delete others and commit it all on top of HEAD
+        pub(crate) fn commit_changes(
+            &self,
+            files: &[(&str, &str)],
+            removed: &[&str],
+            message: &str,
+        ) -> Oid {
+            self.commit_changes_at(files, removed, message, None)
+        }
+
+        // Commit a file with the given commit time (seconds since the epoch)
+        pub(crate) fn commit_file_at(
+            &self,
+            name: &str,
+            content: &str,
+            message: &str,
+            time: i64,
+        ) -> Oid {
+            self.commit_changes_at(&[(name, content)], &[], message, Some(time))
+        }
+
+        fn commit_changes_at(
+            &self,
+            files: &[(&str, &str)],
+            removed: &[&str],
+            message: &str,
+            time: Option<i64>,
+        ) -> Oid {
+            let mut index = self.repo.index().unwrap();
+            for (name, content) in files {
+                let path = self.path.join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, content).unwrap();
+                index.add_path(std::path::Path::new(name)).unwrap();
+            }
+            for name in removed {
+                std::fs::remove_file(self.path.join(name)).unwrap();
+                index.remove_path(std::path::Path::new(name)).unwrap();
+            }
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = self.repo.find_tree(tree_id).unwrap();
+            let mut sig = self.repo.signature().unwrap();
+            if let Some(time) = time {
+                let (name, email) = (sig.name().unwrap(), sig.email().unwrap());
+                sig = git2::Signature::new(name, email, &git2::Time::new(time, 0)).unwrap();
+            }
+            let parent = self
+                .repo
+                .head()
+                .ok()
+                .and_then(|head| head.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            self.repo
+                .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        }
     }
 
     #[test]
@@ -1460,4 +2323,506 @@ mod tests {
         let diff = now.signed_duration_since(result.date);
         assert!(diff.num_seconds() < 60);
     }
+
+    #[test]
+    fn test_index_cache_is_extended_with_new_commits() {
+        let test_repo = TestRepo::new();
+        let cache_path = test_repo.path.join("commit-index.idx");
+        let first = test_repo.commit_file("a.txt", "a\n", "First");
+        let second = test_repo.commit_file("a.txt", "b\n", "Second");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.index_cache = Some(CommitIndexCache::at(&cache_path));
+        assert_eq!(repo.next_desc_commit().unwrap().hash, second.to_string());
+
+        let (tip, commits) = CommitIndexCache::at(&cache_path).load().unwrap();
+        assert_eq!(tip, second);
+        assert_eq!(
+            commits.iter().map(|c| c.oid).collect::<Vec<_>>(),
+            vec![second, first]
+        );
+        assert_eq!(commits[0].author_name, "Test User");
+        assert_eq!(commits[0].author_email, "test@example.com");
+        assert_eq!(commits[1].parent_count, 0);
+
+        let third = test_repo.commit_file("a.txt", "c\n", "Third");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.index_cache = Some(CommitIndexCache::at(&cache_path));
+        assert_eq!(repo.next_desc_commit().unwrap().hash, third.to_string());
+        assert_eq!(repo.next_desc_commit().unwrap().hash, second.to_string());
+
+        let (tip, commits) = CommitIndexCache::at(&cache_path).load().unwrap();
+        assert_eq!(tip, third);
+        assert_eq!(commits.len(), 3);
+    }
+
+    #[test]
+    fn test_index_cache_applies_filters() {
+        let test_repo = TestRepo::new();
+        let cache_path = test_repo.path.join("commit-index.idx");
+        test_repo.commit_file("a.txt", "a\n", "First");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.index_cache = Some(CommitIndexCache::at(&cache_path));
+        repo.next_asc_commit().unwrap();
+
+        // A second run reads from the cache and still honors the author filter
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.index_cache = Some(CommitIndexCache::at(&cache_path));
+        repo.set_author_filter(Some("someone-else".to_string()));
+        let err = repo.next_asc_commit().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No commits found matching the filters"));
+    }
+
+    #[test]
+    fn test_shuffle_weighting_plays_every_commit_once() {
+        let test_repo = TestRepo::new();
+        let mut expected: Vec<String> = (0..4)
+            .map(|i| {
+                test_repo
+                    .commit_file("a.txt", &format!("{}\n", i), &format!("Commit {}", i))
+                    .to_string()
+            })
+            .collect();
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_weighting(CommitWeighting::Shuffle { seed: Some(7) });
+
+        let mut played: Vec<String> = (0..4).map(|_| repo.random_commit().unwrap().hash).collect();
+        played.sort();
+        expected.sort();
+        assert_eq!(played, expected);
+    }
+
+    #[test]
+    fn test_author_balance_weighting_evens_out_authors() {
+        let test_repo = TestRepo::new();
+        for i in 0..9 {
+            test_repo.commit_file("a.txt", &format!("{}\n", i), &format!("Commit {}", i));
+        }
+        let mut config = test_repo.repo.config().unwrap();
+        config.set_str("user.name", "Other User").unwrap();
+        config.set_str("user.email", "other@example.com").unwrap();
+        test_repo.commit_file("b.txt", "b\n", "Only commit by another author");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_weighting(CommitWeighting::AuthorBalance {
+            key: crate::weighting::AuthorKey::Email,
+        });
+
+        // One commit in ten, but half of the picks
+        let picks = 200;
+        let other = (0..picks)
+            .filter(|_| repo.random_commit().unwrap().author_email == "other@example.com")
+            .count();
+        assert!(
+            other > picks * 3 / 10 && other < picks * 7 / 10,
+            "{}",
+            other
+        );
+    }
+
+    #[test]
+    fn test_oversize_skip_policy_skips_large_commits() {
+        let test_repo = TestRepo::new();
+        let small = test_repo.commit_file("a.txt", "a\n", "Small");
+        test_repo.commit_files(
+            &[("b.txt", "b\n"), ("c.txt", "c\n"), ("d.txt", "d\n")],
+            "Large",
+        );
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_files_per_commit: Some(2),
+            ..CommitLimits::default()
+        });
+
+        assert_eq!(repo.next_desc_commit().unwrap().hash, small.to_string());
+        assert!(repo.next_desc_commit().is_err());
+    }
+
+    #[test]
+    fn test_oversize_trim_policy_keeps_largest_files() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_files(
+            &[
+                ("small.txt", "1\n"),
+                ("large.txt", "1\n2\n3\n"),
+                ("medium.txt", "1\n2\n"),
+            ],
+            "Three files",
+        );
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_files_per_commit: Some(2),
+            oversize_policy: OversizePolicy::Trim,
+            ..CommitLimits::default()
+        });
+
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut paths: Vec<&str> = metadata.changes.iter().map(|c| c.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["large.txt", "medium.txt"]);
+        assert_eq!(metadata.omitted_files, vec!["small.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_working_tree_diff_is_trimmed_to_the_limits() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_files(
+            &[
+                ("small.txt", "a\n"),
+                ("large.txt", "a\n"),
+                ("medium.txt", "a\n"),
+            ],
+            "Three files",
+        );
+        for (name, content) in [
+            ("small.txt", "b\n"),
+            ("large.txt", "b\nc\nd\n"),
+            ("medium.txt", "b\nc\n"),
+        ] {
+            std::fs::write(test_repo.path.join(name), content).unwrap();
+        }
+
+        // Skipping would leave nothing to play, so the diff is trimmed instead
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_files_per_commit: Some(2),
+            oversize_policy: OversizePolicy::Skip,
+            ..CommitLimits::default()
+        });
+
+        let metadata = repo.get_working_tree_diff(DiffMode::Unstaged).unwrap();
+        let mut paths: Vec<&str> = metadata.changes.iter().map(|c| c.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["large.txt", "medium.txt"]);
+        assert_eq!(metadata.omitted_files, vec!["small.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_random_playback_drops_oversized_commits_from_the_pool() {
+        let test_repo = TestRepo::new();
+        let small = test_repo.commit_file("a.txt", "a\n", "Small");
+        for i in 0..20 {
+            test_repo.commit_file("b.txt", &format!("{0}\n{0}\n", i), &format!("Large {}", i));
+        }
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_lines_per_commit: Some(1),
+            ..CommitLimits::default()
+        });
+
+        for _ in 0..5 {
+            assert_eq!(repo.random_commit().unwrap().hash, small.to_string());
+        }
+
+        // Every commit is dropped once none of them fits
+        repo.set_limits(CommitLimits {
+            max_lines_per_commit: Some(0),
+            ..CommitLimits::default()
+        });
+        assert!(repo.random_commit().is_err());
+        assert!(repo.commit_cache.borrow().as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_oversize_trim_policy_ignores_excluded_files() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_files(
+            &[
+                ("Cargo.lock", "1\n2\n3\n4\n5\n"),
+                ("large.txt", "1\n2\n3\n"),
+                ("small.txt", "1\n"),
+            ],
+            "Three files",
+        );
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_files_per_commit: Some(1),
+            max_lines_per_commit: Some(3),
+            oversize_policy: OversizePolicy::Trim,
+            ..CommitLimits::default()
+        });
+
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut paths: Vec<&str> = metadata.changes.iter().map(|c| c.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["Cargo.lock", "large.txt"]);
+        assert_eq!(metadata.omitted_files, vec!["small.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_max_change_lines_excludes_file() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("a.txt", "1\n2\n3\n", "Three lines");
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.set_limits(CommitLimits {
+            max_change_lines: 2,
+            ..CommitLimits::default()
+        });
+
+        let metadata = repo.get_commit("HEAD").unwrap();
+        assert!(metadata.changes[0].is_excluded);
+        assert_eq!(
+            metadata.changes[0].exclusion_reason.as_deref(),
+            Some("too many changes (3 lines)")
+        );
+    }
+
+    #[test]
+    fn test_binary_changes_keep_both_sides() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("data.bin", "\0\x01\x02", "Add binary");
+        test_repo.commit_file("data.bin", "\0\x01\x02\x03\x04", "Grow binary");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let change = &metadata.changes[0];
+        assert!(change.is_binary);
+        assert!(change.hunks.is_empty());
+
+        let binary = change.binary.as_ref().unwrap();
+        let old = binary.old.as_ref().unwrap();
+        let new = binary.new.as_ref().unwrap();
+        assert_eq!(old.size, 3);
+        assert_eq!(new.size, 5);
+        assert_eq!(new.data.as_deref(), Some(&[0u8, 1, 2, 3, 4][..]));
+
+        let metadata = repo.get_commit("HEAD~1").unwrap();
+        let binary = metadata.changes[0].binary.as_ref().unwrap();
+        assert!(binary.old.is_none());
+        assert_eq!(binary.new.as_ref().unwrap().size, 3);
+    }
+
+    #[test]
+    fn test_tree_paths_lists_nested_files() {
+        let test_repo = TestRepo::new();
+        std::fs::create_dir_all(test_repo.path.join("src/panes")).unwrap();
+        test_repo.commit_files(
+            &[
+                ("README.md", "readme"),
+                ("src/panes/editor.rs", "fn main() {}"),
+            ],
+            "Initial commit",
+        );
+        test_repo.commit_file("src/main.rs", "fn main() {}", "Add main");
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let mut paths = repo.tree_paths(&metadata).unwrap();
+        paths.sort();
+        assert_eq!(paths, ["README.md", "src/main.rs", "src/panes/editor.rs"]);
+
+        let metadata = repo.get_commit("HEAD~1").unwrap();
+        assert_eq!(repo.tree_paths(&metadata).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_co_authors() {
+        let message = "Add feature\n\nCo-authored-by: Alice <alice@example.com>\nco-authored-by:Bob <bob@example.com>\nSigned-off-by: Carol <carol@example.com>";
+        assert_eq!(
+            parse_co_authors(message),
+            ["Alice <alice@example.com>", "Bob <bob@example.com>"]
+        );
+        assert!(parse_co_authors("Co-authored-by:").is_empty());
+    }
+
+    #[test]
+    fn test_commit_refs_include_branches_and_tags() {
+        let test_repo = TestRepo::new();
+        let first = test_repo.commit_file("a.txt", "one", "First");
+        let second = test_repo.commit_file("a.txt", "two", "Second");
+        let second_commit = test_repo.repo.find_commit(second).unwrap();
+        test_repo
+            .repo
+            .tag_lightweight("v1.0", second_commit.as_object(), false)
+            .unwrap();
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let head_branch = test_repo
+            .repo
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+
+        let metadata = repo.get_commit(&second.to_string()).unwrap();
+        assert!(metadata.refs.contains(&CommitRef {
+            name: head_branch.clone(),
+            kind: RefKind::Branch,
+            exact: true,
+        }));
+        assert!(metadata.refs.contains(&CommitRef {
+            name: "v1.0".to_string(),
+            kind: RefKind::Tag,
+            exact: true,
+        }));
+
+        let metadata = repo.get_commit(&first.to_string()).unwrap();
+        assert!(metadata.refs.contains(&CommitRef {
+            name: head_branch,
+            kind: RefKind::Branch,
+            exact: false,
+        }));
+        assert!(metadata.refs.contains(&CommitRef {
+            name: "v1.0".to_string(),
+            kind: RefKind::Tag,
+            exact: false,
+        }));
+    }
+
+    #[test]
+    fn test_commit_refs_name_the_closest_later_tag_containing_the_commit() {
+        let test_repo = TestRepo::new();
+        let root = test_repo.commit_file_at("a.txt", "zero", "Root", 500);
+        let first = test_repo.commit_file_at("a.txt", "one", "First", 1000);
+        let second = test_repo.commit_file_at("a.txt", "two", "Second", 2000);
+        let third = test_repo.commit_file_at("a.txt", "three", "Third", 3000);
+
+        // A tag between the first and second commit on a side branch off the root
+        let repo = &test_repo.repo;
+        let root_commit = repo.find_commit(root).unwrap();
+        let sig =
+            git2::Signature::new("Side", "side@example.com", &git2::Time::new(1500, 0)).unwrap();
+        let side = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "Side",
+                &root_commit.tree().unwrap(),
+                &[&root_commit],
+            )
+            .unwrap();
+        for (name, target) in [("side", side), ("v1", second), ("v2", third)] {
+            let object = repo.find_object(target, None).unwrap();
+            repo.tag_lightweight(name, &object, false).unwrap();
+        }
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let tags = |oid: Oid| -> Vec<CommitRef> {
+            let metadata = repo.get_commit(&oid.to_string()).unwrap();
+            metadata
+                .refs
+                .into_iter()
+                .filter(|r| r.kind == RefKind::Tag)
+                .collect()
+        };
+        let tag = |name: &str, exact| CommitRef {
+            name: name.to_string(),
+            kind: RefKind::Tag,
+            exact,
+        };
+
+        assert_eq!(tags(first), [tag("v1", false)]);
+        assert_eq!(tags(second), [tag("v1", true)]);
+        assert_eq!(tags(root), [tag("side", false)]);
+        // Cached results are the same
+        assert_eq!(tags(first), [tag("v1", false)]);
+    }
+
+    #[test]
+    fn test_metadata_records_parent_and_head_branch() {
+        let test_repo = TestRepo::new();
+        let first = test_repo.commit_file("a.txt", "one", "First");
+        let second = test_repo.commit_file("a.txt", "two", "Second");
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+
+        assert_eq!(repo.get_commit(&first.to_string()).unwrap().parent, None);
+        let metadata = repo.get_commit(&second.to_string()).unwrap();
+        assert_eq!(metadata.parent, Some(first.to_string()));
+
+        std::fs::write(test_repo.path.join("a.txt"), "three").unwrap();
+        let metadata = repo.get_working_tree_diff(DiffMode::Unstaged).unwrap();
+        assert_eq!(metadata.parent, Some(second.to_string()));
+        let head = test_repo.repo.head().unwrap();
+        assert_eq!(
+            metadata.refs,
+            vec![CommitRef {
+                name: head.shorthand().unwrap().to_string(),
+                kind: RefKind::Branch,
+                exact: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_recent_commits_end_at_commit() {
+        let test_repo = TestRepo::new();
+        let first = test_repo.commit_file("a.txt", "one", "First");
+        let second = test_repo.commit_file("a.txt", "two", "Second");
+        test_repo.commit_file("a.txt", "three", "Third");
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+
+        let metadata = repo.get_commit(&second.to_string()).unwrap();
+        let commits = repo.recent_commits(&metadata, 5).unwrap();
+        let hashes: Vec<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(hashes, vec![second.to_string(), first.to_string()]);
+        assert_eq!(commits[0].summary, "Second");
+    }
+
+    #[test]
+    fn test_mailmap_normalizes_authors() {
+        let test_repo = TestRepo::new();
+        let oid = test_repo.commit_file(
+            ".mailmap",
+            "Canonical Name <canonical@example.com> <test@example.com>\n",
+            "Add mailmap",
+        );
+
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        assert_eq!(metadata.author, "Canonical Name");
+        assert_eq!(metadata.author_email, "canonical@example.com");
+        assert_eq!(metadata.committer, None);
+
+        repo.set_author_filter(Some("Canonical".to_string()));
+        assert_eq!(repo.next_asc_commit().unwrap().hash, oid.to_string());
+
+        // A configured mailmap file takes priority over the repository's
+        let extra = test_repo.path.join("extra.mailmap");
+        std::fs::write(&extra, "Other Name <test@example.com>\n").unwrap();
+        let mut repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.add_mailmap_file(&extra).unwrap();
+        let metadata = repo.get_commit(&oid.to_string()).unwrap();
+        assert_eq!(metadata.author, "Other Name");
+        assert_eq!(metadata.author_email, "test@example.com");
+    }
+
+    #[test]
+    fn test_commit_graph_centers_on_commit() {
+        let test_repo = TestRepo::new();
+        let oids: Vec<Oid> = (0..5)
+            .map(|i| test_repo.commit_file("a.txt", &format!("{}\n", i), &format!("Commit {}", i)))
+            .collect();
+        let tip = test_repo.repo.find_commit(oids[4]).unwrap();
+        test_repo
+            .repo
+            .tag_lightweight("v1.0", tip.as_object(), false)
+            .unwrap();
+
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit(&oids[2].to_string()).unwrap();
+        let graph = repo.commit_graph(&metadata, 1).unwrap();
+
+        let hashes: Vec<String> = graph.commits.iter().map(|c| c.hash.clone()).collect();
+        let expected: Vec<String> = oids[1..4].iter().rev().map(Oid::to_string).collect();
+        assert_eq!(hashes, expected);
+        assert_eq!(graph.current, Some(1));
+        assert_eq!(graph.commits[1].parents, vec![oids[1].to_string()]);
+
+        let metadata = repo.get_commit(&oids[4].to_string()).unwrap();
+        let graph = repo.commit_graph(&metadata, 1).unwrap();
+        assert_eq!(graph.current, Some(0));
+        assert!(graph.commits[0].refs.iter().any(|r| r.name == "v1.0"));
+    }
 }
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use ratatui::style::Color;
+
+const FOLDER: &str = "\u{f07b}";
+const FOLDER_OPEN: &str = "\u{f07c}";
+const DEFAULT_FILE: &str = "\u{f15b}";
+
+/// Icons for well-known file names, checked before extensions
+const FILE_NAMES: &[(&str, &str, Color)] = &[
+    (".gitignore", "\u{e702}", Color::Rgb(241, 80, 47)),
+    (".gitattributes", "\u{e702}", Color::Rgb(241, 80, 47)),
+    (".gitmodules", "\u{e702}", Color::Rgb(241, 80, 47)),
+    ("Cargo.toml", "\u{e7a8}", Color::Rgb(222, 165, 132)),
+    ("Cargo.lock", "\u{e7a8}", Color::Rgb(222, 165, 132)),
+    ("Dockerfile", "\u{f308}", Color::Rgb(69, 142, 230)),
+    ("Gemfile", "\u{e791}", Color::Rgb(204, 52, 45)),
+    ("LICENSE", "\u{f02d}", Color::Rgb(208, 191, 65)),
+    ("Makefile", "\u{e779}", Color::Rgb(109, 128, 134)),
+    ("package.json", "\u{e71e}", Color::Rgb(232, 39, 75)),
+    ("README.md", "\u{f48a}", Color::Rgb(221, 221, 221)),
+];
+
+/// Icons by lowercase extension, colored after the language
+const EXTENSIONS: &[(&str, &str, Color)] = &[
+    ("bash", "\u{e795}", Color::Rgb(137, 224, 81)),
+    ("c", "\u{e61e}", Color::Rgb(89, 158, 255)),
+    ("cc", "\u{e61d}", Color::Rgb(243, 75, 125)),
+    ("cpp", "\u{e61d}", Color::Rgb(243, 75, 125)),
+    ("cs", "\u{f031b}", Color::Rgb(89, 103, 6)),
+    ("css", "\u{e749}", Color::Rgb(66, 165, 245)),
+    ("dart", "\u{e798}", Color::Rgb(3, 88, 156)),
+    ("ex", "\u{e62d}", Color::Rgb(160, 116, 196)),
+    ("exs", "\u{e62d}", Color::Rgb(160, 116, 196)),
+    ("gif", "\u{f1c5}", Color::Rgb(160, 116, 196)),
+    ("go", "\u{e627}", Color::Rgb(0, 173, 216)),
+    ("h", "\u{f0fd}", Color::Rgb(160, 116, 196)),
+    ("hpp", "\u{f0fd}", Color::Rgb(160, 116, 196)),
+    ("hs", "\u{e777}", Color::Rgb(160, 116, 196)),
+    ("html", "\u{e736}", Color::Rgb(228, 77, 38)),
+    ("java", "\u{e738}", Color::Rgb(204, 62, 68)),
+    ("jpg", "\u{f1c5}", Color::Rgb(160, 116, 196)),
+    ("jpeg", "\u{f1c5}", Color::Rgb(160, 116, 196)),
+    ("js", "\u{e74e}", Color::Rgb(203, 203, 65)),
+    ("json", "\u{e60b}", Color::Rgb(203, 203, 65)),
+    ("jsx", "\u{e7ba}", Color::Rgb(32, 194, 227)),
+    ("kt", "\u{e634}", Color::Rgb(127, 82, 255)),
+    ("lock", "\u{f023}", Color::Rgb(187, 187, 187)),
+    ("lua", "\u{e620}", Color::Rgb(81, 160, 207)),
+    ("md", "\u{e73e}", Color::Rgb(221, 221, 221)),
+    ("nix", "\u{f313}", Color::Rgb(126, 186, 228)),
+    ("php", "\u{e73d}", Color::Rgb(160, 116, 196)),
+    ("png", "\u{f1c5}", Color::Rgb(160, 116, 196)),
+    ("py", "\u{e73c}", Color::Rgb(255, 188, 3)),
+    ("rb", "\u{e791}", Color::Rgb(204, 52, 45)),
+    ("rs", "\u{e7a8}", Color::Rgb(222, 165, 132)),
+    ("scala", "\u{e737}", Color::Rgb(204, 62, 68)),
+    ("scss", "\u{e603}", Color::Rgb(245, 83, 133)),
+    ("sh", "\u{e795}", Color::Rgb(137, 224, 81)),
+    ("svg", "\u{f1c5}", Color::Rgb(255, 179, 0)),
+    ("swift", "\u{e755}", Color::Rgb(227, 121, 51)),
+    ("toml", "\u{e6b2}", Color::Rgb(156, 66, 33)),
+    ("ts", "\u{e628}", Color::Rgb(81, 154, 186)),
+    ("tsx", "\u{e7ba}", Color::Rgb(32, 194, 227)),
+    ("txt", "\u{f15c}", Color::Rgb(137, 224, 81)),
+    ("yaml", "\u{e6a8}", Color::Rgb(109, 128, 134)),
+    ("yml", "\u{e6a8}", Color::Rgb(109, 128, 134)),
+    ("zig", "\u{e6a9}", Color::Rgb(246, 154, 27)),
+    ("zsh", "\u{e795}", Color::Rgb(137, 224, 81)),
+];
+
+/// A Nerd Font glyph, with its own color or the theme's file color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Icon {
+    pub glyph: String,
+    pub color: Option<Color>,
+}
+
+impl Icon {
+    fn builtin(glyph: &str, color: Color) -> Self {
+        Self {
+            glyph: glyph.to_string(),
+            color: Some(color),
+        }
+    }
+}
+
+/// File tree icons chosen by file name, then extension
+#[derive(Debug, Clone, Default)]
+pub struct FileIcons {
+    /// Icons from the `[file_icons]` config table, keyed by file name or extension
+    overrides: HashMap<String, Icon>,
+}
+
+impl FileIcons {
+    pub fn new(overrides: HashMap<String, Icon>) -> Self {
+        Self { overrides }
+    }
+
+    /// Icon for a file, looked up by its name and then its extension.
+    pub fn file(&self, name: &str) -> Icon {
+        if let Some(icon) = self.lookup(name, FILE_NAMES) {
+            return icon;
+        }
+        name.rsplit_once('.')
+            .filter(|(stem, _)| !stem.is_empty())
+            .and_then(|(_, extension)| self.lookup(&extension.to_lowercase(), EXTENSIONS))
+            .unwrap_or(Icon {
+                glyph: DEFAULT_FILE.to_string(),
+                color: None,
+            })
+    }
+
+    /// Glyph for a directory, open when its contents are listed below it.
+    pub fn directory(&self, open: bool) -> &'static str {
+        if open {
+            FOLDER_OPEN
+        } else {
+            FOLDER
+        }
+    }
+
+    fn lookup(&self, key: &str, builtin: &[(&str, &str, Color)]) -> Option<Icon> {
+        let default = builtin
+            .iter()
+            .find(|(name, _, _)| *name == key)
+            .map(|(_, glyph, color)| Icon::builtin(glyph, *color));
+        match self.overrides.get(key) {
+            // An override without a color keeps the built-in one
+            Some(icon) => Some(Icon {
+                glyph: icon.glyph.clone(),
+                color: icon.color.or(default.and_then(|icon| icon.color)),
+            }),
+            None => default,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod git;
 pub mod syntax;
 pub mod theme;
+pub mod weighting;
@@ -1,20 +1,36 @@
 mod animation;
+mod cache;
 mod config;
 mod git;
+mod icons;
+mod narrative;
 mod panes;
+mod preview;
+mod session;
+mod split;
 mod syntax;
 mod theme;
 mod ui;
+mod weighting;
 mod widgets;
 
-use animation::SpeedRule;
+use animation::{SpeedRule, Timing};
 use anyhow::{Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveTime;
 use clap::{Parser, Subcommand, ValueEnum};
-use config::Config;
-use git::{DiffMode, GitRepository};
+use config::{Config, FileIconConfig};
+use git::{CommitLimits, DiffMode, GitRepository, OversizePolicy};
+use icons::{FileIcons, Icon};
+use narrative::Narrative;
+use panes::FileTreeMode;
+use session::SessionLimits;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use syntax::LanguageRule;
 use theme::Theme;
 use ui::UI;
+use weighting::{AuthorKey, CommitWeighting};
 
 /// Defines the order in which commits are played back during animation.
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -25,6 +41,60 @@ pub enum PlaybackOrder {
     Desc,
 }
 
+/// Defines how file changes are shown in the editor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ViewMode {
+    /// Type each change out as if written by hand
+    #[default]
+    Typing,
+    /// Show old and new content side by side, revealing each hunk
+    Split,
+}
+
+/// Defines how random playback picks the next commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Weighting {
+    #[default]
+    Uniform,
+    Size,
+    Recency,
+    AuthorBalance,
+    Shuffle,
+}
+
+impl Weighting {
+    /// Builds the selection strategy using the tunable parameters from the config file.
+    fn to_commit_weighting(self, weights: &config::WeightsConfig) -> CommitWeighting {
+        match self {
+            Weighting::Uniform => CommitWeighting::Uniform,
+            Weighting::Size => CommitWeighting::Size {
+                target_lines: weights.size.target_lines,
+                spread: weights.size.spread,
+                sample: weights.size.sample,
+            },
+            Weighting::Recency => CommitWeighting::Recency {
+                half_life_days: weights.recency.half_life_days,
+            },
+            Weighting::AuthorBalance => CommitWeighting::AuthorBalance {
+                key: match weights.author_balance.key.as_str() {
+                    "name" => AuthorKey::Name,
+                    "email" => AuthorKey::Email,
+                    key => {
+                        eprintln!(
+                            "Warning: Unknown weights.author_balance.key '{}', using 'name'",
+                            key
+                        );
+                        AuthorKey::Name
+                    }
+                },
+            },
+            Weighting::Shuffle => CommitWeighting::Shuffle {
+                seed: weights.shuffle.seed,
+            },
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "gitlogue",
@@ -82,6 +152,14 @@ pub struct Args {
     )]
     pub order: Option<PlaybackOrder>,
 
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STRATEGY",
+        help = "How random order picks commits (overrides config file)"
+    )]
+    pub weighting: Option<Weighting>,
+
     #[arg(
         long = "loop",
         num_args = 0..=1,
@@ -91,6 +169,29 @@ pub struct Args {
     )]
     pub loop_playback: Option<bool>,
 
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        help = "Type changes out or show them as a side-by-side diff (overrides config file)"
+    )]
+    pub view: Option<ViewMode>,
+
+    #[arg(
+        long,
+        value_name = "NAME_OR_FILE",
+        help = "Terminal narrative: fun, realistic, minimal, or a template file (overrides config file)"
+    )]
+    pub narrative: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = session::parse_duration,
+        help = "Scale typing and pauses so that every commit plays in about this long (e.g. 60s, overrides config file)"
+    )]
+    pub target_duration: Option<Duration>,
+
     #[arg(long, help = "Display third-party license information")]
     pub license: bool,
 
@@ -145,6 +246,72 @@ pub struct Args {
     )]
     pub speed_rule: Vec<String>,
 
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Skip files larger than this many bytes (default: 512000, overrides config file)"
+    )]
+    pub max_blob_size: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Skip files with more changed lines than this (default: 2000, overrides config file)"
+    )]
+    pub max_change_lines: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Limit the number of files per commit (see --oversize-policy)"
+    )]
+    pub max_files_per_commit: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Limit the number of changed lines per commit (see --oversize-policy)"
+    )]
+    pub max_lines_per_commit: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        value_parser = ["skip", "trim"],
+        help = "Skip commits over the per-commit limits, or trim them to the most interesting files (overrides config file)"
+    )]
+    pub oversize_policy: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = session::parse_duration,
+        help = "Stop playing after this long (e.g. 90s, 15m, 1h30m)"
+    )]
+    pub duration: Option<Duration>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = session::parse_max_commits,
+        help = "Stop playing after this many commits"
+    )]
+    pub max_commits: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "HH:MM",
+        value_parser = session::parse_time_of_day,
+        help = "Stop playing at this local time of day"
+    )]
+    pub until_time: Option<NaiveTime>,
+
+    #[arg(
+        long,
+        help = "Show a summary of the session when it ends instead of exiting"
+    )]
+    pub summary: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -187,6 +354,59 @@ pub enum Commands {
         #[arg(long = "speed-rule", value_name = "PATTERN:MS", action = clap::ArgAction::Append,
               help = "Set typing speed for files matching pattern (e.g., '*.java:50')")]
         speed_rule: Vec<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            value_name = "MODE",
+            help = "Type changes out or show them as a side-by-side diff"
+        )]
+        view: Option<ViewMode>,
+
+        #[arg(
+            long,
+            value_name = "NAME_OR_FILE",
+            help = "Terminal narrative: fun, realistic, minimal, or a template file"
+        )]
+        narrative: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = session::parse_duration,
+            help = "Scale typing and pauses so that the changes play in about this long (e.g. 60s)"
+        )]
+        target_duration: Option<Duration>,
+
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = session::parse_duration,
+            help = "Stop playing after this long (e.g. 90s, 15m, 1h30m)"
+        )]
+        duration: Option<Duration>,
+
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = session::parse_max_commits,
+            help = "Stop playing after this many loops of the changes"
+        )]
+        max_commits: Option<usize>,
+
+        #[arg(
+            long,
+            value_name = "HH:MM",
+            value_parser = session::parse_time_of_day,
+            help = "Stop playing at this local time of day"
+        )]
+        until_time: Option<NaiveTime>,
+
+        #[arg(
+            long,
+            help = "Show a summary of the session when it ends instead of exiting"
+        )]
+        summary: bool,
     },
 }
 
@@ -202,6 +422,32 @@ pub enum ThemeCommands {
 }
 
 impl Args {
+    /// Resolves the commit size limits: CLI arguments > config file > defaults.
+    fn commit_limits(&self, config: &Config) -> CommitLimits {
+        let policy = self
+            .oversize_policy
+            .as_deref()
+            .unwrap_or(&config.oversize_policy);
+
+        CommitLimits {
+            max_blob_size: self.max_blob_size.unwrap_or(config.max_blob_size),
+            max_change_lines: self.max_change_lines.unwrap_or(config.max_change_lines),
+            max_files_per_commit: self.max_files_per_commit.or(config.max_files_per_commit),
+            max_lines_per_commit: self.max_lines_per_commit.or(config.max_lines_per_commit),
+            oversize_policy: match policy {
+                "skip" => OversizePolicy::Skip,
+                "trim" => OversizePolicy::Trim,
+                policy => {
+                    eprintln!(
+                        "Warning: Unknown oversize_policy '{}', using 'skip'",
+                        policy
+                    );
+                    OversizePolicy::Skip
+                }
+            },
+        }
+    }
+
     /// Validates the command-line arguments and returns the Git repository path.
     pub fn validate(&self) -> Result<PathBuf> {
         let start_path = self.path.clone().unwrap_or_else(|| PathBuf::from("."));
@@ -242,6 +488,232 @@ impl Args {
     }
 }
 
+// Parse the `[languages]` config table, warning about invalid entries
+fn language_rules(config: &Config) -> Vec<LanguageRule> {
+    config
+        .languages
+        .iter()
+        .filter_map(|(pattern, language)| {
+            LanguageRule::new(pattern, language).or_else(|| {
+                eprintln!(
+                    "Warning: Invalid language mapping '{} = {}', skipping",
+                    pattern, language
+                );
+                None
+            })
+        })
+        .collect()
+}
+
+fn view_mode(config: &Config) -> ViewMode {
+    match config.view.as_str() {
+        "typing" => ViewMode::Typing,
+        "split" => ViewMode::Split,
+        view => {
+            eprintln!("Warning: Unknown view '{}', using 'typing'", view);
+            ViewMode::Typing
+        }
+    }
+}
+
+fn weighting(config: &Config) -> Weighting {
+    match config.weighting.as_str() {
+        "uniform" => Weighting::Uniform,
+        "size" => Weighting::Size,
+        "recency" => Weighting::Recency,
+        "author-balance" => Weighting::AuthorBalance,
+        "shuffle" => Weighting::Shuffle,
+        weighting => {
+            eprintln!(
+                "Warning: Unknown weighting '{}', using 'uniform'",
+                weighting
+            );
+            Weighting::Uniform
+        }
+    }
+}
+
+fn file_tree_mode(config: &Config) -> FileTreeMode {
+    match config.file_tree.as_str() {
+        "changes" => FileTreeMode::Changes,
+        "repo" => FileTreeMode::Repo,
+        mode => {
+            eprintln!("Warning: Unknown file_tree '{}', using 'changes'", mode);
+            FileTreeMode::Changes
+        }
+    }
+}
+
+// Use the configured strftime date format, falling back to the default when it is invalid
+fn date_format(config: &Config) -> String {
+    let is_valid = StrftimeItems::new(&config.date_format).all(|item| item != Item::Error);
+    if is_valid {
+        config.date_format.clone()
+    } else {
+        eprintln!(
+            "Warning: Invalid date format '{}', using the default",
+            config.date_format
+        );
+        Config::default().date_format
+    }
+}
+
+// Build the timing from the `[timing]` preset and overrides, warning about invalid entries.
+// A target duration from the command line replaces the configured one.
+fn timing(config: &Config, target_duration: Option<Duration>) -> Timing {
+    let preset = config.timing.preset.as_deref().unwrap_or("default");
+    let mut timing = Timing::preset(preset).unwrap_or_else(|e| {
+        eprintln!("Warning: {:#}, using the default timing", e);
+        Timing::default()
+    });
+    for (name, multiplier) in &config.timing.pauses {
+        if !multiplier.is_finite() || *multiplier < 0.0 {
+            eprintln!(
+                "Warning: Invalid timing {} = {}, skipping",
+                name, multiplier
+            );
+        } else if !timing.set(name, *multiplier) {
+            eprintln!("Warning: Unknown timing '{}', skipping", name);
+        }
+    }
+    timing.commit_delay_ms = config.timing.commit_delay_ms;
+    timing.target_duration = target_duration.or_else(|| {
+        let value = config.timing.target_duration.as_deref()?;
+        session::parse_duration(value)
+            .map_err(|e| eprintln!("Warning: {}, skipping", e))
+            .ok()
+    });
+    let min = config
+        .timing
+        .min_fit_speed_ms
+        .unwrap_or(timing.min_fit_speed_ms);
+    let max = config
+        .timing
+        .max_fit_speed_ms
+        .unwrap_or(timing.max_fit_speed_ms);
+    if min.is_finite() && max.is_finite() && min > 0.0 && min <= max {
+        timing.min_fit_speed_ms = min;
+        timing.max_fit_speed_ms = max;
+    } else {
+        eprintln!(
+            "Warning: Invalid timing min_fit_speed_ms = {}, max_fit_speed_ms = {}, skipping",
+            min, max
+        );
+    }
+    timing
+}
+
+// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+// Add the configured mailmap file
+fn add_mailmap(repo: &mut GitRepository, config: &Config) {
+    let Some(path) = &config.mailmap else {
+        return;
+    };
+    if let Err(e) = repo.add_mailmap_file(&expand_home(path)) {
+        eprintln!("Warning: {:#}, skipping", e);
+    }
+}
+
+// Build file tree icons with the `[file_icons]` overrides, warning about invalid colors
+fn file_icons(config: &Config) -> Option<FileIcons> {
+    if !config.icons {
+        return None;
+    }
+    let overrides = config
+        .file_icons
+        .iter()
+        .filter_map(|(key, icon)| {
+            let (glyph, color) = match icon {
+                FileIconConfig::Glyph(glyph) => (glyph, None),
+                FileIconConfig::Styled { icon, color } => (icon, color.as_deref()),
+            };
+            let color = match color.map(str::parse) {
+                Some(Ok(color)) => Some(color),
+                Some(Err(_)) => {
+                    eprintln!(
+                        "Warning: Invalid icon color '{}' for '{}', skipping",
+                        color.unwrap_or_default(),
+                        key
+                    );
+                    return None;
+                }
+                None => None,
+            };
+            let icon = Icon {
+                glyph: glyph.clone(),
+                color,
+            };
+            Some((key.clone(), icon))
+        })
+        .collect();
+    Some(FileIcons::new(overrides))
+}
+
+// Load user grammars and query overrides, warning about entries that fail to load
+fn load_runtime_languages() {
+    let (Ok(grammars_dir), Ok(queries_dir)) = (Config::grammars_dir(), Config::queries_dir())
+    else {
+        return;
+    };
+    for warning in syntax::load_runtime_languages(&grammars_dir, &queries_dir) {
+        eprintln!("Warning: {}, skipping", warning);
+    }
+}
+
+// Load a template file, a user narrative from the narratives directory or a built-in one,
+// falling back to the default narrative when it is invalid
+fn load_narrative(name: &str) -> Narrative {
+    let narrative = if name.ends_with(".toml") {
+        Narrative::load_file(&expand_home(name))
+    } else {
+        match Config::narratives_dir() {
+            Ok(narratives_dir) => Narrative::load_from_dir(name, &narratives_dir),
+            Err(_) => Narrative::load(name),
+        }
+    };
+    narrative.unwrap_or_else(|e| {
+        eprintln!("Warning: {:#}, using the default narrative", e);
+        Narrative::load(&Config::default().narrative).unwrap_or_default()
+    })
+}
+
+// Load a built-in theme or a user theme file from the themes directory
+fn load_theme(name: &str) -> Result<Theme> {
+    match Config::themes_dir() {
+        Ok(themes_dir) => Theme::load_from_dir(name, &themes_dir),
+        Err(_) => Theme::load(name),
+    }
+}
+
+// Apply the config file settings shared by every playback mode, with CLI overrides
+fn configure_ui(
+    ui: &mut UI,
+    config: &Config,
+    view: Option<ViewMode>,
+    narrative: Option<&str>,
+    target_duration: Option<Duration>,
+) {
+    ui.set_animate_deletions(config.animate_deletions);
+    ui.set_highlight_typed_lines(config.highlight_typed_lines);
+    ui.set_minimap(config.minimap);
+    ui.set_file_tree_mode(file_tree_mode(config));
+    ui.set_file_icons(file_icons(config));
+    ui.set_commit_graph(config.commit_graph);
+    ui.set_scrollback(config.scrollback);
+    ui.set_timing(timing(config, target_duration));
+    ui.set_narrative(load_narrative(narrative.unwrap_or(&config.narrative)));
+    ui.set_date_format(date_format(config));
+    ui.set_language_rules(language_rules(config));
+    ui.set_view_mode(view.unwrap_or_else(|| view_mode(config)));
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -260,11 +732,21 @@ fn main() -> Result<()> {
                     for theme in Theme::available_themes() {
                         println!("  - {}", theme);
                     }
+                    let user_themes = Config::themes_dir()
+                        .map(|dir| Theme::user_themes(&dir))
+                        .unwrap_or_default();
+                    if !user_themes.is_empty() {
+                        println!("User themes:");
+                        for theme in user_themes {
+                            println!("  - {}", theme);
+                        }
+                    }
                     return Ok(());
                 }
                 ThemeCommands::Set { name } => {
                     // Validate theme exists
-                    Theme::load(name)?;
+                    load_runtime_languages();
+                    load_theme(name)?;
 
                     // Load existing config or create new one
                     let mut config = Config::load().unwrap_or_default();
@@ -284,9 +766,20 @@ fn main() -> Result<()> {
                 loop_playback,
                 ignore,
                 speed_rule,
+                view,
+                narrative,
+                target_duration,
+                duration,
+                max_commits,
+                until_time,
+                summary,
             } => {
                 let repo_path = args.validate()?;
-                let repo = GitRepository::open(&repo_path)?;
+                let mut repo = GitRepository::open(&repo_path)?;
+                let config = Config::load()?;
+                repo.set_limits(args.commit_limits(&config));
+                add_mailmap(&mut repo, &config);
+                load_runtime_languages();
 
                 let mode = if *unstaged {
                     DiffMode::Unstaged
@@ -301,8 +794,6 @@ fn main() -> Result<()> {
                     return Ok(());
                 }
 
-                let config = Config::load()?;
-
                 let mut patterns = config.ignore_patterns.clone();
                 patterns.extend(ignore.clone());
                 git::init_ignore_patterns(&patterns).ok();
@@ -312,7 +803,7 @@ fn main() -> Result<()> {
                 let background = background.unwrap_or(config.background);
                 let loop_playback = loop_playback.unwrap_or(false);
 
-                let mut theme = Theme::load(theme_name)?;
+                let mut theme = load_theme(theme_name)?;
                 if !background {
                     theme = theme.with_transparent_background();
                 }
@@ -341,6 +832,19 @@ fn main() -> Result<()> {
                     speed_rules,
                 );
                 ui.set_diff_mode(Some(mode));
+                configure_ui(
+                    &mut ui,
+                    &config,
+                    *view,
+                    narrative.as_deref(),
+                    *target_duration,
+                );
+                ui.set_session_limits(&SessionLimits {
+                    duration: *duration,
+                    max_commits: *max_commits,
+                    until_time: *until_time,
+                    summary: *summary,
+                });
                 ui.load_commit(metadata);
                 ui.run()?;
 
@@ -377,6 +881,13 @@ fn main() -> Result<()> {
 
     // Load config: CLI arguments > config file > defaults
     let config = Config::load()?;
+    load_runtime_languages();
+
+    if config.commit_cache {
+        repo.enable_index_cache();
+    }
+    repo.set_limits(args.commit_limits(&config));
+    add_mailmap(&mut repo, &config);
 
     // Initialize ignore patterns: CLI flags > ignore-file > config
     let mut patterns = config.ignore_patterns.clone();
@@ -406,8 +917,11 @@ fn main() -> Result<()> {
         order = PlaybackOrder::Asc;
     }
 
+    let weighting = args.weighting.unwrap_or_else(|| weighting(&config));
+    repo.set_weighting(weighting.to_commit_weighting(&config.weights));
+
     let loop_playback = args.loop_playback.unwrap_or(config.loop_playback);
-    let mut theme = Theme::load(theme_name)?;
+    let mut theme = load_theme(theme_name)?;
 
     // Apply transparent background if requested
     if !background {
@@ -468,6 +982,19 @@ fn main() -> Result<()> {
         is_range_mode,
         speed_rules,
     );
+    configure_ui(
+        &mut ui,
+        &config,
+        args.view,
+        args.narrative.as_deref(),
+        args.target_duration,
+    );
+    ui.set_session_limits(&SessionLimits {
+        duration: args.duration,
+        max_commits: args.max_commits,
+        until_time: args.until_time,
+        summary: args.summary,
+    });
     ui.load_commit(metadata);
     ui.run()?;
 
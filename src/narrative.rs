@@ -0,0 +1,705 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::git::{
+    BinaryBlob, CommitMetadata, DiffHunk, FileChange, FileStatus, GraphCommit, LineChangeType,
+    RefKind,
+};
+
+/// Commits listed by the `{log}` placeholder
+pub const LOG_LINES: usize = 5;
+// Columns of `{stat}`, git's width when not writing to a terminal
+const STAT_WIDTH: usize = 80;
+
+/// Placeholders that templates may use, like `{short_hash}`
+const PLACEHOLDERS: &[&str] = &[
+    "hash",
+    "short_hash",
+    "parent",
+    "author",
+    "email",
+    "date",
+    "branch",
+    "summary",
+    "files",
+    "additions",
+    "deletions",
+    "shortstat",
+];
+
+/// Placeholders that expand to several output lines, each on a line of its own
+const BLOCK_PLACEHOLDERS: &[&str] = &["stat", "log"];
+
+/// A narrative template file, built in or in `<narratives dir>/<name>.toml`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NarrativeFile {
+    /// Narrative providing the sections that are not set, which are otherwise empty
+    extends: Option<String>,
+    intro: Option<Vec<String>>,
+    outro: Option<Vec<String>>,
+    working_tree_intro: Option<Vec<String>>,
+    working_tree_outro: Option<Vec<String>>,
+}
+
+/// Terminal lines played around a commit's files.
+/// Lines starting with `$ ` are typed as commands, the others are printed as output.
+#[derive(Debug, Clone, Default)]
+pub struct Narrative {
+    pub intro: Vec<String>,
+    pub outro: Vec<String>,
+    pub working_tree_intro: Vec<String>,
+    pub working_tree_outro: Vec<String>,
+}
+
+impl Narrative {
+    /// Load a built-in narrative by name
+    pub fn load(name: &str) -> Result<Self> {
+        let content = match name {
+            "fun" => include_str!("narratives/fun.toml"),
+            "realistic" => include_str!("narratives/realistic.toml"),
+            "minimal" => include_str!("narratives/minimal.toml"),
+            _ => {
+                return Err(anyhow::anyhow!("Unknown narrative: {}", name))
+                    .context("Available narratives: fun, realistic, minimal")
+            }
+        };
+        Self::from_toml(content)
+    }
+
+    /// Load narrative by name, preferring a user template `<name>.toml` in `narratives_dir`
+    pub fn load_from_dir(name: &str, narratives_dir: &Path) -> Result<Self> {
+        let path = narratives_dir.join(format!("{}.toml", name));
+        if !path.is_file() {
+            return Self::load(name);
+        }
+        Self::load_file(&path)
+    }
+
+    /// Load a user template file
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read narrative file: {}", path.display()))?;
+        Self::from_toml(&content)
+            .with_context(|| format!("Failed to parse narrative file: {}", path.display()))
+    }
+
+    /// Parse a template, taking unset sections from the narrative it extends
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: NarrativeFile = toml::from_str(content)?;
+        let sections = [
+            &file.intro,
+            &file.outro,
+            &file.working_tree_intro,
+            &file.working_tree_outro,
+        ];
+        for line in sections.into_iter().flatten().flatten() {
+            validate_placeholders(line)?;
+        }
+
+        let base = match &file.extends {
+            Some(name) => Self::load(name)?,
+            None => Self::default(),
+        };
+        Ok(Self {
+            intro: file.intro.unwrap_or(base.intro),
+            outro: file.outro.unwrap_or(base.outro),
+            working_tree_intro: file.working_tree_intro.unwrap_or(base.working_tree_intro),
+            working_tree_outro: file.working_tree_outro.unwrap_or(base.working_tree_outro),
+        })
+    }
+
+    /// Whether any line prints the recent history with `{log}`
+    pub fn uses_log(&self) -> bool {
+        [
+            &self.intro,
+            &self.outro,
+            &self.working_tree_intro,
+            &self.working_tree_outro,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|line| line.trim() == "{log}")
+    }
+}
+
+// Reject `{name}` placeholders that would never be filled in
+fn validate_placeholders(line: &str) -> Result<()> {
+    let mut rest = line;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        if BLOCK_PLACEHOLDERS.contains(&name) {
+            if line.trim() != format!("{{{}}}", name) {
+                anyhow::bail!("{{{}}} must be on a line of its own in \"{}\"", name, line);
+            }
+        } else if !PLACEHOLDERS.contains(&name) {
+            anyhow::bail!(
+                "Unknown placeholder {{{}}} in \"{}\" (available: {}, {})",
+                name,
+                line,
+                PLACEHOLDERS.join(", "),
+                BLOCK_PLACEHOLDERS.join(", ")
+            );
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// Placeholder values for one commit
+pub struct NarrativeContext {
+    values: Vec<(&'static str, String)>,
+    stat: Vec<String>,
+    log: Vec<String>,
+}
+
+impl NarrativeContext {
+    /// Values for a commit, with `history` (newest first) for the `{log}` placeholder
+    pub fn new(metadata: &CommitMetadata, history: &[GraphCommit]) -> Self {
+        let short = |hash: &str| short_hash(hash).to_string();
+        let (additions, deletions) = metadata
+            .changes
+            .iter()
+            .map(|change| line_counts(&change.hunks))
+            .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d));
+        let files = plural(metadata.changes.len(), "file");
+        // A branch at the commit, then one containing it
+        let branch = metadata
+            .refs
+            .iter()
+            .filter(|commit_ref| commit_ref.kind == RefKind::Branch)
+            .min_by_key(|commit_ref| !commit_ref.exact)
+            .map_or("HEAD".to_string(), |commit_ref| commit_ref.name.clone());
+
+        let values = vec![
+            ("hash", metadata.hash.clone()),
+            ("short_hash", short(&metadata.hash)),
+            (
+                "parent",
+                metadata
+                    .parent
+                    .as_deref()
+                    .map_or("0000000".to_string(), short),
+            ),
+            ("author", metadata.author.clone()),
+            ("email", metadata.author_email.clone()),
+            (
+                "date",
+                metadata
+                    .date
+                    .with_timezone(&metadata.utc_offset)
+                    .format("%Y-%m-%d %H:%M:%S %z")
+                    .to_string(),
+            ),
+            ("branch", branch.clone()),
+            (
+                "summary",
+                metadata
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or("Update")
+                    .to_string(),
+            ),
+            ("files", files),
+            ("additions", additions.to_string()),
+            ("deletions", deletions.to_string()),
+            (
+                "shortstat",
+                shortstat(metadata.changes.len(), additions, deletions),
+            ),
+        ];
+        Self {
+            stat: stat_lines(metadata),
+            log: log_lines(history, &branch),
+            values,
+        }
+    }
+
+    /// Fill in a template line, which `{stat}` and `{log}` turn into several lines
+    pub fn expand_lines(&self, template: &str) -> Vec<String> {
+        match template.trim() {
+            "{stat}" => self.stat.clone(),
+            "{log}" => self.log.clone(),
+            _ => vec![self.expand(template)],
+        }
+    }
+
+    /// Fill in the placeholders of a template line
+    pub fn expand(&self, template: &str) -> String {
+        let mut result = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let value = rest[start..].find('}').and_then(|end| {
+                let name = &rest[start + 1..start + end];
+                let (_, value) = self.values.iter().find(|(key, _)| *key == name)?;
+                Some((value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    result.push_str(value);
+                    rest = &rest[start + end + 1..];
+                }
+                None => {
+                    result.push('{');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..7.min(hash.len())]
+}
+
+// "1 file", "3 files"
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    }
+}
+
+fn line_counts(hunks: &[DiffHunk]) -> (usize, usize) {
+    hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .fold((0, 0), |(added, deleted), line| match line.change_type {
+            LineChangeType::Addition => (added + 1, deleted),
+            LineChangeType::Deletion => (added, deleted + 1),
+            LineChangeType::Context => (added, deleted),
+        })
+}
+
+// " 3 files changed, 10 insertions(+), 2 deletions(-)", leaving out a zero count like git
+fn shortstat(files: usize, insertions: usize, deletions: usize) -> String {
+    let mut line = format!(" {} changed", plural(files, "file"));
+    if insertions > 0 || deletions == 0 {
+        line.push_str(&format!(", {}(+)", plural(insertions, "insertion")));
+    }
+    if deletions > 0 || insertions == 0 {
+        line.push_str(&format!(", {}(-)", plural(deletions, "deletion")));
+    }
+    line
+}
+
+// `git show --stat` lines: each file with its changed lines and a `+`/`-` graph, then the totals
+fn stat_lines(metadata: &CommitMetadata) -> Vec<String> {
+    let mut changes: Vec<&FileChange> = metadata.changes.iter().collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    let rows: Vec<(String, &FileChange, (usize, usize))> = changes
+        .into_iter()
+        .map(|change| {
+            let name = match (&change.status, &change.old_path) {
+                (FileStatus::Renamed, Some(old_path)) => rename_name(old_path, &change.path),
+                _ => change.path.clone(),
+            };
+            (name, change, line_counts(&change.hunks))
+        })
+        .collect();
+
+    let binary_sizes = |change: &FileChange| {
+        let binary = change.binary.as_ref();
+        let size = |blob: Option<&BinaryBlob>| blob.map_or(0, |blob| blob.size);
+        (
+            size(binary.and_then(|b| b.old.as_ref())),
+            size(binary.and_then(|b| b.new.as_ref())),
+        )
+    };
+    let max_len = rows
+        .iter()
+        .map(|(name, _, _)| name.width())
+        .max()
+        .unwrap_or(0);
+    let max_changed = rows
+        .iter()
+        .filter(|(_, change, _)| !change.is_binary)
+        .map(|(_, _, (added, deleted))| added + deleted)
+        .max()
+        .unwrap_or(0);
+    // Width of "Bin <old> -> <new> bytes"
+    let bin_width = rows
+        .iter()
+        .filter(|(_, change, _)| change.is_binary)
+        .map(|(_, change, _)| {
+            let (old, new) = binary_sizes(change);
+            14 + old.to_string().len() + new.to_string().len()
+        })
+        .max();
+    let count_width =
+        max_changed
+            .to_string()
+            .len()
+            .max(if bin_width.is_some() { "Bin".len() } else { 0 });
+
+    // Share the columns between names and the graph like git does
+    let bin_width = bin_width.unwrap_or(0);
+    let mut graph_width = if max_changed + 4 > bin_width {
+        max_changed
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = max_len;
+    if name_width + count_width + 6 + graph_width > STAT_WIDTH {
+        let graph_limit = (STAT_WIDTH * 3 / 8).saturating_sub(count_width + 6);
+        if graph_width > graph_limit {
+            graph_width = graph_limit.max(6);
+        }
+        let available = STAT_WIDTH.saturating_sub(count_width + 6 + graph_width);
+        if name_width > available {
+            name_width = available;
+        } else {
+            graph_width = STAT_WIDTH - count_width - 6 - name_width;
+        }
+    }
+    // Like git, every non-empty side keeps at least one mark
+    let scale = |count: usize| match count {
+        0 => 0,
+        count => 1 + count * (graph_width - 1) / max_changed,
+    };
+    let graph = |added: usize, deleted: usize| {
+        if graph_width > max_changed {
+            return (added, deleted);
+        }
+        let mut total = scale(added + deleted);
+        if total < 2 && added > 0 && deleted > 0 {
+            total = 2;
+        }
+        if added < deleted {
+            (scale(added), total - scale(added))
+        } else {
+            (total - scale(deleted), scale(deleted))
+        }
+    };
+
+    let mut lines: Vec<String> = rows
+        .iter()
+        .map(|(name, change, (added, deleted))| {
+            let name = truncate_name(name, name_width);
+            let padding = " ".repeat(name_width.saturating_sub(name.width()));
+            let detail = if change.is_binary {
+                let (old, new) = binary_sizes(change);
+                format!("{:>count_width$} {} -> {} bytes", "Bin", old, new)
+            } else {
+                let (plus, minus) = graph(*added, *deleted);
+                format!(
+                    "{:>count_width$} {}{}",
+                    added + deleted,
+                    "+".repeat(plus),
+                    "-".repeat(minus),
+                )
+            };
+            format!(" {}{} | {}", name, padding, detail.trim_end())
+        })
+        .collect();
+    let (additions, deletions) = rows
+        .iter()
+        .fold((0, 0), |(a, d), (_, _, (added, deleted))| {
+            (a + added, d + deleted)
+        });
+    lines.push(shortstat(rows.len(), additions, deletions));
+    lines
+}
+
+// "old => new", with the directories both paths share outside braces: "src/{a => b}/mod.rs"
+fn rename_name(old: &str, new: &str) -> String {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    let prefix = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .filter(|(_, (a, _))| **a == b'/')
+        .last()
+        .map_or(0, |(index, _)| index + 1);
+    // A shared suffix starts at a slash, which may be the one ending the prefix
+    let lowest = prefix.saturating_sub(1);
+    let suffix = old_bytes
+        .iter()
+        .enumerate()
+        .rev()
+        .zip(new_bytes.iter().enumerate().rev())
+        .take_while(|((i, a), (j, b))| *i >= lowest && *j >= lowest && a == b)
+        .filter(|((_, a), _)| **a == b'/')
+        .last()
+        .map_or(0, |((index, _), _)| old.len() - index);
+    if prefix + suffix == 0 {
+        return format!("{} => {}", old, new);
+    }
+    let middle = |path: &str| {
+        let end = path.len().saturating_sub(suffix).max(prefix);
+        path[prefix..end].to_string()
+    };
+    format!(
+        "{}{{{} => {}}}{}",
+        &old[..prefix],
+        middle(old),
+        middle(new),
+        &old[old.len() - suffix..]
+    )
+}
+
+// Shorten a name to `width` columns like git, keeping its end from a directory on: ".../b/c.rs"
+fn truncate_name(name: &str, width: usize) -> String {
+    if name.width() <= width {
+        return name.to_string();
+    }
+    let keep = width.saturating_sub(3);
+    let mut start = name.len();
+    let mut tail_width = 0;
+    for (index, ch) in name.char_indices().rev() {
+        tail_width += ch.width().unwrap_or(0);
+        if tail_width > keep {
+            break;
+        }
+        start = index;
+    }
+    let tail = &name[start..];
+    let tail = tail.find('/').map_or(tail, |slash| &tail[slash..]);
+    format!("...{}", tail)
+}
+
+// `git log --oneline` lines, with HEAD on the branch the story committed to
+fn log_lines(history: &[GraphCommit], branch: &str) -> Vec<String> {
+    history
+        .iter()
+        .enumerate()
+        .map(|(index, commit)| {
+            let mut decorations = Vec::new();
+            let mut refs: Vec<_> = commit.refs.iter().collect();
+            if index == 0 {
+                if branch == "HEAD" {
+                    decorations.push("HEAD".to_string());
+                } else {
+                    decorations.push(format!("HEAD -> {}", branch));
+                    refs.retain(|r| !(r.kind == RefKind::Branch && r.name == branch));
+                }
+            }
+            refs.sort_by_key(|r| r.kind == RefKind::Branch);
+            decorations.extend(refs.iter().map(|r| match r.kind {
+                RefKind::Branch => r.name.clone(),
+                RefKind::Tag => format!("tag: {}", r.name),
+            }));
+
+            if decorations.is_empty() {
+                format!("{} {}", short_hash(&commit.hash), commit.summary)
+            } else {
+                format!(
+                    "{} ({}) {}",
+                    short_hash(&commit.hash),
+                    decorations.join(", "),
+                    commit.summary
+                )
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::TestRepo;
+    use crate::git::GitRepository;
+    use chrono::{DateTime, FixedOffset};
+
+    // The second commit of a new repository, with the branch it is on
+    fn context() -> (CommitMetadata, String, NarrativeContext) {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("a.txt", "one\n", "Add a");
+        test_repo.commit_file("a.txt", "one\ntwo\n", "Extend a\n\nWith a body");
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let context = NarrativeContext::new(&metadata, &[]);
+        let branch = git2::Repository::open(&test_repo.path)
+            .unwrap()
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+        (metadata, branch, context)
+    }
+
+    #[test]
+    fn test_unknown_placeholders_are_rejected() {
+        let error = Narrative::from_toml("intro = [\"$ echo {short_hash} {sumary}\"]")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Unknown placeholder {sumary}"), "{}", error);
+        assert!(Narrative::from_toml("outro = [\"{hash} {}\"]").is_err());
+    }
+
+    #[test]
+    fn test_block_placeholders_must_stand_alone() {
+        assert!(Narrative::from_toml("intro = [\"  {stat}  \", \"{log}\"]").is_ok());
+        let error = Narrative::from_toml("outro = [\"Changes: {stat}\"]")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("{stat} must be on a line of its own"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_unclosed_braces_are_kept_as_text() {
+        let narrative = Narrative::from_toml("intro = [\"$ echo {\", \"{hash\"]").unwrap();
+        assert_eq!(narrative.intro, ["$ echo {", "{hash"]);
+
+        let (_, _, context) = context();
+        assert_eq!(context.expand("$ echo {"), "$ echo {");
+        assert_eq!(context.expand("{hash"), "{hash");
+        assert_eq!(context.expand("{ {author"), "{ {author");
+    }
+
+    #[test]
+    fn test_expand_fills_in_placeholders() {
+        let (metadata, branch, context) = context();
+        assert_eq!(
+            context.expand("[{branch} {short_hash}] {summary}"),
+            format!("[{} {}] Extend a", branch, &metadata.hash[..7])
+        );
+        assert_eq!(
+            context.expand("{{hash}} {files},{additions},{deletions}"),
+            format!("{{{}}} 1 file,1,0", metadata.hash)
+        );
+        assert_eq!(
+            context.expand_lines("{stat}"),
+            [" a.txt | 1 +", " 1 file changed, 1 insertion(+)"]
+        );
+    }
+
+    #[test]
+    fn test_date_is_shown_in_the_author_timezone() {
+        let (mut metadata, _, _) = context();
+        metadata.date = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        metadata.utc_offset = FixedOffset::east_opt(-(5 * 3600 + 30 * 60)).unwrap();
+        let context = NarrativeContext::new(&metadata, &[]);
+        assert_eq!(context.expand("{date}"), "2023-11-14 16:43:20 -0530");
+    }
+
+    #[test]
+    fn test_extends_fills_in_unset_sections() {
+        let realistic = Narrative::load("realistic").unwrap();
+        let narrative =
+            Narrative::from_toml("extends = \"realistic\"\noutro = [\"$ git push\"]").unwrap();
+        assert_eq!(narrative.intro, realistic.intro);
+        assert_eq!(narrative.outro, ["$ git push"]);
+        assert_eq!(narrative.working_tree_outro, realistic.working_tree_outro);
+
+        // Without a base, unset sections stay empty
+        let narrative = Narrative::from_toml("outro = [\"$ git push\"]").unwrap();
+        assert!(narrative.intro.is_empty());
+        assert!(narrative.working_tree_intro.is_empty());
+
+        let error = Narrative::from_toml("extends = \"epic\"").unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown narrative: epic"));
+    }
+
+    fn head_stat(test_repo: &TestRepo) -> Vec<String> {
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        stat_lines(&repo.get_commit("HEAD").unwrap())
+    }
+
+    fn numbers(range: std::ops::RangeInclusive<usize>) -> String {
+        range.map(|n| format!("{}\n", n)).collect()
+    }
+
+    #[test]
+    fn test_stat_scales_the_graph_like_git() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_changes(
+            &[
+                ("small.txt", "a\nb\nc\n"),
+                ("big.txt", &numbers(1..=10)),
+                ("old.txt", "one\ntwo\nthree\nfour\nfive\n"),
+                ("image.bin", "\x00\x01\x02"),
+            ],
+            &[],
+            "Add",
+        );
+        test_repo.commit_changes(
+            &[
+                ("small.txt", "a\nB\nc\nd\n"),
+                ("big.txt", &numbers(1..=110)),
+                ("new.txt", "one\ntwo\nthree\nfour\nfive\n"),
+                ("image.bin", "\x00\x01\x02\x03\x04"),
+            ],
+            &["old.txt"],
+            "Change",
+        );
+
+        // `git show --stat --format=` of the same commit
+        let graph = "+".repeat(53);
+        assert_eq!(
+            head_stat(&test_repo),
+            [
+                format!(" big.txt            | 100 {}", graph).as_str(),
+                " image.bin          | Bin 3 -> 5 bytes",
+                " old.txt => new.txt |   0",
+                " small.txt          |   3 +-",
+                " 4 files changed, 102 insertions(+), 1 deletion(-)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stat_shortens_names_and_renames_like_git() {
+        let long = "src/very/long/directory/name/for/truncation/file_with_a_long_name.txt";
+        let test_repo = TestRepo::new();
+        test_repo.commit_changes(
+            &[
+                (long, &numbers(1..=5)),
+                ("a.txt", "x\n"),
+                ("b.bin", "\x00\x01"),
+                ("lib/old/mod.rs", &numbers(1..=20)),
+            ],
+            &[],
+            "Add",
+        );
+        test_repo.commit_changes(
+            &[
+                (long, &numbers(1..=7)),
+                ("a.txt", "y\nz\n"),
+                ("b.bin", &"\x00".repeat(12345)),
+                ("lib/new/mod.rs", &numbers(1..=20)),
+            ],
+            &["lib/old/mod.rs"],
+            "Change",
+        );
+
+        // `git show --stat --format=` of the same commit
+        assert_eq!(
+            head_stat(&test_repo),
+            [
+                " a.txt                                                   |   3 ++-",
+                " b.bin                                                   | Bin 2 -> 12345 bytes",
+                " lib/{old => new}/mod.rs                                 |   0",
+                " .../name/for/truncation/file_with_a_long_name.txt       |   2 ++",
+                " 4 files changed, 4 insertions(+), 1 deletion(-)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_names_keep_shared_directories_outside_braces() {
+        assert_eq!(rename_name("a.txt", "b.txt"), "a.txt => b.txt");
+        assert_eq!(rename_name("src/a.rs", "src/b.rs"), "src/{a.rs => b.rs}");
+        assert_eq!(rename_name("a/x/c.rs", "b/x/c.rs"), "{a => b}/x/c.rs");
+        assert_eq!(
+            rename_name("src/c.rs", "src/lib/c.rs"),
+            "src/{ => lib}/c.rs"
+        );
+    }
+}
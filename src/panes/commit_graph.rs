@@ -0,0 +1,288 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Padding},
+    Frame,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::git::{CommitGraph, CommitMetadata, GitRepository, GraphCommit, RefKind};
+use crate::theme::Theme;
+use crate::widgets::SelectableParagraph;
+
+// Commits shown on each side of the playing commit
+const GRAPH_CONTEXT: usize = 50;
+
+/// How a lane meets the commit's column in a graph row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connector {
+    /// Another child of the commit, ending here
+    Converge,
+    /// A lane starting here for a parent of a merge
+    Open,
+    /// A lane already waiting for a parent of a merge
+    Join,
+}
+
+pub struct CommitGraphPane {
+    cached_hash: Option<String>,
+    cached_lines: Vec<Line<'static>>,
+    cached_current_line_index: Option<usize>,
+}
+
+impl CommitGraphPane {
+    pub fn new() -> Self {
+        Self {
+            cached_hash: None,
+            cached_lines: Vec::new(),
+            cached_current_line_index: None,
+        }
+    }
+
+    /// Walks the history around a newly loaded commit.
+    pub fn set_commit_metadata(
+        &mut self,
+        metadata: &CommitMetadata,
+        theme: &Theme,
+        repo: Option<&GitRepository>,
+    ) {
+        if self.cached_hash.as_ref() == Some(&metadata.hash) {
+            return;
+        }
+
+        // Without a repository or a readable history the pane stays empty
+        let graph = repo
+            .and_then(|repo| repo.commit_graph(metadata, GRAPH_CONTEXT).ok())
+            .unwrap_or_default();
+        self.cached_lines = Self::build_graph_lines(&graph, theme);
+        self.cached_current_line_index = graph.current;
+        self.cached_hash = Some(metadata.hash.clone());
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default()
+            .style(Style::default().bg(theme.background_left))
+            .padding(Padding::vertical(1));
+
+        // Graph rows are cut at the pane width instead of wrapping
+        let width = area.width.saturating_sub(4) as usize;
+        let lines = if self.cached_lines.is_empty() {
+            vec![Line::from(Span::styled(
+                "No history",
+                Style::default().fg(theme.status_no_commit),
+            ))]
+        } else {
+            self.cached_lines
+                .iter()
+                .map(|line| truncate_line(line, width))
+                .collect()
+        };
+
+        let content = SelectableParagraph::new(lines)
+            .block(block)
+            .selected_line(self.cached_current_line_index)
+            .selected_style(Style::default().bg(theme.file_tree_current_file_bg))
+            .background_style(Style::default().bg(theme.background_left))
+            .padding(Padding::horizontal(2))
+            .dim(20, 0.6);
+        f.render_widget(content, area);
+    }
+
+    // One row per commit: lane glyphs, then refs, short hash and summary
+    fn build_graph_lines(graph: &CommitGraph, theme: &Theme) -> Vec<Line<'static>> {
+        let palette = [
+            theme.syntax_function,
+            theme.syntax_keyword,
+            theme.syntax_string,
+            theme.syntax_type,
+            theme.syntax_number,
+            theme.syntax_constant,
+        ];
+        let lane_color = |lane: usize| palette[lane % palette.len()];
+
+        // The commit each lane is waiting for
+        let mut lanes: Vec<Option<&str>> = Vec::new();
+        let mut lines = Vec::new();
+
+        for (index, commit) in graph.commits.iter().enumerate() {
+            let waiting: Vec<usize> = (0..lanes.len())
+                .filter(|&lane| lanes[lane] == Some(commit.hash.as_str()))
+                .collect();
+            let column = match waiting.first() {
+                Some(&lane) => lane,
+                // A branch tip, or a commit whose children are outside the graph
+                None => free_lane(&mut lanes, &[]),
+            };
+            let passing: Vec<bool> = lanes.iter().map(Option::is_some).collect();
+
+            let mut connectors: Vec<(usize, Connector)> = waiting
+                .iter()
+                .skip(1)
+                .map(|&lane| (lane, Connector::Converge))
+                .collect();
+            lanes[column] = commit.parents.first().map(String::as_str);
+            for parent in commit.parents.iter().skip(1) {
+                match lanes.iter().position(|lane| *lane == Some(parent.as_str())) {
+                    Some(lane) if lane != column => connectors.push((lane, Connector::Join)),
+                    _ => {
+                        // Lanes of converging children are only freed after this row
+                        let lane = free_lane(&mut lanes, &waiting);
+                        lanes[lane] = Some(parent.as_str());
+                        connectors.push((lane, Connector::Open));
+                    }
+                }
+            }
+            for &lane in waiting.iter().skip(1) {
+                lanes[lane] = None;
+            }
+
+            let is_current = graph.current == Some(index);
+            let mut spans = graph_spans(column, &connectors, &passing, is_current, &lane_color);
+            spans.extend(commit_spans(commit, is_current, theme));
+            lines.push(Line::from(spans));
+
+            while lanes.last() == Some(&None) {
+                lanes.pop();
+            }
+        }
+
+        lines
+    }
+}
+
+// Index of a lane free for a new line of history, skipping `reserved` ones
+fn free_lane(lanes: &mut Vec<Option<&str>>, reserved: &[usize]) -> usize {
+    match (0..lanes.len()).find(|lane| lanes[*lane].is_none() && !reserved.contains(lane)) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+// Draw the lanes of a row, joined to the commit by horizontal lines where they meet it
+fn graph_spans(
+    column: usize,
+    connectors: &[(usize, Connector)],
+    passing: &[bool],
+    is_current: bool,
+    lane_color: &dyn Fn(usize) -> Color,
+) -> Vec<Span<'static>> {
+    let lane_count = connectors
+        .iter()
+        .map(|(lane, _)| lane + 1)
+        .chain([passing.len(), column + 1])
+        .max()
+        .unwrap_or(0);
+    let low = connectors
+        .iter()
+        .map(|(lane, _)| *lane)
+        .fold(column, usize::min);
+    let high = connectors
+        .iter()
+        .map(|(lane, _)| *lane)
+        .fold(column, usize::max);
+    let is_passing = |lane: usize| passing.get(lane).copied().unwrap_or(false);
+
+    let mut spans = Vec::new();
+    for lane in 0..lane_count {
+        let connector = connectors.iter().find(|(l, _)| *l == lane).map(|(_, c)| *c);
+        let right = lane > column;
+        let (glyph, style) = if lane == column {
+            let style = Style::default().fg(lane_color(lane));
+            if is_current {
+                ("◉", style.add_modifier(Modifier::BOLD))
+            } else {
+                ("●", style)
+            }
+        } else if let Some(connector) = connector {
+            let glyph = match (connector, right) {
+                (Connector::Converge, true) => "╯",
+                (Connector::Converge, false) => "╰",
+                (Connector::Open, true) => "╮",
+                (Connector::Open, false) => "╭",
+                (Connector::Join, true) => "┤",
+                (Connector::Join, false) => "├",
+            };
+            (glyph, Style::default().fg(lane_color(lane)))
+        } else if lane > low && lane < high {
+            let glyph = if is_passing(lane) { "┼" } else { "─" };
+            (glyph, Style::default().fg(lane_color(column)))
+        } else if is_passing(lane) {
+            ("│", Style::default().fg(lane_color(lane)))
+        } else {
+            (" ", Style::default())
+        };
+        spans.push(Span::styled(glyph, style));
+
+        let gap = if lane >= low && lane < high {
+            "─"
+        } else {
+            " "
+        };
+        spans.push(Span::styled(gap, Style::default().fg(lane_color(column))));
+    }
+    spans
+}
+
+// Refs at the commit, its short hash and its summary
+fn commit_spans(commit: &GraphCommit, is_current: bool, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    if !commit.refs.is_empty() {
+        let names: Vec<String> = commit
+            .refs
+            .iter()
+            .map(|commit_ref| match commit_ref.kind {
+                RefKind::Branch => commit_ref.name.clone(),
+                RefKind::Tag => format!("tag: {}", commit_ref.name),
+            })
+            .collect();
+        spans.push(Span::styled(
+            format!("({}) ", names.join(", ")),
+            Style::default()
+                .fg(theme.status_hash)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(Span::styled(
+        format!("{} ", &commit.hash[..7.min(commit.hash.len())]),
+        Style::default().fg(theme.status_no_commit),
+    ));
+    let summary_style = if is_current {
+        Style::default()
+            .fg(theme.file_tree_current_file_fg)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.status_message)
+    };
+    spans.push(Span::styled(commit.summary.clone(), summary_style));
+    spans
+}
+
+// Cut a line to at most `width` columns
+fn truncate_line(line: &Line<'static>, width: usize) -> Line<'static> {
+    let mut used = 0;
+    let mut spans = Vec::new();
+    for span in &line.spans {
+        let span_width = span.content.width();
+        if used + span_width <= width {
+            used += span_width;
+            spans.push(span.clone());
+            continue;
+        }
+        let mut content = String::new();
+        for ch in span.content.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            if used + ch_width > width {
+                break;
+            }
+            used += ch_width;
+            content.push(ch);
+        }
+        spans.push(Span::styled(content, span.style));
+        break;
+    }
+    Line::from(spans).style(line.style)
+}
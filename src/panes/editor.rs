@@ -2,13 +2,17 @@ use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Padding},
+    widgets::{Block, Padding, Paragraph},
     Frame,
 };
 
-use crate::animation::{ActivePane, AnimationEngine};
+use crate::animation::{ActivePane, AnimationEngine, LineMark, LineStatus};
 use crate::theme::Theme;
-use crate::widgets::SelectableParagraph;
+use crate::widgets::{blend, SelectableParagraph};
+
+// Opacity of the typed line tint right after typing, fading to nothing
+const TYPED_LINE_TINT: f32 = 0.15;
+const TYPED_LINE_FADE_SECS: f32 = 3.0;
 
 pub struct EditorPane;
 
@@ -17,12 +21,8 @@ struct HighlightContext<'a> {
     line_num: usize,
     show_cursor: bool,
     cursor_col: usize,
-    cursor_line: usize,
-    old_highlights: &'a [crate::syntax::HighlightSpan],
-    new_highlights: &'a [crate::syntax::HighlightSpan],
-    old_line_offsets: &'a [usize],
-    new_line_offsets: &'a [usize],
-    line_offset: isize,
+    highlights: &'a [crate::syntax::HighlightSpan],
+    line_offsets: &'a [usize],
     theme: &'a Theme,
 }
 
@@ -33,6 +33,41 @@ impl EditorPane {
             .padding(Padding::vertical(1));
 
         let content_height = area.height.saturating_sub(2) as usize; // Subtract top and bottom padding
+
+        if let Some(preview) = &engine.preview {
+            let block = block.padding(Padding::new(2, 2, 1, 1));
+            let width = area.width.saturating_sub(4) as usize;
+            let lines = preview.render(width, content_height, theme);
+            f.render_widget(Paragraph::new(lines).block(block), area);
+            return;
+        }
+        if let Some(split) = &engine.split {
+            let block = block.padding(Padding::new(2, 2, 1, 1));
+            let width = area.width.saturating_sub(4) as usize;
+            let lines = split.render(width, content_height, theme);
+            f.render_widget(Paragraph::new(lines).block(block), area);
+            return;
+        }
+        // The overview takes the rightmost column, next to the text's own padding
+        let area = if engine.minimap() && area.width > 1 {
+            let minimap_area = Rect {
+                x: area.right() - 1,
+                width: 1,
+                ..area
+            };
+            let lines = self.build_minimap(engine, content_height, theme);
+            let minimap_block = Block::default()
+                .style(Style::default().bg(theme.background_right))
+                .padding(Padding::vertical(1));
+            f.render_widget(Paragraph::new(lines).block(minimap_block), minimap_area);
+            Rect {
+                width: area.width - 1,
+                ..area
+            }
+        } else {
+            area
+        };
+
         let scroll_offset = engine.buffer.scroll_offset;
         let buffer_lines = &engine.buffer.lines;
         let line_num_width = format!("{}", buffer_lines.len()).len().max(3);
@@ -85,8 +120,10 @@ impl EditorPane {
 
         spans.push(self.render_line_number(line_num, is_cursor_line, line_num_width, theme));
 
+        let mark = engine.buffer.marks.get(line_num);
+        spans.push(self.render_gutter_marker(mark, theme));
         spans.push(Span::styled(
-            "  ",
+            " ",
             Style::default().fg(theme.editor_separator),
         ));
 
@@ -98,18 +135,112 @@ impl EditorPane {
             line_num,
             show_cursor,
             cursor_col: engine.buffer.cursor_col,
-            cursor_line: engine.buffer.cursor_line,
-            old_highlights: &engine.buffer.old_highlights,
-            new_highlights: &engine.buffer.new_highlights,
-            old_line_offsets: &engine.buffer.old_content_line_offsets,
-            new_line_offsets: &engine.buffer.new_content_line_offsets,
-            line_offset: engine.line_offset,
+            highlights: &engine.buffer.cached_highlights,
+            line_offsets: &engine.buffer.line_offsets,
             theme,
         });
 
-        spans.extend(line_spans);
+        // Selected lines share the cursor line background
+        let is_selected = engine
+            .buffer
+            .selection
+            .is_some_and(|(start, end)| (start..=end).contains(&line_num));
+        if is_selected {
+            spans.extend(
+                line_spans
+                    .into_iter()
+                    .map(|span| span.patch_style(Style::default().bg(theme.editor_cursor_line_bg))),
+            );
+        } else {
+            spans.extend(line_spans);
+        }
+
+        let typed_line_bg = mark
+            .filter(|_| engine.highlight_typed_lines())
+            .and_then(|mark| self.typed_line_bg(mark, theme));
+        match typed_line_bg {
+            Some(bg) => Line::from(spans).style(Style::default().bg(bg)),
+            None => Line::from(spans),
+        }
+    }
+
+    // One row per slice of the buffer: the viewport, edited lines and hunks still to be typed
+    fn build_minimap(
+        &self,
+        engine: &AnimationEngine,
+        height: usize,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        let buffer = &engine.buffer;
+        let line_count = buffer.lines.len();
+        let viewport = buffer.scroll_offset..buffer.scroll_offset + height;
+        let rows = height.min(line_count);
+
+        (0..rows)
+            .map(|row| {
+                let start = row * line_count / rows;
+                let mut end = (row + 1) * line_count / rows;
+                // Hunks inserted at the end are marked on the entry after the last line
+                if row + 1 == rows {
+                    end += 1;
+                }
+                let marks = &buffer.marks[start..end.min(buffer.marks.len())];
+                let in_viewport = start < viewport.end && end > viewport.start;
+
+                let (symbol, color) = if marks.iter().any(|mark| mark.pending_hunk) {
+                    ("◆", theme.editor_line_number_cursor)
+                } else if marks.iter().any(|mark| mark.status == LineStatus::Added) {
+                    ("▐", theme.editor_gutter_added)
+                } else if marks.iter().any(|mark| mark.status == LineStatus::Modified) {
+                    ("▐", theme.editor_gutter_modified)
+                } else if marks.iter().any(|mark| mark.deleted_above > 0) {
+                    ("▾", theme.editor_gutter_deleted)
+                } else if in_viewport {
+                    ("┃", theme.editor_line_number)
+                } else {
+                    ("│", theme.editor_separator)
+                };
+                let bg = if in_viewport {
+                    theme.editor_cursor_line_bg
+                } else {
+                    theme.background_right
+                };
+                Line::from(Span::styled(symbol, Style::default().fg(color).bg(bg)))
+            })
+            .collect()
+    }
+
+    fn render_gutter_marker(&self, mark: Option<&LineMark>, theme: &Theme) -> Span<'static> {
+        let Some(mark) = mark else {
+            return Span::raw(" ");
+        };
+        match mark.status {
+            LineStatus::Added => Span::styled("+", Style::default().fg(theme.editor_gutter_added)),
+            LineStatus::Modified => {
+                Span::styled("~", Style::default().fg(theme.editor_gutter_modified))
+            }
+            LineStatus::Unchanged if mark.deleted_above > 0 => {
+                Span::styled("▾", Style::default().fg(theme.editor_gutter_deleted))
+            }
+            LineStatus::Unchanged => Span::raw(" "),
+        }
+    }
 
-        Line::from(spans)
+    // Tint of a recently typed line, fading into the editor background
+    fn typed_line_bg(&self, mark: &LineMark, theme: &Theme) -> Option<Color> {
+        // A transparent background cannot be blended with
+        if !matches!(theme.background_right, Color::Rgb(..)) {
+            return None;
+        }
+        let elapsed = mark.typed_at?.elapsed().as_secs_f32();
+        let fade = 1.0 - elapsed / TYPED_LINE_FADE_SECS;
+        (fade > 0.0).then(|| {
+            blend(
+                theme.editor_typed_line_bg,
+                TYPED_LINE_TINT * fade,
+                theme.background_right,
+            )
+        })
     }
 
     fn render_line_number(
@@ -134,75 +265,31 @@ impl EditorPane {
     }
 
     fn highlight_line(&self, ctx: HighlightContext<'_>) -> Vec<Span<'_>> {
-        let (highlights, line_offsets) = self.select_highlights_and_offsets(
-            ctx.line_num,
-            ctx.cursor_line,
-            ctx.old_highlights,
-            ctx.new_highlights,
-            ctx.old_line_offsets,
-            ctx.new_line_offsets,
-        );
-
-        let byte_offset = self.calculate_byte_offset(
-            ctx.line_num,
-            ctx.cursor_line,
-            ctx.line_offset,
-            line_offsets,
-        );
+        // Highlights are refreshed with the buffer, so each line maps to its own offset
+        let byte_offset = ctx
+            .line_offsets
+            .get(ctx.line_num)
+            .copied()
+            .unwrap_or_else(|| *ctx.line_offsets.last().unwrap_or(&0));
 
         let line_highlights =
-            self.filter_line_highlights(highlights, byte_offset, ctx.line_content.len());
+            self.filter_line_highlights(ctx.highlights, byte_offset, ctx.line_content.len());
 
         self.apply_highlights(&line_highlights, byte_offset, &ctx)
     }
 
-    fn select_highlights_and_offsets<'a>(
-        &self,
-        line_num: usize,
-        cursor_line: usize,
-        old_highlights: &'a [crate::syntax::HighlightSpan],
-        new_highlights: &'a [crate::syntax::HighlightSpan],
-        old_line_offsets: &'a [usize],
-        new_line_offsets: &'a [usize],
-    ) -> (&'a [crate::syntax::HighlightSpan], &'a [usize]) {
-        if line_num <= cursor_line {
-            (new_highlights, new_line_offsets)
-        } else {
-            (old_highlights, old_line_offsets)
-        }
-    }
-
-    fn calculate_byte_offset(
-        &self,
-        line_num: usize,
-        cursor_line: usize,
-        line_offset: isize,
-        line_offsets: &[usize],
-    ) -> usize {
-        let target_line = if line_num > cursor_line {
-            ((line_num as isize) - line_offset).max(0) as usize
-        } else {
-            line_num
-        };
-
-        line_offsets
-            .get(target_line)
-            .copied()
-            .unwrap_or_else(|| *line_offsets.last().unwrap_or(&0))
-    }
-
     fn filter_line_highlights(
         &self,
         highlights: &[crate::syntax::HighlightSpan],
         byte_offset: usize,
         line_len: usize,
-    ) -> Vec<(usize, usize, crate::syntax::TokenType)> {
+    ) -> Vec<(usize, usize, crate::syntax::TokenType, &'static str)> {
         let line_end = byte_offset + line_len;
         highlights
             .iter()
             .filter_map(|h| {
                 if h.start < line_end && h.end > byte_offset {
-                    Some((h.start, h.end, h.token_type))
+                    Some((h.start, h.end, h.token_type, h.language))
                 } else {
                     None
                 }
@@ -212,7 +299,7 @@ impl EditorPane {
 
     fn apply_highlights(
         &self,
-        line_highlights: &[(usize, usize, crate::syntax::TokenType)],
+        line_highlights: &[(usize, usize, crate::syntax::TokenType, &'static str)],
         byte_offset: usize,
         ctx: &HighlightContext,
     ) -> Vec<Span<'_>> {
@@ -260,13 +347,13 @@ impl EditorPane {
         &self,
         char_byte_start: usize,
         char_byte_end: usize,
-        line_highlights: &[(usize, usize, crate::syntax::TokenType)],
+        line_highlights: &[(usize, usize, crate::syntax::TokenType, &'static str)],
         theme: &Theme,
     ) -> Color {
         line_highlights
             .iter()
             .find(|h| char_byte_start >= h.0 && char_byte_end <= h.1)
-            .map(|h| h.2.color(theme))
+            .map(|h| h.2.color_for(theme, h.3))
             .unwrap_or(theme.syntax_variable) // Use theme color instead of Color::White
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use ratatui::{
     layout::Rect,
@@ -8,18 +8,69 @@ use ratatui::{
     Frame,
 };
 
-use crate::git::{CommitMetadata, LineChangeType};
+use crate::git::{CommitMetadata, FileChange, GitRepository, LineChangeType};
+use crate::icons::FileIcons;
 use crate::theme::Theme;
 use crate::widgets::SelectableParagraph;
 
 type FileEntry = (usize, String, String, Color, usize, usize);
 type FileTree = BTreeMap<String, Vec<FileEntry>>;
 
+/// Which files the file tree lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileTreeMode {
+    /// Only the changed files, grouped by directory
+    #[default]
+    Changes,
+    /// Every file in the commit's tree, expanded along the changed paths until toggled
+    Repo,
+}
+
+/// A directory of the repository tree with the changes below it
+#[derive(Default)]
+struct DirNode {
+    dirs: BTreeMap<String, DirNode>,
+    /// File names with the index of their change, if any
+    files: BTreeMap<String, Option<usize>>,
+    /// Indices of the changes anywhere below this directory
+    changes: Vec<usize>,
+}
+
+impl DirNode {
+    fn insert(&mut self, path: &str, change: Option<usize>) {
+        let mut node = self;
+        let mut parts = path.split('/').peekable();
+        while let Some(part) = parts.next() {
+            if let Some(index) = change {
+                node.changes.push(index);
+            }
+            if parts.peek().is_none() {
+                // A changed file keeps its index when the tree also lists it
+                let entry = node.files.entry(part.to_string()).or_default();
+                *entry = entry.or(change);
+                return;
+            }
+            node = node.dirs.entry(part.to_string()).or_default();
+        }
+    }
+}
+
 pub struct FileTreePane {
     cached_lines: Vec<Line<'static>>,
     cached_current_line_index: Option<usize>,
+    /// Change index of the file on each cached line
+    cached_line_files: Vec<Option<usize>>,
+    /// Path of the directory on cached lines that show one, in repo mode
+    cached_line_dirs: HashMap<usize, String>,
+    /// Directories the user expanded or collapsed in the repo tree of the cached commit
+    toggled_dirs: HashSet<String>,
     cached_metadata_id: Option<String>,
     cached_current_file_index: Option<usize>,
+    mode: FileTreeMode,
+    /// Files in the tree of the cached commit, in repo mode
+    repo_paths: Vec<String>,
+    /// Nerd Font glyphs shown before entries, when enabled
+    icons: Option<FileIcons>,
 }
 
 impl FileTreePane {
@@ -27,36 +78,110 @@ impl FileTreePane {
         Self {
             cached_lines: vec![Line::from("No commit loaded")],
             cached_current_line_index: None,
+            cached_line_files: Vec::new(),
+            cached_line_dirs: HashMap::new(),
+            toggled_dirs: HashSet::new(),
             cached_metadata_id: None,
             cached_current_file_index: None,
+            mode: FileTreeMode::default(),
+            repo_paths: Vec::new(),
+            icons: None,
         }
     }
 
+    pub fn set_mode(&mut self, mode: FileTreeMode) {
+        self.mode = mode;
+    }
+
+    pub fn set_icons(&mut self, icons: Option<FileIcons>) {
+        self.icons = icons;
+    }
+
     pub fn set_commit_metadata(
         &mut self,
         metadata: &CommitMetadata,
         current_file_index: usize,
         theme: &Theme,
+        repo: Option<&GitRepository>,
     ) {
         let metadata_id = metadata.hash.clone();
 
         // Only recalculate if metadata or current file changed
-        if self.cached_metadata_id.as_ref() == Some(&metadata_id)
-            && self.cached_current_file_index == Some(current_file_index)
-        {
+        let metadata_changed = self.cached_metadata_id.as_ref() != Some(&metadata_id);
+        if !metadata_changed && self.cached_current_file_index == Some(current_file_index) {
             return;
         }
 
-        let (lines, current_line_index) =
-            Self::build_tree_lines(metadata, current_file_index, theme);
+        if metadata_changed && self.mode == FileTreeMode::Repo {
+            // Without the tree, only the changed files are listed
+            self.repo_paths = repo
+                .and_then(|repo| repo.tree_paths(metadata).ok())
+                .unwrap_or_default();
+            self.toggled_dirs.clear();
+        }
+
+        let icons = self.icons.as_ref();
+        let (lines, line_files, current_line_index) = match self.mode {
+            FileTreeMode::Changes => {
+                self.cached_line_dirs.clear();
+                Self::build_tree_lines(metadata, current_file_index, icons, theme)
+            }
+            FileTreeMode::Repo => {
+                let mut builder = RepoTreeBuilder {
+                    metadata,
+                    current_file_index,
+                    icons,
+                    theme,
+                    toggled_dirs: &self.toggled_dirs,
+                    lines: Vec::new(),
+                    line_files: Vec::new(),
+                    line_dirs: HashMap::new(),
+                    current_line_index: None,
+                };
+                builder.push_dir(&Self::repo_tree(metadata, &self.repo_paths), "", 0);
+                self.cached_line_dirs = builder.line_dirs;
+                (
+                    builder.lines,
+                    builder.line_files,
+                    builder.current_line_index,
+                )
+            }
+        };
 
         self.cached_lines = lines;
+        self.cached_line_files = line_files;
         self.cached_current_line_index = current_line_index;
         self.cached_metadata_id = Some(metadata_id);
         self.cached_current_file_index = Some(current_file_index);
     }
 
     pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        f.render_widget(self.paragraph(theme), area);
+    }
+
+    /// Change index of the file shown at a screen position, if any.
+    pub fn file_at(&self, area: Rect, column: u16, row: u16, theme: &Theme) -> Option<usize> {
+        let (line, _) = self.paragraph(theme).line_at(area, column, row)?;
+        self.cached_line_files.get(line).copied().flatten()
+    }
+
+    /// Expands or collapses the repo tree directory shown at a screen position.
+    /// Returns whether there was one; the tree is rebuilt on the next update.
+    pub fn toggle_dir_at(&mut self, area: Rect, column: u16, row: u16, theme: &Theme) -> bool {
+        let Some((line, _)) = self.paragraph(theme).line_at(area, column, row) else {
+            return false;
+        };
+        let Some(path) = self.cached_line_dirs.get(&line) else {
+            return false;
+        };
+        if !self.toggled_dirs.remove(path) {
+            self.toggled_dirs.insert(path.clone());
+        }
+        self.cached_current_file_index = None;
+        true
+    }
+
+    fn paragraph(&self, theme: &Theme) -> SelectableParagraph<'static> {
         let block = Block::default()
             .style(Style::default().bg(theme.background_left))
             .padding(Padding {
@@ -66,45 +191,27 @@ impl FileTreePane {
                 bottom: 1,
             });
 
-        let content = SelectableParagraph::new(self.cached_lines.clone())
+        SelectableParagraph::new(self.cached_lines.clone())
             .block(block)
             .selected_line(self.cached_current_line_index)
             .selected_style(Style::default().bg(theme.file_tree_current_file_bg))
             .background_style(Style::default().bg(theme.background_left))
             .padding(Padding::horizontal(2))
-            .dim(20, 0.6);
-        f.render_widget(content, area);
+            .dim(20, 0.6)
     }
 
     fn build_tree_lines(
         metadata: &CommitMetadata,
         current_file_index: usize,
+        icons: Option<&FileIcons>,
         theme: &Theme,
-    ) -> (Vec<Line<'static>>, Option<usize>) {
+    ) -> (Vec<Line<'static>>, Vec<Option<usize>>, Option<usize>) {
         // Build directory tree
         let mut tree: FileTree = BTreeMap::new();
 
         for (index, change) in metadata.changes.iter().enumerate() {
-            let (status_char, color) = match change.status.as_str() {
-                "A" => ("+", theme.file_tree_added),
-                "D" => ("-", theme.file_tree_deleted),
-                "M" => ("~", theme.file_tree_modified),
-                "R" => (">", theme.file_tree_renamed),
-                _ => (" ", theme.file_tree_default),
-            };
-
-            // Count additions and deletions
-            let mut additions = 0;
-            let mut deletions = 0;
-            for hunk in &change.hunks {
-                for line in &hunk.lines {
-                    match line.change_type {
-                        LineChangeType::Addition => additions += 1,
-                        LineChangeType::Deletion => deletions += 1,
-                        _ => {}
-                    }
-                }
-            }
+            let (status_char, color) = Self::status_marker(change, theme);
+            let (additions, deletions) = Self::count_lines(change);
 
             let parts: Vec<&str> = change.path.split('/').collect();
             if parts.len() == 1 {
@@ -133,6 +240,7 @@ impl FileTreePane {
         }
 
         let mut lines = Vec::new();
+        let mut line_files = Vec::new();
         let mut current_line_index = None;
         let sorted_dirs: Vec<_> = tree.keys().cloned().collect();
 
@@ -143,7 +251,10 @@ impl FileTreePane {
 
             // Add directory header if not root
             if !dir.is_empty() {
-                let dir_text = format!("{}/", dir);
+                let dir_text = match icons {
+                    Some(icons) => format!("{} {}/", icons.directory(true), dir),
+                    None => format!("{}/", dir),
+                };
                 let dir_spans = vec![Span::styled(
                     dir_text,
                     Style::default()
@@ -151,6 +262,7 @@ impl FileTreePane {
                         .add_modifier(Modifier::BOLD),
                 )];
                 lines.push(Line::from(dir_spans));
+                line_files.push(None);
             }
 
             // Add files
@@ -179,12 +291,15 @@ impl FileTreePane {
                     Modifier::empty()
                 };
 
-                let spans = vec![
+                let mut spans = vec![
                     Span::raw(indent),
                     Span::styled(
                         status_str,
                         Style::default().fg(*color).add_modifier(Modifier::BOLD),
                     ),
+                ];
+                spans.extend(Self::file_icon(icons, filename, theme));
+                spans.extend([
                     Span::styled(
                         filename.to_string(),
                         Style::default().fg(fg_color).add_modifier(modifier),
@@ -197,12 +312,238 @@ impl FileTreePane {
                         deletions_str,
                         Style::default().fg(theme.file_tree_stats_deleted),
                     ),
-                ];
+                ]);
 
                 lines.push(Line::from(spans));
+                line_files.push(Some(*index));
+            }
+        }
+
+        (lines, line_files, current_line_index)
+    }
+
+    fn repo_tree(metadata: &CommitMetadata, repo_paths: &[String]) -> DirNode {
+        let mut root = DirNode::default();
+        for path in repo_paths {
+            root.insert(path, None);
+        }
+        // Deleted files are no longer in the tree but are still listed
+        for (index, change) in metadata.changes.iter().enumerate() {
+            root.insert(&change.path, Some(index));
+        }
+        root
+    }
+
+    fn file_icon(icons: Option<&FileIcons>, name: &str, theme: &Theme) -> Option<Span<'static>> {
+        let icon = icons?.file(name);
+        Some(Span::styled(
+            format!("{} ", icon.glyph),
+            Style::default().fg(icon.color.unwrap_or(theme.file_tree_default)),
+        ))
+    }
+
+    fn status_marker(change: &FileChange, theme: &Theme) -> (&'static str, Color) {
+        match change.status.as_str() {
+            "A" => ("+", theme.file_tree_added),
+            "D" => ("-", theme.file_tree_deleted),
+            "M" => ("~", theme.file_tree_modified),
+            "R" => (">", theme.file_tree_renamed),
+            _ => (" ", theme.file_tree_default),
+        }
+    }
+
+    // Count additions and deletions
+    fn count_lines(change: &FileChange) -> (usize, usize) {
+        let mut additions = 0;
+        let mut deletions = 0;
+        for hunk in &change.hunks {
+            for line in &hunk.lines {
+                match line.change_type {
+                    LineChangeType::Addition => additions += 1,
+                    LineChangeType::Deletion => deletions += 1,
+                    _ => {}
+                }
             }
         }
+        (additions, deletions)
+    }
+}
+
+/// Lines of the repo tree, built directory by directory
+struct RepoTreeBuilder<'a> {
+    metadata: &'a CommitMetadata,
+    current_file_index: usize,
+    icons: Option<&'a FileIcons>,
+    theme: &'a Theme,
+    /// Directories whose default expansion the user flipped
+    toggled_dirs: &'a HashSet<String>,
+    lines: Vec<Line<'static>>,
+    line_files: Vec<Option<usize>>,
+    line_dirs: HashMap<usize, String>,
+    current_line_index: Option<usize>,
+}
+
+impl RepoTreeBuilder<'_> {
+    // Directories first, expanded when they contain changes unless toggled, then files
+    fn push_dir(&mut self, node: &DirNode, prefix: &str, depth: usize) {
+        let indent = "  ".repeat(depth);
+
+        for (name, dir) in &node.dirs {
+            let path = format!("{}{}/", prefix, name);
+            let expanded = dir.changes.is_empty() == self.toggled_dirs.contains(&path);
+            // Mixed changes below a directory are shown as modified
+            let (status_char, color) = match dir.changes.first() {
+                None => (" ", self.theme.file_tree_default),
+                Some(&first) => {
+                    let marker =
+                        FileTreePane::status_marker(&self.metadata.changes[first], self.theme);
+                    if dir.changes.iter().all(|&index| {
+                        FileTreePane::status_marker(&self.metadata.changes[index], self.theme)
+                            == marker
+                    }) {
+                        marker
+                    } else {
+                        ("~", self.theme.file_tree_modified)
+                    }
+                }
+            };
+            let arrow = match self.icons {
+                Some(icons) => format!(
+                    "{} {} ",
+                    if expanded { "▾" } else { "▸" },
+                    icons.directory(expanded)
+                ),
+                None => if expanded { "▾ " } else { "▸ " }.to_string(),
+            };
+
+            self.line_dirs.insert(self.lines.len(), path.clone());
+            self.lines.push(Line::from(vec![
+                Span::raw(indent.clone()),
+                Span::styled(
+                    format!("{} ", status_char),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{}{}/", arrow, name),
+                    Style::default()
+                        .fg(self.theme.file_tree_directory)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+            self.line_files.push(None);
+
+            if expanded {
+                self.push_dir(dir, &path, depth + 1);
+            }
+        }
+
+        for (name, change_index) in &node.files {
+            let Some(index) = *change_index else {
+                let mut spans = vec![Span::raw(format!("{}  ", indent))];
+                spans.extend(FileTreePane::file_icon(self.icons, name, self.theme));
+                spans.push(Span::styled(
+                    name.clone(),
+                    Style::default().fg(self.theme.file_tree_default),
+                ));
+                self.lines.push(Line::from(spans));
+                self.line_files.push(None);
+                continue;
+            };
+
+            let change = &self.metadata.changes[index];
+            let is_current = index == self.current_file_index;
+            if is_current {
+                self.current_line_index = Some(self.lines.len());
+            }
+            let (status_char, color) = FileTreePane::status_marker(change, self.theme);
+            let (additions, deletions) = FileTreePane::count_lines(change);
+            let (fg_color, modifier) = if is_current {
+                (self.theme.file_tree_current_file_fg, Modifier::BOLD)
+            } else {
+                (self.theme.file_tree_default, Modifier::empty())
+            };
+
+            let mut spans = vec![
+                Span::raw(indent.clone()),
+                Span::styled(
+                    format!("{} ", status_char),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ];
+            spans.extend(FileTreePane::file_icon(self.icons, name, self.theme));
+            spans.extend([
+                Span::styled(
+                    name.clone(),
+                    Style::default().fg(fg_color).add_modifier(modifier),
+                ),
+                Span::styled(
+                    format!(" +{}", additions),
+                    Style::default().fg(self.theme.file_tree_stats_added),
+                ),
+                Span::styled(
+                    format!(" -{}", deletions),
+                    Style::default().fg(self.theme.file_tree_stats_deleted),
+                ),
+            ]);
+            self.lines.push(Line::from(spans));
+            self.line_files.push(Some(index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::TestRepo;
+
+    const AREA: Rect = Rect::new(0, 0, 40, 20);
+
+    fn texts(pane: &FileTreePane) -> Vec<String> {
+        pane.cached_lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Clicks the line with the given text, below the top padding
+    fn click(pane: &mut FileTreePane, text: &str, theme: &Theme) -> bool {
+        let line = texts(pane).iter().position(|t| t == text).unwrap();
+        pane.toggle_dir_at(AREA, 4, 1 + line as u16, theme)
+    }
+
+    #[test]
+    fn test_repo_tree_directories_toggle_on_click() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_files(
+            &[("src/main.rs", "fn main() {}\n"), ("docs/a.md", "# A\n")],
+            "Add",
+        );
+        test_repo.commit_file("src/main.rs", "fn main() { run() }\n", "Change");
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        let metadata = repo.get_commit("HEAD").unwrap();
+        let theme = Theme::default();
+
+        let mut pane = FileTreePane::new();
+        pane.set_mode(FileTreeMode::Repo);
+        pane.set_commit_metadata(&metadata, 0, &theme, Some(&repo));
+        assert_eq!(texts(&pane), ["  ▸ docs/", "~ ▾ src/", "  ~ main.rs +1 -1"]);
+
+        assert!(click(&mut pane, "  ▸ docs/", &theme));
+        assert!(click(&mut pane, "~ ▾ src/", &theme));
+        pane.set_commit_metadata(&metadata, 0, &theme, Some(&repo));
+        assert_eq!(texts(&pane), ["  ▾ docs/", "    a.md", "~ ▸ src/"]);
+        assert_eq!(pane.cached_current_line_index, None);
 
-        (lines, current_line_index)
+        // Files are not directories, and a new commit starts from the default expansion
+        assert!(!click(&mut pane, "    a.md", &theme));
+        let parent = repo.get_commit("HEAD~1").unwrap();
+        pane.set_commit_metadata(&parent, 0, &theme, Some(&repo));
+        pane.set_commit_metadata(&metadata, 0, &theme, Some(&repo));
+        assert_eq!(texts(&pane), ["  ▸ docs/", "~ ▾ src/", "  ~ main.rs +1 -1"]);
     }
 }
@@ -1,9 +1,11 @@
+mod commit_graph;
 mod editor;
 mod file_tree;
 mod status_bar;
 mod terminal;
 
+pub use commit_graph::CommitGraphPane;
 pub use editor::EditorPane;
-pub use file_tree::FileTreePane;
+pub use file_tree::{FileTreeMode, FileTreePane};
 pub use status_bar::StatusBarPane;
 pub use terminal::TerminalPane;
@@ -1,60 +1,135 @@
+use chrono::{DateTime, Utc};
 use ratatui::{
     layout::Rect,
-    style::Style,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Padding},
     Frame,
 };
 
-use crate::git::CommitMetadata;
+use crate::animation::AnimationEngine;
+use crate::git::{CommitMetadata, LineChangeType, RefKind};
 use crate::theme::Theme;
 use crate::widgets::SelectableParagraph;
 
-pub struct StatusBarPane;
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+// Branches and tags listed before the rest are summarized as a count
+const MAX_REFS: usize = 3;
+const HASH_LABEL: &str = "hash: ";
+const SHORT_HASH_LEN: usize = 7;
+
+pub struct StatusBarPane {
+    date_format: String,
+}
 
 impl StatusBarPane {
-    pub fn render(
+    pub fn new() -> Self {
+        Self {
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+        }
+    }
+
+    /// Sets the strftime format of commit dates, which must be valid.
+    pub fn set_date_format(&mut self, format: String) {
+        self.date_format = format;
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, engine: &AnimationEngine, theme: &Theme) {
+        f.render_widget(self.paragraph(area, engine, theme), area);
+    }
+
+    /// Full hash of the commit when a screen position is on its short hash.
+    pub fn hash_at(
         &self,
-        f: &mut Frame,
         area: Rect,
-        metadata: Option<&CommitMetadata>,
+        column: u16,
+        row: u16,
+        engine: &AnimationEngine,
         theme: &Theme,
-    ) {
+    ) -> Option<String> {
+        let meta = engine.current_metadata()?;
+        if meta.hash == "working-tree" {
+            return None;
+        }
+        let (line, offset) = self
+            .paragraph(area, engine, theme)
+            .line_at(area, column, row)?;
+        let start = HASH_LABEL.len();
+        (line == 0 && (start..start + SHORT_HASH_LEN).contains(&offset)).then(|| meta.hash.clone())
+    }
+
+    fn paragraph<'a>(
+        &self,
+        area: Rect,
+        engine: &'a AnimationEngine,
+        theme: &Theme,
+    ) -> SelectableParagraph<'a> {
         let block = Block::default()
             .style(Style::default().bg(theme.background_left))
             .padding(Padding::vertical(1));
 
-        let status_text = if let Some(meta) = metadata {
+        let status_text = if let Some(meta) = engine.current_metadata() {
             let is_working_tree = meta.hash == "working-tree";
             let hash_display = if is_working_tree {
                 "working"
             } else {
-                &meta.hash[..7.min(meta.hash.len())]
+                &meta.hash[..SHORT_HASH_LEN.min(meta.hash.len())]
             };
 
+            let mut hash_line = vec![
+                Span::raw(HASH_LABEL),
+                Span::styled(hash_display, Style::default().fg(theme.status_hash)),
+            ];
+            hash_line.extend(self.refs_spans(meta, theme));
+
+            let author = if meta.author_email.is_empty() {
+                meta.author.clone()
+            } else {
+                format!("{} <{}>", meta.author, meta.author_email)
+            };
             let mut lines = vec![
-                Line::from(vec![
-                    Span::raw("hash: "),
-                    Span::styled(hash_display, Style::default().fg(theme.status_hash)),
-                ]),
+                Line::from(hash_line),
                 Line::from(vec![
                     Span::raw("author: "),
-                    Span::styled(&meta.author, Style::default().fg(theme.status_author)),
+                    Span::styled(author, Style::default().fg(theme.status_author)),
                 ]),
             ];
+            if let Some(committer) = &meta.committer {
+                lines.push(Line::from(vec![
+                    Span::raw("committer: "),
+                    Span::styled(committer.as_str(), Style::default().fg(theme.status_author)),
+                ]));
+            }
+            for co_author in &meta.co_authors {
+                lines.push(Line::from(vec![
+                    Span::raw("co-author: "),
+                    Span::styled(co_author.as_str(), Style::default().fg(theme.status_author)),
+                ]));
+            }
 
             // Only show date for actual commits (not working tree)
             if !is_working_tree {
-                let date_str = meta.date.format("%Y-%m-%d %H:%M:%S").to_string();
+                let date_str = format!(
+                    "{} ({})",
+                    meta.date.format(&self.date_format),
+                    relative_date(meta.date, Utc::now())
+                );
                 lines.push(Line::from(vec![
                     Span::raw("date: "),
                     Span::styled(date_str, Style::default().fg(theme.status_date)),
                 ]));
             }
 
-            // Add commit message lines (skip empty lines)
+            lines.push(self.stats_line(meta, engine, theme));
+            let bar_width = area.width.saturating_sub(4) as usize;
+            lines.push(self.progress_line(engine.progress(), bar_width, theme));
+
+            // Add commit message lines (skip empty lines and the co-author trailers shown above)
             for msg_line in meta.message.lines() {
-                if !msg_line.trim().is_empty() {
+                let is_co_author = msg_line
+                    .split_once(':')
+                    .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case("co-authored-by"));
+                if !msg_line.trim().is_empty() && !is_co_author {
                     lines.push(Line::from(vec![Span::styled(
                         msg_line,
                         Style::default().fg(theme.status_message),
@@ -70,11 +145,117 @@ impl StatusBarPane {
             )])]
         };
 
-        let content = SelectableParagraph::new(status_text)
+        SelectableParagraph::new(status_text)
             .block(block)
             .background_style(Style::default().bg(theme.background_left))
-            .padding(Padding::horizontal(2));
+            .padding(Padding::horizontal(2))
+    }
+
+    // Refs at the commit are highlighted, refs that only contain it are muted
+    fn refs_spans<'a>(&self, meta: &'a CommitMetadata, theme: &Theme) -> Vec<Span<'a>> {
+        let mut refs: Vec<_> = meta.refs.iter().collect();
+        refs.sort_by_key(|r| (!r.exact, r.kind == RefKind::Tag));
+
+        let mut spans = Vec::new();
+        for (i, commit_ref) in refs.iter().take(MAX_REFS).enumerate() {
+            spans.push(Span::raw(if i == 0 { "  " } else { ", " }));
+            let name = match commit_ref.kind {
+                RefKind::Branch => commit_ref.name.clone(),
+                RefKind::Tag => format!("tag: {}", commit_ref.name),
+            };
+            let style = if commit_ref.exact {
+                Style::default()
+                    .fg(theme.status_hash)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.status_no_commit)
+            };
+            spans.push(Span::styled(name, style));
+        }
+        if refs.len() > MAX_REFS {
+            spans.push(Span::styled(
+                format!(" +{}", refs.len() - MAX_REFS),
+                Style::default().fg(theme.status_no_commit),
+            ));
+        }
+        spans
+    }
 
-        f.render_widget(content, area);
+    // "file 3/12  +120 -45", with the position of the open file in playback order
+    fn stats_line(
+        &self,
+        meta: &CommitMetadata,
+        engine: &AnimationEngine,
+        theme: &Theme,
+    ) -> Line<'static> {
+        let (additions, deletions) = meta
+            .changes
+            .iter()
+            .flat_map(|change| &change.hunks)
+            .flat_map(|hunk| &hunk.lines)
+            .fold((0, 0), |(added, deleted), line| match line.change_type {
+                LineChangeType::Addition => (added + 1, deleted),
+                LineChangeType::Deletion => (added, deleted + 1),
+                LineChangeType::Context => (added, deleted),
+            });
+
+        let total = meta.changes.len();
+        let files = match engine.current_file_path {
+            Some(_) => {
+                let position = meta
+                    .sorted_file_indices()
+                    .iter()
+                    .position(|&index| index == engine.current_file_index)
+                    .map_or(0, |position| position + 1);
+                format!("file {}/{}", position, total)
+            }
+            None => format!("{} files", total),
+        };
+
+        Line::from(vec![
+            Span::styled(files, Style::default().fg(theme.status_date)),
+            Span::styled(
+                format!("  +{}", additions),
+                Style::default().fg(theme.file_tree_stats_added),
+            ),
+            Span::styled(
+                format!(" -{}", deletions),
+                Style::default().fg(theme.file_tree_stats_deleted),
+            ),
+        ])
+    }
+
+    fn progress_line(&self, progress: f64, width: usize, theme: &Theme) -> Line<'static> {
+        let label = format!(" {:>3}%", (progress * 100.0).round() as usize);
+        let bar_width = width.saturating_sub(label.len());
+        let filled = ((bar_width as f64) * progress).round() as usize;
+        Line::from(vec![
+            Span::styled("━".repeat(filled), Style::default().fg(theme.status_hash)),
+            Span::styled(
+                "─".repeat(bar_width - filled),
+                Style::default().fg(theme.separator),
+            ),
+            Span::styled(label, Style::default().fg(theme.status_no_commit)),
+        ])
+    }
+}
+
+// Describe how long ago a date was, e.g. "3 years ago"
+fn relative_date(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - date).num_seconds();
+    if seconds < 60 {
+        return "just now".to_string();
+    }
+    let (value, unit) = match seconds {
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s if s < 86_400 * 30 => (s / 86_400, "day"),
+        s if s < 86_400 * 365 => (s / (86_400 * 30), "month"),
+        s => (s / (86_400 * 365), "year"),
+    };
+    if value == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", value, unit)
     }
 }
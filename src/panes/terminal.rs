@@ -5,8 +5,9 @@ use ratatui::{
     widgets::{Block, Padding},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::animation::{ActivePane, AnimationEngine};
+use crate::animation::{ActivePane, AnimationEngine, TerminalLineKind};
 use crate::theme::Theme;
 use crate::widgets::SelectableParagraph;
 
@@ -20,25 +21,39 @@ impl TerminalPane {
 
         // Get visible lines based on area height (subtract padding)
         let content_height = area.height.saturating_sub(2) as usize; // Subtract top and bottom padding
-        let total_lines = engine.terminal_lines.len();
+        let content_width = area.width.saturating_sub(4) as usize;
+        // Lines scrolled back in the history end the view above the latest line
+        let total_lines = engine
+            .terminal_lines
+            .len()
+            .saturating_sub(engine.terminal_scroll);
 
         let lines: Vec<Line> = if total_lines > 0 {
             let start_idx = total_lines.saturating_sub(content_height);
-            engine.terminal_lines[start_idx..]
+            engine.terminal_lines[start_idx..total_lines]
                 .iter()
                 .enumerate()
                 .map(|(idx, line)| {
-                    let is_last_line = start_idx + idx == total_lines - 1;
+                    let is_last_line = start_idx + idx == engine.terminal_lines.len() - 1;
                     let show_cursor = is_last_line
                         && engine.cursor_visible
                         && engine.active_pane == ActivePane::Terminal;
 
-                    if line.starts_with("~ ") {
+                    let text = &line.text;
+                    if line.kind == TerminalLineKind::Divider {
+                        // Divider between commits, drawn across the pane
+                        let text = format!("── {} ", text);
+                        let fill = content_width.saturating_sub(text.width());
+                        Line::from(vec![
+                            Span::styled(text, Style::default().fg(theme.status_no_commit)),
+                            Span::styled("─".repeat(fill), Style::default().fg(theme.separator)),
+                        ])
+                    } else if line.kind == TerminalLineKind::Command {
                         // Command line
                         if show_cursor {
                             // Add cursor at the end of the line
                             let mut spans = vec![Span::styled(
-                                line.clone(),
+                                text.clone(),
                                 Style::default().fg(theme.terminal_command),
                             )];
                             spans.push(Span::styled(
@@ -51,14 +66,14 @@ impl TerminalPane {
                             Line::from(spans)
                         } else {
                             Line::from(vec![Span::styled(
-                                line.clone(),
+                                text.clone(),
                                 Style::default().fg(theme.terminal_command),
                             )])
                         }
                     } else {
                         // Output line - normal style
                         Line::from(vec![Span::styled(
-                            line.clone(),
+                            text.clone(),
                             Style::default().fg(theme.terminal_output),
                         )])
                     }
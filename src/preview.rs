@@ -0,0 +1,439 @@
+use image::{ImageFormat, ImageReader, Limits, RgbaImage};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::git::{BinaryBlob, FileChange};
+use crate::theme::Theme;
+
+// Decoded images are downscaled to this size, which is larger than any editor pane
+const THUMBNAIL_SIZE: u32 = 256;
+// Larger images are shown as a hex dump rather than stalling the UI while decoding
+const MAX_IMAGE_SIDE: u32 = 8192;
+const MAX_DECODE_BYTES: u64 = 64 * 1024 * 1024;
+// Number of leading bytes shown for binaries that are not images
+const HEX_DUMP_BYTES: usize = 256;
+const HEX_BYTES_PER_ROW: usize = 16;
+// Columns between the before and after images
+const IMAGE_GAP: usize = 4;
+
+struct DecodedImage {
+    format: &'static str,
+    width: u32,
+    height: u32,
+    thumbnail: RgbaImage,
+}
+
+impl DecodedImage {
+    fn decode(data: &[u8]) -> Option<Self> {
+        let format = image::guess_format(data).ok()?;
+        let name = match format {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::Jpeg => "JPEG",
+            _ => return None,
+        };
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_IMAGE_SIDE);
+        limits.max_image_height = Some(MAX_IMAGE_SIDE);
+        limits.max_alloc = Some(MAX_DECODE_BYTES);
+        let mut reader = ImageReader::with_format(std::io::Cursor::new(data), format);
+        reader.limits(limits);
+        let image = reader.decode().ok()?;
+
+        Some(Self {
+            format: name,
+            width: image.width(),
+            height: image.height(),
+            thumbnail: image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgba8(),
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}×{}", self.format, self.width, self.height)
+    }
+}
+
+enum PreviewKind {
+    Image {
+        old: Option<DecodedImage>,
+        new: Option<DecodedImage>,
+    },
+    Hex {
+        label: &'static str,
+        data: Vec<u8>,
+    },
+}
+
+/// Editor pane preview of a binary file change: half-block images or a hex dump
+pub struct BinaryPreview {
+    old_size: Option<usize>,
+    new_size: Option<usize>,
+    kind: PreviewKind,
+}
+
+impl BinaryPreview {
+    /// Builds a preview for a binary change, or `None` for text files.
+    pub fn from_change(change: &FileChange) -> Option<Self> {
+        let binary = change.binary.as_ref()?;
+        let data = |blob: &Option<BinaryBlob>| blob.as_ref().and_then(|b| b.data.clone());
+        let old_data = data(&binary.old);
+        let new_data = data(&binary.new);
+
+        let old_image = old_data.as_deref().and_then(DecodedImage::decode);
+        let new_image = new_data.as_deref().and_then(DecodedImage::decode);
+
+        let kind = if old_image.is_some() || new_image.is_some() {
+            PreviewKind::Image {
+                old: old_image,
+                new: new_image,
+            }
+        } else {
+            let (label, mut data) = match (new_data, old_data) {
+                (Some(data), _) => ("new", data),
+                (None, Some(data)) => ("old", data),
+                (None, None) => ("new", Vec::new()),
+            };
+            data.truncate(HEX_DUMP_BYTES);
+            PreviewKind::Hex { label, data }
+        };
+
+        Some(Self {
+            old_size: binary.old.as_ref().map(|b| b.size),
+            new_size: binary.new.as_ref().map(|b| b.size),
+            kind,
+        })
+    }
+
+    /// Renders the preview into at most `height` lines of `width` columns.
+    pub fn render(&self, width: usize, height: usize, theme: &Theme) -> Vec<Line<'static>> {
+        let summary_style = Style::default()
+            .fg(theme.editor_line_number_cursor)
+            .add_modifier(Modifier::BOLD);
+
+        let mut lines = match &self.kind {
+            PreviewKind::Image { old, new } => {
+                let dimensions = match (old, new) {
+                    (Some(old), Some(new)) => format!("{} → {}", old.describe(), new.describe()),
+                    (Some(image), None) | (None, Some(image)) => image.describe(),
+                    (None, None) => String::new(),
+                };
+                let mut lines = vec![
+                    Line::from(Span::styled(
+                        format!("{}  ·  {}", dimensions, self.describe_sizes()),
+                        summary_style,
+                    )),
+                    Line::from(""),
+                ];
+                lines.extend(self.render_images(old, new, width, height.saturating_sub(3), theme));
+                lines
+            }
+            PreviewKind::Hex { label, data } => {
+                let mut lines = vec![
+                    Line::from(Span::styled(
+                        format!("binary file  ·  {}", self.describe_sizes()),
+                        summary_style,
+                    )),
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        format!("first {} bytes of the {} file:", data.len(), label),
+                        Style::default().fg(theme.editor_line_number),
+                    )),
+                ];
+                lines.extend(
+                    data.chunks(HEX_BYTES_PER_ROW)
+                        .enumerate()
+                        .map(|(row, bytes)| hex_row(row * HEX_BYTES_PER_ROW, bytes, theme)),
+                );
+                lines
+            }
+        };
+
+        lines.truncate(height);
+        lines
+    }
+
+    fn describe_sizes(&self) -> String {
+        match (self.old_size, self.new_size) {
+            (Some(old), Some(new)) => format!(
+                "{} → {} ({})",
+                format_size(old),
+                format_size(new),
+                format_size_delta(old, new)
+            ),
+            (Some(size), None) | (None, Some(size)) => format_size(size),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn render_images(
+        &self,
+        old: &Option<DecodedImage>,
+        new: &Option<DecodedImage>,
+        width: usize,
+        rows: usize,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        let label_style = Style::default().fg(theme.editor_line_number);
+        let images: Vec<(&str, &DecodedImage)> = [("before", old), ("after", new)]
+            .into_iter()
+            .filter_map(|(label, image)| image.as_ref().map(|image| (label, image)))
+            .collect();
+        if images.is_empty() || rows == 0 {
+            return Vec::new();
+        }
+
+        let column_width =
+            (width.saturating_sub(IMAGE_GAP * (images.len() - 1)) / images.len()).max(1);
+        let columns: Vec<Vec<Vec<Span<'static>>>> = images
+            .iter()
+            .map(|(_, image)| half_block_rows(&image.thumbnail, column_width, rows, theme))
+            .collect();
+
+        let mut label_line = Vec::new();
+        for (i, (label, _)) in images.iter().enumerate() {
+            if i > 0 {
+                label_line.push(Span::raw(" ".repeat(IMAGE_GAP)));
+            }
+            label_line.push(Span::styled(
+                format!("{:<width$}", label, width = column_width),
+                label_style,
+            ));
+        }
+
+        let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines = vec![Line::from(label_line)];
+        for row in 0..height {
+            let mut spans = Vec::new();
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::raw(" ".repeat(IMAGE_GAP)));
+                }
+                match column.get(row) {
+                    Some(cells) => spans.extend(cells.iter().cloned()),
+                    None => spans.push(Span::raw(" ".repeat(column_width))),
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+}
+
+// Scale the image to fit `max_cols` x `max_rows` cells, two pixels per cell using '▀'
+fn half_block_rows(
+    image: &RgbaImage,
+    max_cols: usize,
+    max_rows: usize,
+    theme: &Theme,
+) -> Vec<Vec<Span<'static>>> {
+    if image.width() == 0 || image.height() == 0 {
+        return Vec::new();
+    }
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let scale = (max_cols as f64 / width).min((max_rows * 2) as f64 / height);
+    let cols = ((width * scale).round() as usize).clamp(1, max_cols);
+    let pixel_rows = ((height * scale).round() as usize).clamp(1, max_rows * 2);
+    let background = rgb_of(theme.background_right);
+
+    let sample = |col: usize, pixel_row: usize| -> Color {
+        if pixel_row >= pixel_rows {
+            return theme.background_right;
+        }
+        let x = ((col as f64 + 0.5) / scale) as u32;
+        let y = ((pixel_row as f64 + 0.5) / scale) as u32;
+        let pixel = image.get_pixel(x.min(image.width() - 1), y.min(image.height() - 1));
+        blend(pixel.0, background)
+    };
+
+    (0..pixel_rows.div_ceil(2))
+        .map(|row| {
+            let mut cells: Vec<Span<'static>> = (0..cols)
+                .map(|col| {
+                    Span::styled(
+                        "▀",
+                        Style::default()
+                            .fg(sample(col, row * 2))
+                            .bg(sample(col, row * 2 + 1)),
+                    )
+                })
+                .collect();
+            if cols < max_cols {
+                cells.push(Span::raw(" ".repeat(max_cols - cols)));
+            }
+            cells
+        })
+        .collect()
+}
+
+fn hex_row(offset: usize, bytes: &[u8], theme: &Theme) -> Line<'static> {
+    let mut hex = String::with_capacity(HEX_BYTES_PER_ROW * 3 + 1);
+    for i in 0..HEX_BYTES_PER_ROW {
+        if i == HEX_BYTES_PER_ROW / 2 {
+            hex.push(' ');
+        }
+        match bytes.get(i) {
+            Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+            None => hex.push_str("   "),
+        }
+    }
+    let ascii: String = bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    Line::from(vec![
+        Span::styled(
+            format!("{:08x}  ", offset),
+            Style::default().fg(theme.editor_line_number),
+        ),
+        Span::styled(hex, Style::default().fg(theme.syntax_number)),
+        Span::styled(
+            format!(" |{}|", ascii),
+            Style::default().fg(theme.syntax_string),
+        ),
+    ])
+}
+
+// Alpha-blend a pixel over the pane background
+fn blend([r, g, b, a]: [u8; 4], (br, bg, bb): (u8, u8, u8)) -> Color {
+    let mix = |fg: u8, back: u8| -> u8 {
+        ((fg as u16 * a as u16 + back as u16 * (255 - a as u16)) / 255) as u8
+    };
+    Color::Rgb(mix(r, br), mix(g, bg), mix(b, bb))
+}
+
+fn rgb_of(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        _ => (0, 0, 0),
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+fn format_size_delta(old: usize, new: usize) -> String {
+    if new >= old {
+        format!("+{}", format_size(new - old))
+    } else {
+        format!("-{}", format_size(old - new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{BinaryContent, FileStatus};
+    use image::Rgba;
+
+    fn png(image: &RgbaImage) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut data, ImageFormat::Png).unwrap();
+        data.into_inner()
+    }
+
+    fn added_binary(data: Vec<u8>) -> FileChange {
+        FileChange {
+            path: "file.bin".to_string(),
+            old_path: None,
+            status: FileStatus::Added,
+            is_binary: true,
+            binary: Some(BinaryContent {
+                old: None,
+                new: Some(BinaryBlob {
+                    size: data.len(),
+                    data: Some(data),
+                }),
+            }),
+            is_excluded: false,
+            exclusion_reason: None,
+            old_content: None,
+            new_content: None,
+            hunks: Vec::new(),
+            diff: String::new(),
+        }
+    }
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_half_block_rows_pack_two_pixel_rows_per_cell() {
+        let red = Rgba([255, 0, 0, 255]);
+        let blue = Rgba([0, 0, 255, 255]);
+        let image = RgbaImage::from_fn(2, 2, |_, y| if y == 0 { red } else { blue });
+
+        let rows = half_block_rows(&image, 2, 1, &Theme::default());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 2);
+        for cell in &rows[0] {
+            assert_eq!(cell.content, "▀");
+            assert_eq!(cell.style.fg, Some(Color::Rgb(255, 0, 0)));
+            assert_eq!(cell.style.bg, Some(Color::Rgb(0, 0, 255)));
+        }
+    }
+
+    #[test]
+    fn test_half_block_rows_keep_aspect_ratio() {
+        let image = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 0, 255]));
+
+        // Limited by the single row, 4×2 pixels take 4 columns and the rest is padding
+        let rows = half_block_rows(&image, 8, 1, &Theme::default());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 5);
+        assert_eq!(rows[0][4].content, "    ");
+    }
+
+    #[test]
+    fn test_hex_row_shows_offset_bytes_and_ascii() {
+        let line = hex_row(16, b"Hi\x00\xff", &Theme::default());
+        assert_eq!(
+            text(&line),
+            format!("00000010  48 69 00 ff {}|Hi..|", " ".repeat(12 * 3 + 2))
+        );
+    }
+
+    #[test]
+    fn test_image_is_previewed_as_thumbnail() {
+        let image = RgbaImage::from_pixel(3, 2, Rgba([0, 255, 0, 255]));
+        let preview = BinaryPreview::from_change(&added_binary(png(&image))).unwrap();
+
+        let lines = preview.render(20, 10, &Theme::default());
+        assert!(text(&lines[0]).starts_with("PNG 3×2"));
+        assert!(matches!(preview.kind, PreviewKind::Image { .. }));
+    }
+
+    #[test]
+    fn test_image_over_decode_limits_falls_back_to_hex_dump() {
+        let image = RgbaImage::new(MAX_IMAGE_SIDE + 1, 1);
+        let preview = BinaryPreview::from_change(&added_binary(png(&image))).unwrap();
+
+        assert!(matches!(preview.kind, PreviewKind::Hex { .. }));
+        let lines = preview.render(80, 10, &Theme::default());
+        assert!(text(&lines[0]).starts_with("binary file"));
+        assert!(text(&lines[3]).starts_with("00000000  89 50 4e 47"));
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveTime};
+
+use crate::git::{CommitMetadata, LineChangeType};
+
+// Too little time left to start another commit
+const MIN_COMMIT_TIME: Duration = Duration::from_secs(3);
+
+/// Parse a duration like `90s`, `15m`, `1h30m` or a number of seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("Invalid duration '{}' (e.g. 90s, 15m, 1h30m)", s);
+    if let Ok(seconds) = s.parse::<u64>() {
+        if seconds > 0 {
+            return Ok(Duration::from_secs(seconds));
+        }
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    // Units must come from largest to smallest, each at most once
+    let mut previous_unit = u64::MAX;
+    for ch in s.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        if unit >= previous_unit {
+            return Err(invalid());
+        }
+        previous_unit = unit;
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(|| format!("Duration '{}' is too long", s))?;
+        number.clear();
+    }
+    if !number.is_empty() || total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Parse a number of commits, which must be at least one.
+pub fn parse_max_commits(s: &str) -> Result<usize, String> {
+    match s.trim().parse::<usize>() {
+        Ok(0) => Err("The number of commits must be at least 1".to_string()),
+        Ok(count) => Ok(count),
+        Err(_) => Err(format!("Invalid number of commits '{}'", s)),
+    }
+}
+
+/// Parse a local time of day like `18:00` or `18:00:30`.
+pub fn parse_time_of_day(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s.trim(), "%H:%M:%S"))
+        .map_err(|_| format!("Invalid time '{}' (e.g. 18:00)", s))
+}
+
+/// How long a session may play
+#[derive(Debug, Clone, Default)]
+pub struct SessionLimits {
+    pub duration: Option<Duration>,
+    pub max_commits: Option<usize>,
+    /// Local time of day, today or else tomorrow
+    pub until_time: Option<NaiveTime>,
+    /// Show a summary screen when the session ends instead of exiting
+    pub summary: bool,
+}
+
+/// Budget and totals of the commits played since startup
+pub struct Session {
+    started_at: Instant,
+    deadline: Option<Instant>,
+    max_commits: Option<usize>,
+    pub summary: bool,
+    commits: usize,
+    files: usize,
+    additions: usize,
+    deletions: usize,
+    authors: HashSet<String>,
+}
+
+impl Session {
+    pub fn new(limits: &SessionLimits) -> Self {
+        let started_at = Instant::now();
+        let until = limits.until_time.map(|time| {
+            let now = Local::now().naive_local();
+            let mut target = now.date().and_time(time);
+            if target <= now {
+                target += chrono::Duration::days(1);
+            }
+            (target - now).to_std().unwrap_or_default()
+        });
+        let deadline = [limits.duration, until]
+            .into_iter()
+            .flatten()
+            .min()
+            // A budget too long for the clock never runs out
+            .and_then(|budget| started_at.checked_add(budget));
+
+        Self {
+            started_at,
+            deadline,
+            max_commits: limits.max_commits,
+            summary: limits.summary,
+            commits: 0,
+            files: 0,
+            additions: 0,
+            deletions: 0,
+            authors: HashSet::new(),
+        }
+    }
+
+    /// Counts a commit that starts playing.
+    pub fn record(&mut self, metadata: &CommitMetadata) {
+        self.commits += 1;
+        self.files += metadata.changes.len();
+        for line in metadata
+            .changes
+            .iter()
+            .flat_map(|change| &change.hunks)
+            .flat_map(|hunk| &hunk.lines)
+        {
+            match line.change_type {
+                LineChangeType::Addition => self.additions += 1,
+                LineChangeType::Deletion => self.deletions += 1,
+                LineChangeType::Context => {}
+            }
+        }
+        self.authors.insert(metadata.author.clone());
+    }
+
+    /// Time left until the deadline, if there is one
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Whether the deadline has passed, ending the commit being played
+    pub fn is_expired(&self) -> bool {
+        self.remaining()
+            .is_some_and(|remaining| remaining.is_zero())
+    }
+
+    /// Whether another commit may start
+    pub fn can_start_commit(&self) -> bool {
+        self.max_commits.is_none_or(|max| self.commits < max)
+            && self
+                .remaining()
+                .is_none_or(|remaining| remaining >= MIN_COMMIT_TIME)
+    }
+
+    /// Totals shown on the summary screen, as label and value
+    pub fn summary_rows(&self) -> Vec<(&'static str, String)> {
+        let elapsed = self.started_at.elapsed().as_secs();
+        vec![
+            ("Commits", self.commits.to_string()),
+            ("Files", self.files.to_string()),
+            ("Lines", format!("+{} -{}", self.additions, self.deletions)),
+            ("Authors", self.authors.len().to_string()),
+            (
+                "Time",
+                format!(
+                    "{}h {:02}m {:02}s",
+                    elapsed / 3600,
+                    elapsed / 60 % 60,
+                    elapsed % 60
+                ),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let secs = |s| parse_duration(s).map(|d| d.as_secs());
+        assert_eq!(secs("90"), Ok(90));
+        assert_eq!(secs("90s"), Ok(90));
+        assert_eq!(secs(" 15m "), Ok(900));
+        assert_eq!(secs("1h30m"), Ok(5400));
+        assert_eq!(secs("1h0m5s"), Ok(3605));
+
+        for invalid in [
+            "", "0", "0s", "h", "1x", "1h30", "1.5h", "-5s", "1h1h", "30m1h",
+        ] {
+            assert!(
+                parse_duration(invalid).is_err(),
+                "{:?} was accepted",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_overflow() {
+        let hours = format!("{}h", u64::MAX / 3600 + 1);
+        assert!(parse_duration(&hours).unwrap_err().contains("too long"));
+        let sum = format!("{}h{}s", u64::MAX / 3600, u64::MAX);
+        assert!(parse_duration(&sum).unwrap_err().contains("too long"));
+        assert!(parse_duration("99999999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn test_session_with_an_endless_duration_has_no_deadline() {
+        let session = Session::new(&SessionLimits {
+            duration: Some(Duration::from_secs(u64::MAX)),
+            ..SessionLimits::default()
+        });
+        assert_eq!(session.deadline(), None);
+        assert!(!session.is_expired());
+        assert!(session.can_start_commit());
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(
+            parse_time_of_day("18:00"),
+            Ok(NaiveTime::from_hms_opt(18, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time_of_day(" 07:05:30 "),
+            Ok(NaiveTime::from_hms_opt(7, 5, 30).unwrap())
+        );
+        for invalid in ["", "24:00", "18", "6pm", "18:60"] {
+            assert!(
+                parse_time_of_day(invalid).is_err(),
+                "{:?} was accepted",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_max_commits() {
+        assert_eq!(parse_max_commits("3"), Ok(3));
+        assert!(parse_max_commits("0").is_err());
+        assert!(parse_max_commits("-1").is_err());
+        assert!(parse_max_commits("many").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::git::{FileChange, LineChangeType};
+use crate::syntax::{HighlightSpan, Highlighter};
+use crate::theme::Theme;
+use crate::widgets::blend;
+
+// Opacity of the added/removed colors over the editor background
+const CHANGE_TINT: f32 = 0.2;
+// Columns between the old and new sides
+const DIVIDER: &str = " │ ";
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Unchanged,
+    Removed,
+    Added,
+    Modified,
+}
+
+/// One row of the split view: a line of the old file, the new file, or both (0-based)
+#[derive(Debug, Clone)]
+struct SplitRow {
+    old: Option<usize>,
+    new: Option<usize>,
+    kind: RowKind,
+    /// Position among the changed rows, which are revealed in this order
+    change_index: Option<usize>,
+}
+
+/// Old and new lines of a file aligned side by side, hunk by hunk
+#[derive(Debug, Clone)]
+pub struct SplitLayout {
+    rows: Vec<SplitRow>,
+    /// Number of changed rows in each hunk
+    hunk_sizes: Vec<usize>,
+}
+
+impl SplitLayout {
+    /// Aligns the old and new content of a change using its hunk line numbers.
+    pub fn from_change(change: &FileChange) -> Self {
+        let line_count =
+            |content: &Option<String>| content.as_deref().map_or(0, |c| c.lines().count());
+        let old_count = line_count(&change.old_content);
+        let new_count = line_count(&change.new_content);
+
+        let mut layout = Self {
+            rows: Vec::new(),
+            hunk_sizes: Vec::new(),
+        };
+        let (mut old, mut new) = (0, 0);
+        let mut changes = 0;
+
+        for hunk in &change.hunks {
+            // A side without lines starts after `start`, otherwise at it (1-based)
+            let old_begin = if hunk.old_lines == 0 {
+                hunk.old_start
+            } else {
+                hunk.old_start - 1
+            };
+            let new_begin = if hunk.new_lines == 0 {
+                hunk.new_start
+            } else {
+                hunk.new_start - 1
+            };
+            while old < old_begin && new < new_begin {
+                layout.push_unchanged(Some(old), Some(new));
+                old += 1;
+                new += 1;
+            }
+
+            let hunk_start = changes;
+            let mut removed = Vec::new();
+            let mut added = Vec::new();
+            for line in &hunk.lines {
+                match line.change_type {
+                    LineChangeType::Deletion => removed.extend(line.old_line_no.map(|n| n - 1)),
+                    LineChangeType::Addition => added.extend(line.new_line_no.map(|n| n - 1)),
+                    LineChangeType::Context => {
+                        layout.push_changes(&mut removed, &mut added, &mut changes);
+                        layout.push_unchanged(
+                            line.old_line_no.map(|n| n - 1),
+                            line.new_line_no.map(|n| n - 1),
+                        );
+                    }
+                }
+            }
+            layout.push_changes(&mut removed, &mut added, &mut changes);
+            layout.hunk_sizes.push(changes - hunk_start);
+
+            old = old_begin + hunk.old_lines;
+            new = new_begin + hunk.new_lines;
+        }
+
+        while old < old_count || new < new_count {
+            layout.push_unchanged(
+                (old < old_count).then_some(old),
+                (new < new_count).then_some(new),
+            );
+            old += 1;
+            new += 1;
+        }
+
+        layout
+    }
+
+    /// Number of changed rows in each hunk, one reveal step per row
+    pub fn hunk_sizes(&self) -> &[usize] {
+        &self.hunk_sizes
+    }
+
+    fn push_unchanged(&mut self, old: Option<usize>, new: Option<usize>) {
+        self.rows.push(SplitRow {
+            old,
+            new,
+            kind: RowKind::Unchanged,
+            change_index: None,
+        });
+    }
+
+    // Pair removed lines with added ones so that modified lines share a row
+    fn push_changes(
+        &mut self,
+        removed: &mut Vec<usize>,
+        added: &mut Vec<usize>,
+        changes: &mut usize,
+    ) {
+        for i in 0..removed.len().max(added.len()) {
+            let old = removed.get(i).copied();
+            let new = added.get(i).copied();
+            let kind = match (old, new) {
+                (Some(_), Some(_)) => RowKind::Modified,
+                (Some(_), None) => RowKind::Removed,
+                _ => RowKind::Added,
+            };
+            self.rows.push(SplitRow {
+                old,
+                new,
+                kind,
+                change_index: Some(*changes),
+            });
+            *changes += 1;
+        }
+        removed.clear();
+        added.clear();
+    }
+}
+
+/// One side of the split view with its syntax highlighting
+struct SplitSide {
+    lines: Vec<String>,
+    line_offsets: Vec<usize>,
+    highlights: Vec<HighlightSpan>,
+}
+
+impl SplitSide {
+    fn new(content: &str, highlighter: &mut Highlighter) -> Self {
+        let mut line_offsets = vec![0];
+        line_offsets.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+            line_offsets,
+            highlights: highlighter.highlight(content),
+        }
+    }
+}
+
+/// Editor pane view of a text change: old content on the left, new content on the right,
+/// with changed rows revealed one at a time
+pub struct SplitView {
+    layout: SplitLayout,
+    old: SplitSide,
+    new: SplitSide,
+    revealed: usize,
+}
+
+impl SplitView {
+    /// Builds the view of a change, highlighting both sides with the file's language.
+    pub fn new(change: &FileChange, highlighter: &mut Highlighter) -> Self {
+        Self {
+            layout: SplitLayout::from_change(change),
+            old: SplitSide::new(change.old_content.as_deref().unwrap_or(""), highlighter),
+            new: SplitSide::new(change.new_content.as_deref().unwrap_or(""), highlighter),
+            revealed: 0,
+        }
+    }
+
+    /// Reveals the next changed row.
+    pub fn reveal_row(&mut self) {
+        self.revealed += 1;
+    }
+
+    /// Renders the rows around the most recently revealed change into `height` lines.
+    pub fn render(&self, width: usize, height: usize, theme: &Theme) -> Vec<Line<'static>> {
+        let is_visible = |row: &SplitRow| row.change_index.is_none_or(|i| i < self.revealed);
+        let visible: Vec<&SplitRow> = self
+            .layout
+            .rows
+            .iter()
+            .filter(|row| is_visible(row))
+            .collect();
+
+        // Both sides scroll together, centered on the latest change (or where the first will appear)
+        let target = Some(self.revealed.saturating_sub(1));
+        let rows = &self.layout.rows;
+        let focus = rows
+            .iter()
+            .position(|row| row.change_index == target)
+            .map_or(0, |end| {
+                rows[..end].iter().filter(|row| is_visible(row)).count()
+            });
+        let scroll = focus
+            .saturating_sub(height / 2)
+            .min(visible.len().saturating_sub(height));
+
+        let number_width = self
+            .old
+            .lines
+            .len()
+            .max(self.new.lines.len())
+            .to_string()
+            .len()
+            .max(3);
+        let side_width = width.saturating_sub(DIVIDER.chars().count()) / 2;
+        // A transparent background cannot be blended with, so changed rows then only
+        // show their change in the color of their line numbers
+        let tint = |color| match theme.background_right {
+            Color::Rgb(..) => blend(color, CHANGE_TINT, theme.background_right),
+            background => background,
+        };
+        let unchanged = (theme.background_right, theme.editor_line_number);
+        let removed = (tint(theme.file_tree_deleted), theme.file_tree_deleted);
+        let added = (tint(theme.file_tree_added), theme.file_tree_added);
+
+        visible
+            .iter()
+            .skip(scroll)
+            .take(height)
+            .map(|row| {
+                let (old_colors, new_colors) = match row.kind {
+                    RowKind::Unchanged => (unchanged, unchanged),
+                    RowKind::Removed => (removed, unchanged),
+                    RowKind::Added => (unchanged, added),
+                    RowKind::Modified => (removed, added),
+                };
+                let mut spans = render_side(
+                    &self.old,
+                    row.old,
+                    old_colors,
+                    number_width,
+                    side_width,
+                    theme,
+                );
+                spans.push(Span::styled(
+                    DIVIDER,
+                    Style::default().fg(theme.editor_separator),
+                ));
+                spans.extend(render_side(
+                    &self.new,
+                    row.new,
+                    new_colors,
+                    number_width,
+                    side_width,
+                    theme,
+                ));
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+// Render a line number and the highlighted line, padded or cut to exactly `width` columns,
+// with the row's background and line number colors
+fn render_side(
+    side: &SplitSide,
+    line: Option<usize>,
+    (bg, number_fg): (Color, Color),
+    number_width: usize,
+    width: usize,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let Some((line, content)) = line.and_then(|line| Some((line, side.lines.get(line)?))) else {
+        // The other side has no counterpart for this row
+        return vec![Span::styled(
+            " ".repeat(width),
+            Style::default().bg(theme.background_left),
+        )];
+    };
+
+    let mut spans = vec![Span::styled(
+        format!("{:>number_width$} ", line + 1),
+        Style::default().fg(number_fg).bg(bg),
+    )];
+    let text_width = width.saturating_sub(number_width + 1);
+    let offset = side.line_offsets.get(line).copied().unwrap_or(0);
+    let line_highlights: Vec<&HighlightSpan> = side
+        .highlights
+        .iter()
+        .filter(|h| h.start < offset + content.len() && h.end > offset)
+        .collect();
+
+    let mut used = 0;
+    for (i, ch) in content.char_indices() {
+        let (text, char_width) = match ch {
+            '\t' => (" ".repeat(TAB_WIDTH), TAB_WIDTH),
+            _ => (ch.to_string(), ch.width().unwrap_or(0)),
+        };
+        if used + char_width > text_width {
+            break;
+        }
+        used += char_width;
+
+        let start = offset + i;
+        let color = line_highlights
+            .iter()
+            .find(|h| start >= h.start && start + ch.len_utf8() <= h.end)
+            .map(|h| h.token_type.color_for(theme, h.language))
+            .unwrap_or(theme.syntax_variable);
+        spans.push(Span::styled(text, Style::default().fg(color).bg(bg)));
+    }
+    spans.push(Span::styled(
+        " ".repeat(text_width - used),
+        Style::default().bg(bg),
+    ));
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::TestRepo;
+    use crate::git::GitRepository;
+
+    // Rows as `old:new kind` with 1-based line numbers, `-` for a missing side
+    fn rows(layout: &SplitLayout) -> Vec<String> {
+        let number = |line: Option<usize>| line.map_or("-".to_string(), |n| (n + 1).to_string());
+        layout
+            .rows
+            .iter()
+            .map(|row| {
+                let kind = match row.kind {
+                    RowKind::Unchanged => "U",
+                    RowKind::Removed => "R",
+                    RowKind::Added => "A",
+                    RowKind::Modified => "M",
+                };
+                format!("{}:{} {}", number(row.old), number(row.new), kind)
+            })
+            .collect()
+    }
+
+    fn head_change(old: &str, new: &str) -> FileChange {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("file.txt", old, "Add");
+        test_repo.commit_file("file.txt", new, "Change");
+        let repo = GitRepository::open(&test_repo.path).unwrap();
+        repo.get_commit("HEAD").unwrap().changes.remove(0)
+    }
+
+    fn numbered(lines: &[&str]) -> String {
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    #[test]
+    fn test_layout_aligns_uneven_runs_across_hunks() {
+        let old: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let mut new = old.clone();
+        new.remove(15);
+        new.splice(11..13, ["12x"]);
+        new.splice(1..2, ["2a", "2b", "2c"]);
+
+        let layout = SplitLayout::from_change(&head_change(&numbered(&old), &numbered(&new)));
+
+        let mut expected = vec!["1:1 U", "2:2 M", "-:3 A", "-:4 A"];
+        let unchanged: Vec<String> = (3..=11).map(|n| format!("{}:{} U", n, n + 2)).collect();
+        expected.extend(unchanged.iter().map(String::as_str));
+        expected.extend(["12:14 M", "13:- R", "14:15 U", "15:16 U", "16:- R"]);
+        expected.extend(["17:17 U", "18:18 U", "19:19 U", "20:20 U"]);
+        assert_eq!(rows(&layout), expected);
+        assert_eq!(layout.hunk_sizes(), [3, 3]);
+
+        let order: Vec<usize> = layout.rows.iter().filter_map(|r| r.change_index).collect();
+        assert_eq!(order, (0..6).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_layout_pairs_more_removed_than_added_lines() {
+        let layout = SplitLayout::from_change(&head_change(
+            &numbered(&["a", "b", "c", "d", "e"]),
+            &numbered(&["a", "x", "e", "f"]),
+        ));
+
+        assert_eq!(
+            rows(&layout),
+            ["1:1 U", "2:2 M", "3:- R", "4:- R", "5:3 U", "-:4 A"]
+        );
+        assert_eq!(layout.hunk_sizes(), [4]);
+    }
+
+    // Line number span of a side of a rendered row
+    fn number_style(line: &Line, number: &str) -> Style {
+        line.spans
+            .iter()
+            .find(|span| span.content == format!("{:>3} ", number))
+            .unwrap()
+            .style
+    }
+
+    #[test]
+    fn test_changed_rows_are_colored_over_transparent_background() {
+        let change = head_change(&numbered(&["a", "b"]), &numbered(&["a", "c", "d"]));
+        let mut highlighter = Highlighter::new();
+        let mut view = SplitView::new(&change, &mut highlighter);
+        for _ in 0..3 {
+            view.reveal_row();
+        }
+
+        let theme = Theme::default();
+        let lines = view.render(60, 10, &theme);
+        let added = number_style(&lines[2], "3");
+        assert_eq!(added.fg, Some(theme.file_tree_added));
+        assert_eq!(
+            added.bg,
+            Some(blend(
+                theme.file_tree_added,
+                CHANGE_TINT,
+                theme.background_right
+            ))
+        );
+        assert_eq!(
+            number_style(&lines[0], "1").fg,
+            Some(theme.editor_line_number)
+        );
+
+        let theme = Theme::default().with_transparent_background();
+        let lines = view.render(60, 10, &theme);
+        let removed = number_style(&lines[1], "2");
+        assert_eq!(removed.fg, Some(theme.file_tree_deleted));
+        assert_eq!(removed.bg, Some(Color::Reset));
+        assert_eq!(number_style(&lines[2], "3").fg, Some(theme.file_tree_added));
+    }
+}
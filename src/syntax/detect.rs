@@ -0,0 +1,234 @@
+use globset::{Glob, GlobMatcher};
+use std::path::Path;
+
+use super::languages::{file_name_language, language_name, resolve_language_name};
+
+// Like Vim, only the first and last few lines are searched for modelines
+const MODELINE_LINES: usize = 5;
+
+/// A rule that maps files matching a glob pattern to a language (`[languages]` in config.toml)
+#[derive(Debug, Clone)]
+pub struct LanguageRule {
+    pub matcher: GlobMatcher,
+    pub language: &'static str,
+}
+
+impl LanguageRule {
+    /// Creates a rule, returning `None` for an invalid pattern or unknown language.
+    /// Example: `LanguageRule::new("*.tmpl", "html")`
+    pub fn new(pattern: &str, language: &str) -> Option<Self> {
+        let language = resolve_language_name(language)?;
+        let matcher = Glob::new(pattern).ok()?.compile_matcher();
+        Some(Self { matcher, language })
+    }
+
+    /// Check if a file path matches this rule
+    pub fn matches(&self, path: &str) -> bool {
+        self.matcher.is_match(path)
+    }
+}
+
+/// Detects the language of a file, trying in order: user rules, Vim/Emacs modelines,
+/// well-known file names, the file extension and finally the shebang line.
+pub fn detect_language(path: &str, content: &str, rules: &[LanguageRule]) -> Option<&'static str> {
+    if let Some(rule) = rules.iter().find(|rule| rule.matches(path)) {
+        return Some(rule.language);
+    }
+
+    let path = Path::new(path);
+    modeline_language(content)
+        .or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(file_name_language)
+        })
+        .or_else(|| language_name(path))
+        .or_else(|| shebang_language(content))
+}
+
+/// Detects the language from a `#!` interpreter line, e.g. `#!/usr/bin/env python3`.
+pub fn shebang_language(content: &str) -> Option<&'static str> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+
+    // `env` takes options and variable assignments before the interpreter. With `-S` the
+    // rest of the line is split into words, so the interpreter follows the same way.
+    if interpreter == "env" {
+        interpreter = loop {
+            match words.next()? {
+                "-u" | "--unset" | "-C" | "--chdir" => {
+                    words.next()?;
+                }
+                word if word.starts_with('-') || word.contains('=') => {}
+                word => break word,
+            }
+        };
+    }
+
+    // Drop version suffixes like python3.12 or ruby2.7
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match name {
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" => Some("bash"),
+        "python" | "pypy" => Some("python"),
+        "node" | "nodejs" => Some("javascript"),
+        "deno" | "bun" | "ts-node" | "tsx" => Some("typescript"),
+        "ruby" => Some("ruby"),
+        "php" => Some("php"),
+        "lua" | "luajit" => Some("lua"),
+        "elixir" => Some("elixir"),
+        "escript" => Some("erlang"),
+        "runhaskell" | "runghc" | "stack" => Some("haskell"),
+        "scala" => Some("scala"),
+        "swift" => Some("swift"),
+        "make" => Some("make"),
+        "just" => Some("just"),
+        _ => None,
+    }
+}
+
+/// Detects the language from a Vim (`vim: set ft=python:`) or Emacs (`-*- mode: ruby -*-`) modeline.
+pub fn modeline_language(content: &str) -> Option<&'static str> {
+    let lines: Vec<&str> = content.lines().collect();
+    let head = lines.iter().take(MODELINE_LINES);
+    let tail = lines
+        .iter()
+        .skip(MODELINE_LINES.max(lines.len().saturating_sub(MODELINE_LINES)));
+
+    // Emacs only reads the first line, or the second after a shebang
+    let emacs = lines.iter().take(2).find_map(|line| emacs_modeline(line));
+
+    emacs.or_else(|| head.chain(tail).find_map(|line| vim_modeline(line)))
+}
+
+fn vim_modeline(line: &str) -> Option<&'static str> {
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .filter_map(|marker| {
+            line.match_indices(marker)
+                // The marker must start the line or follow whitespace
+                .find(|(i, _)| *i == 0 || line[..*i].ends_with(char::is_whitespace))
+                .map(|(i, _)| i + marker.len())
+        })
+        .min()?;
+
+    line[start..]
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            matches!(key, "ft" | "filetype" | "syn" | "syntax").then_some(value)
+        })
+        .and_then(resolve_language_name)
+}
+
+fn emacs_modeline(line: &str) -> Option<&'static str> {
+    let start = line.find("-*-")? + 3;
+    let end = start + line[start..].find("-*-")?;
+    let vars = line[start..end].trim();
+
+    // Either `-*- python -*-` or `-*- mode: python; coding: utf-8 -*-`
+    let mode = if vars.contains(':') {
+        vars.split(';').find_map(|var| {
+            let (key, value) = var.split_once(':')?;
+            (key.trim().eq_ignore_ascii_case("mode")).then(|| value.trim())
+        })?
+    } else {
+        vars
+    };
+
+    resolve_language_name(mode.trim_end_matches("-mode"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shebang_interpreters() {
+        assert_eq!(shebang_language("#!/bin/sh\necho"), Some("bash"));
+        assert_eq!(shebang_language("#!/usr/bin/python3.11"), Some("python"));
+        assert_eq!(shebang_language("#!/usr/bin/env ruby2.7 -w"), Some("ruby"));
+        assert_eq!(shebang_language("#! /usr/bin/env node"), Some("javascript"));
+        assert_eq!(shebang_language("#!/usr/bin/perl"), None);
+        assert_eq!(shebang_language("echo\n#!/bin/sh"), None);
+    }
+
+    #[test]
+    fn test_shebang_env_options() {
+        assert_eq!(
+            shebang_language("#!/usr/bin/env -S deno run --allow-net"),
+            Some("typescript")
+        );
+        assert_eq!(
+            shebang_language("#!/usr/bin/env -S PYTHONUNBUFFERED=1 python3 -u"),
+            Some("python")
+        );
+        assert_eq!(
+            shebang_language("#!/usr/bin/env -u LANG -C /tmp bash"),
+            Some("bash")
+        );
+        assert_eq!(shebang_language("#!/usr/bin/env"), None);
+    }
+
+    #[test]
+    fn test_vim_modelines() {
+        assert_eq!(modeline_language("# vim: set ft=python:"), Some("python"));
+        assert_eq!(modeline_language("// vi:syntax=rust"), Some("rust"));
+        assert_eq!(modeline_language("/* ex: set filetype=c : */"), Some("c"));
+        // Searched in the last lines too, but not in the middle of a long file
+        let tail = format!("{}# vim: ft=ruby", "x\n".repeat(20));
+        assert_eq!(modeline_language(&tail), Some("ruby"));
+        let middle = format!("{}# vim: ft=ruby\n{}", "x\n".repeat(10), "x\n".repeat(10));
+        assert_eq!(modeline_language(&middle), None);
+        // The marker must be a word of its own
+        assert_eq!(modeline_language("novim: ft=python"), None);
+    }
+
+    #[test]
+    fn test_emacs_modelines() {
+        assert_eq!(modeline_language("# -*- ruby -*-"), Some("ruby"));
+        assert_eq!(
+            modeline_language("#!/bin/sh\n# -*- mode: python; coding: utf-8 -*-"),
+            Some("python")
+        );
+        assert_eq!(
+            modeline_language(";; -*- mode: js-mode -*-"),
+            Some("javascript")
+        );
+        // Only the first two lines are read
+        assert_eq!(modeline_language("a\nb\n# -*- ruby -*-"), None);
+    }
+
+    #[test]
+    fn test_detection_precedence() {
+        let rules = vec![LanguageRule::new("*.tmpl", "html").unwrap()];
+        let modeline = "#!/usr/bin/env python\n# vim: ft=ruby";
+
+        // A user rule wins over everything else
+        assert_eq!(detect_language("page.tmpl", modeline, &rules), Some("html"));
+        // Then the modeline, over the file name, extension and shebang
+        assert_eq!(detect_language("script.sh", modeline, &rules), Some("ruby"));
+        assert_eq!(detect_language("Makefile", modeline, &rules), Some("ruby"));
+        // Then the file name and the extension, over the shebang
+        assert_eq!(
+            detect_language("Gemfile", "#!/bin/sh", &rules),
+            Some("ruby")
+        );
+        assert_eq!(
+            detect_language("tool.py", "#!/bin/sh", &rules),
+            Some("python")
+        );
+        // The shebang is the last resort
+        assert_eq!(detect_language("tool", "#!/bin/sh", &rules), Some("bash"));
+        assert_eq!(detect_language("tool", "plain text", &rules), None);
+    }
+
+    #[test]
+    fn test_dockerfiles_are_not_detected() {
+        assert_eq!(
+            detect_language("Dockerfile", "FROM rust\nRUN make", &[]),
+            None
+        );
+        assert_eq!(detect_language("Containerfile", "FROM rust", &[]), None);
+    }
+}
@@ -0,0 +1,5 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_cmake::LANGUAGE.into()
+}
+
+pub const HIGHLIGHT_QUERY: &str = tree_sitter_cmake::HIGHLIGHTS_QUERY;
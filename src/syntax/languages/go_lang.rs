@@ -3,3 +3,13 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_go::HIGHLIGHTS_QUERY;
+
+// Highlight SQL statements embedded in string literals
+pub const INJECTION_QUERY: &str = r#"
+([
+  (interpreted_string_literal_content)
+  (raw_string_literal_content)
+] @injection.content
+  (#match? @injection.content "(?i)^\\s*(select|insert|update|delete|create|alter|drop|with)\\s")
+  (#set! injection.language "sql"))
+"#;
@@ -3,3 +3,10 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_javascript::HIGHLIGHT_QUERY;
+
+// Highlight SQL statements embedded in string literals
+pub const INJECTION_QUERY: &str = r#"
+((string_fragment) @injection.content
+  (#match? @injection.content "(?i)^\\s*(select|insert|update|delete|create|alter|drop|with)\\s")
+  (#set! injection.language "sql"))
+"#;
@@ -0,0 +1,5 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_just::LANGUAGE.into()
+}
+
+pub const HIGHLIGHT_QUERY: &str = include_str!("queries/just_highlights.scm");
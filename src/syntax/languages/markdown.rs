@@ -2,6 +2,11 @@ pub fn language() -> tree_sitter::Language {
     tree_sitter_md::LANGUAGE.into()
 }
 
+/// Grammar for inline content (emphasis, links, code spans), injected into block `inline` nodes
+pub fn inline_language() -> tree_sitter::Language {
+    tree_sitter_md::INLINE_LANGUAGE.into()
+}
+
 // Custom highlight query for better visibility
 // Based on tree_sitter_md::HIGHLIGHT_QUERY_BLOCK with improved colors
 pub const HIGHLIGHT_QUERY: &str = r#"
@@ -58,3 +63,9 @@ pub const HIGHLIGHT_QUERY: &str = r#"
   (backslash_escape)
 ] @string.escape
 "#;
+
+pub const INJECTION_QUERY: &str = tree_sitter_md::INJECTION_QUERY_BLOCK;
+
+pub const INLINE_HIGHLIGHT_QUERY: &str = tree_sitter_md::HIGHLIGHT_QUERY_INLINE;
+
+pub const INLINE_INJECTION_QUERY: &str = tree_sitter_md::INJECTION_QUERY_INLINE;
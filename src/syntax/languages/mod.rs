@@ -1,6 +1,7 @@
 pub mod bash;
 pub mod c;
 pub mod clojure;
+pub mod cmake;
 pub mod cpp;
 pub mod csharp;
 pub mod css;
@@ -13,14 +14,17 @@ pub mod html;
 pub mod java;
 pub mod javascript;
 pub mod json;
+pub mod just;
 pub mod kotlin;
 pub mod lua;
+pub mod make;
 pub mod markdown;
 pub mod php;
 pub mod python;
 pub mod ruby;
 pub mod rust;
 pub mod scala;
+pub mod sql;
 pub mod svelte;
 pub mod swift;
 pub mod typescript;
@@ -31,41 +35,196 @@ pub mod zig;
 use std::path::Path;
 use tree_sitter::Language;
 
-pub fn get_language(path: &Path) -> Option<(Language, &'static str)> {
+use super::runtime;
+
+/// Returns the canonical language name for a file path, based on its extension.
+pub fn language_name(path: &Path) -> Option<&'static str> {
     let extension = path.extension()?.to_str()?;
+    if let Some(language) = runtime::manifest_language(extension) {
+        return Some(language);
+    }
 
     match extension {
-        "sh" | "bash" | "zsh" => Some((bash::language(), bash::HIGHLIGHT_QUERY)),
+        "sh" | "bash" | "zsh" => Some("bash"),
         // C++ before C to handle .h files (can be either)
         "cpp" | "cc" | "cxx" | "c++" | "C" | "CPP" | "hpp" | "hh" | "hxx" | "h++" | "H" | "HPP"
-        | "tcc" | "inl" => Some((cpp::language(), cpp::HIGHLIGHT_QUERY)),
-        "c" | "h" => Some((c::language(), c::HIGHLIGHT_QUERY)),
-        "clj" | "cljs" | "cljc" | "edn" => Some((clojure::language(), clojure::HIGHLIGHT_QUERY)),
-        "cs" | "csx" => Some((csharp::language(), csharp::HIGHLIGHT_QUERY)),
-        "css" | "scss" | "sass" => Some((css::language(), css::HIGHLIGHT_QUERY)),
+        | "tcc" | "inl" => Some("cpp"),
+        "c" | "h" => Some("c"),
+        "clj" | "cljs" | "cljc" | "edn" => Some("clojure"),
+        "cmake" => Some("cmake"),
+        "cs" | "csx" => Some("csharp"),
+        "css" | "scss" | "sass" => Some("css"),
+        "dart" => Some("dart"),
+        "ex" | "exs" => Some("elixir"),
+        "erl" | "hrl" | "es" | "escript" => Some("erlang"),
+        "go" => Some("go"),
+        "hs" | "lhs" => Some("haskell"),
+        "html" | "htm" => Some("html"),
+        "java" => Some("java"),
+        "js" | "jsx" | "mjs" | "cjs" => Some("javascript"),
+        "json" | "jsonc" => Some("json"),
+        "just" => Some("just"),
+        "kt" | "kts" => Some("kotlin"),
+        "lua" => Some("lua"),
+        "mk" | "mak" => Some("make"),
+        "md" | "markdown" => Some("markdown"),
+        "php" | "php3" | "php4" | "php5" | "phtml" => Some("php"),
+        "py" | "pyw" => Some("python"),
+        "rb" | "rbw" | "rake" | "gemspec" => Some("ruby"),
+        "rs" => Some("rust"),
+        "scala" | "sc" | "sbt" => Some("scala"),
+        "sql" => Some("sql"),
+        "svelte" => Some("svelte"),
+        "swift" => Some("swift"),
+        "ts" | "tsx" | "mts" | "cts" => Some("typescript"),
+        "xml" | "svg" | "xsl" | "xslt" => Some("xml"),
+        "yaml" | "yml" => Some("yaml"),
+        "zig" => Some("zig"),
+        // Runtime grammars are matched by their name, e.g. `nix.so` for `*.nix`
+        _ => runtime::runtime_language_name(extension),
+    }
+}
+
+/// Returns the canonical language name for well-known file names without a useful extension.
+pub fn file_name_language(file_name: &str) -> Option<&'static str> {
+    match file_name {
+        ".bashrc" | ".bash_profile" | ".bash_login" | ".bash_logout" | ".bash_aliases"
+        | ".profile" | ".zshrc" | ".zshenv" | ".zprofile" | ".zlogin" | ".zlogout" | ".envrc"
+        | "PKGBUILD" | "APKBUILD" => Some("bash"),
+        "Makefile" | "makefile" | "GNUmakefile" => Some("make"),
+        "CMakeLists.txt" => Some("cmake"),
+        "Justfile" | "justfile" | ".justfile" => Some("just"),
+        "Rakefile" | "Gemfile" | "Guardfile" | "Vagrantfile" | "Podfile" | "Brewfile"
+        | "Fastfile" | "Appfile" | "Capfile" | "Dangerfile" | "config.ru" => Some("ruby"),
+        ".babelrc" | ".eslintrc" | ".prettierrc" => Some("json"),
+        _ => None,
+    }
+}
+
+/// Returns the grammar and highlight query for a canonical language name.
+///
+/// Runtime grammars take priority over built-in ones, and user query overrides over both.
+pub fn get_language_by_name(name: &str) -> Option<(Language, &'static str)> {
+    let (language, query) = runtime::runtime_language(name).or_else(|| builtin_language(name))?;
+    Some((
+        language,
+        runtime::highlight_query_override(name).unwrap_or(query),
+    ))
+}
+
+/// Returns the compiled-in grammar and highlight query for a canonical language name.
+pub(super) fn builtin_language(name: &str) -> Option<(Language, &'static str)> {
+    match name {
+        "bash" => Some((bash::language(), bash::HIGHLIGHT_QUERY)),
+        "cpp" => Some((cpp::language(), cpp::HIGHLIGHT_QUERY)),
+        "c" => Some((c::language(), c::HIGHLIGHT_QUERY)),
+        "clojure" => Some((clojure::language(), clojure::HIGHLIGHT_QUERY)),
+        "cmake" => Some((cmake::language(), cmake::HIGHLIGHT_QUERY)),
+        "csharp" => Some((csharp::language(), csharp::HIGHLIGHT_QUERY)),
+        "css" => Some((css::language(), css::HIGHLIGHT_QUERY)),
         "dart" => Some((dart::language(), dart::HIGHLIGHT_QUERY)),
-        "ex" | "exs" => Some((elixir::language(), elixir::HIGHLIGHT_QUERY)),
-        "erl" | "hrl" | "es" | "escript" => Some((erlang::language(), erlang::HIGHLIGHT_QUERY)),
+        "elixir" => Some((elixir::language(), elixir::HIGHLIGHT_QUERY)),
+        "erlang" => Some((erlang::language(), erlang::HIGHLIGHT_QUERY)),
         "go" => Some((go_lang::language(), go_lang::HIGHLIGHT_QUERY)),
-        "hs" | "lhs" => Some((haskell::language(), haskell::HIGHLIGHT_QUERY)),
-        "html" | "htm" => Some((html::language(), html::HIGHLIGHT_QUERY)),
+        "haskell" => Some((haskell::language(), haskell::HIGHLIGHT_QUERY)),
+        "html" => Some((html::language(), html::HIGHLIGHT_QUERY)),
         "java" => Some((java::language(), java::HIGHLIGHT_QUERY)),
-        "js" | "jsx" | "mjs" | "cjs" => Some((javascript::language(), javascript::HIGHLIGHT_QUERY)),
-        "json" | "jsonc" => Some((json::language(), json::HIGHLIGHT_QUERY)),
-        "kt" | "kts" => Some((kotlin::language(), kotlin::HIGHLIGHT_QUERY)),
+        "javascript" => Some((javascript::language(), javascript::HIGHLIGHT_QUERY)),
+        "json" => Some((json::language(), json::HIGHLIGHT_QUERY)),
+        "just" => Some((just::language(), just::HIGHLIGHT_QUERY)),
+        "kotlin" => Some((kotlin::language(), kotlin::HIGHLIGHT_QUERY)),
         "lua" => Some((lua::language(), lua::HIGHLIGHT_QUERY)),
-        "md" | "markdown" => Some((markdown::language(), markdown::HIGHLIGHT_QUERY)),
-        "php" | "php3" | "php4" | "php5" | "phtml" => Some((php::language(), php::HIGHLIGHT_QUERY)),
-        "py" | "pyw" => Some((python::language(), python::HIGHLIGHT_QUERY)),
-        "rb" | "rbw" | "rake" | "gemspec" => Some((ruby::language(), ruby::HIGHLIGHT_QUERY)),
-        "rs" => Some((rust::language(), rust::HIGHLIGHT_QUERY)),
-        "scala" | "sc" | "sbt" => Some((scala::language(), scala::HIGHLIGHT_QUERY)),
+        "make" => Some((make::language(), make::HIGHLIGHT_QUERY)),
+        "markdown" => Some((markdown::language(), markdown::HIGHLIGHT_QUERY)),
+        "markdown_inline" => Some((
+            markdown::inline_language(),
+            markdown::INLINE_HIGHLIGHT_QUERY,
+        )),
+        "php" => Some((php::language(), php::HIGHLIGHT_QUERY)),
+        "python" => Some((python::language(), python::HIGHLIGHT_QUERY)),
+        "ruby" => Some((ruby::language(), ruby::HIGHLIGHT_QUERY)),
+        "rust" => Some((rust::language(), rust::HIGHLIGHT_QUERY)),
+        "scala" => Some((scala::language(), scala::HIGHLIGHT_QUERY)),
+        "sql" => Some((sql::language(), sql::HIGHLIGHT_QUERY)),
         "svelte" => Some((svelte::language(), svelte::HIGHLIGHT_QUERY)),
         "swift" => Some((swift::language(), swift::HIGHLIGHT_QUERY)),
-        "ts" | "tsx" | "mts" | "cts" => Some((typescript::language(), typescript::HIGHLIGHT_QUERY)),
-        "xml" | "svg" | "xsl" | "xslt" => Some((xml::language(), xml::HIGHLIGHT_QUERY)),
-        "yaml" | "yml" => Some((yaml::language(), yaml::HIGHLIGHT_QUERY)),
+        "typescript" => Some((typescript::language(), typescript::HIGHLIGHT_QUERY)),
+        "xml" => Some((xml::language(), xml::HIGHLIGHT_QUERY)),
+        "yaml" => Some((yaml::language(), yaml::HIGHLIGHT_QUERY)),
         "zig" => Some((zig::language(), zig::HIGHLIGHT_QUERY)),
         _ => None,
     }
 }
+
+/// Returns the injection query for a canonical language name, if it embeds other languages.
+pub fn get_injection_query(name: &str) -> Option<&'static str> {
+    if let Some(query) = runtime::injection_query_override(name) {
+        return Some(query);
+    }
+    if runtime::runtime_language_name(name).is_some() {
+        return runtime::runtime_injection_query(name);
+    }
+
+    match name {
+        "go" => Some(go_lang::INJECTION_QUERY),
+        "html" => Some(html::INJECTION_QUERY),
+        "javascript" => Some(javascript::INJECTION_QUERY),
+        "markdown" => Some(markdown::INJECTION_QUERY),
+        "markdown_inline" => Some(markdown::INLINE_INJECTION_QUERY),
+        "python" => Some(python::INJECTION_QUERY),
+        "rust" => Some(rust::INJECTION_QUERY),
+        "svelte" => Some(svelte::INJECTION_QUERY),
+        "typescript" => Some(typescript::INJECTION_QUERY),
+        _ => None,
+    }
+}
+
+/// Resolves a language name from an injection (e.g. a fenced code block info string)
+/// to a canonical language name. Accepts canonical names, common aliases and file extensions.
+pub fn resolve_language_name(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_ascii_lowercase();
+    match name.as_str() {
+        "shell" | "sh" | "zsh" | "shell-script" => Some("bash"),
+        "makefile" => Some("make"),
+        "c++" => Some("cpp"),
+        "c#" | "cs" => Some("csharp"),
+        "golang" => Some("go"),
+        "markdown_inline" => Some("markdown_inline"),
+        _ => runtime::runtime_language_name(&name)
+            .or_else(|| language_name(Path::new(&format!("injected.{}", name))))
+            .or_else(|| {
+                language_name(Path::new(&format!(
+                    "injected.{}",
+                    canonical_extension(&name)?
+                )))
+            }),
+    }
+}
+
+// Map canonical names that are not file extensions (e.g. "rust") to one that is
+fn canonical_extension(name: &str) -> Option<&'static str> {
+    match name {
+        "bash" => Some("sh"),
+        "clojure" => Some("clj"),
+        "csharp" => Some("cs"),
+        "elixir" => Some("ex"),
+        "erlang" => Some("erl"),
+        "haskell" => Some("hs"),
+        "javascript" => Some("js"),
+        "kotlin" => Some("kt"),
+        "python" => Some("py"),
+        "ruby" => Some("rb"),
+        "rust" => Some("rs"),
+        "typescript" => Some("ts"),
+        _ => None,
+    }
+}
+
+/// Returns the language whose theme overrides apply to spans of `name`.
+/// Markdown's inline grammar is styled with `[syntax.markdown]`.
+pub fn theme_language(name: &'static str) -> &'static str {
+    match name {
+        "markdown_inline" => "markdown",
+        _ => name,
+    }
+}
@@ -0,0 +1,5 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_sequel::LANGUAGE.into()
+}
+
+pub const HIGHLIGHT_QUERY: &str = tree_sitter_sequel::HIGHLIGHTS_QUERY;
@@ -3,3 +3,25 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_svelte_ng::HIGHLIGHTS_QUERY;
+
+// Script and style blocks, honouring their `lang` attribute
+pub const INJECTION_QUERY: &str = r#"
+(script_element
+  (raw_text) @injection.content
+  (#set! injection.language "javascript"))
+
+((script_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @_lang)))
+  (raw_text) @injection.content)
+  (#eq? @_attr "lang")
+  (#any-of? @_lang "ts" "typescript")
+  (#set! injection.language "typescript"))
+
+(style_element
+  (raw_text) @injection.content
+  (#set! injection.language "css"))
+"#;
@@ -1,32 +1,120 @@
+mod detect;
 pub mod languages;
+mod runtime;
 
 use crate::theme::Theme;
 use ratatui::style::Color;
-use std::path::Path;
+use std::collections::HashMap;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Language, Parser, Query, QueryCursor};
+use tree_sitter::{InputEdit, Language, Parser, Query, QueryCursor, Range};
 
-pub use languages::get_language;
+pub use detect::{detect_language, LanguageRule};
+pub use languages::get_language_by_name;
+use languages::{resolve_language_name, theme_language};
+pub use runtime::load_runtime_languages;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Injections nested deeper than this (e.g. HTML in Markdown in Markdown) are not highlighted
+const MAX_INJECTION_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TokenType {
+    Attribute,
+    Builtin,
     Comment,
     Constant,
+    DiffAdded,
+    DiffRemoved,
+    Escape,
     Function,
+    Heading,
     Keyword,
     Label,
+    Link,
+    Macro,
     Number,
     Operator,
     Parameter,
     Property,
     Punctuation,
     String,
+    Tag,
     Type,
     Variable,
 }
 
 impl TokenType {
-    pub fn color(&self, theme: &Theme) -> Color {
+    pub const ALL: [TokenType; 22] = [
+        TokenType::Attribute,
+        TokenType::Builtin,
+        TokenType::Comment,
+        TokenType::Constant,
+        TokenType::DiffAdded,
+        TokenType::DiffRemoved,
+        TokenType::Escape,
+        TokenType::Function,
+        TokenType::Heading,
+        TokenType::Keyword,
+        TokenType::Label,
+        TokenType::Link,
+        TokenType::Macro,
+        TokenType::Number,
+        TokenType::Operator,
+        TokenType::Parameter,
+        TokenType::Property,
+        TokenType::Punctuation,
+        TokenType::String,
+        TokenType::Tag,
+        TokenType::Type,
+        TokenType::Variable,
+    ];
+
+    /// Name used for the token in theme files
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenType::Attribute => "attribute",
+            TokenType::Builtin => "builtin",
+            TokenType::Comment => "comment",
+            TokenType::Constant => "constant",
+            TokenType::DiffAdded => "diff_added",
+            TokenType::DiffRemoved => "diff_removed",
+            TokenType::Escape => "escape",
+            TokenType::Function => "function",
+            TokenType::Heading => "heading",
+            TokenType::Keyword => "keyword",
+            TokenType::Label => "label",
+            TokenType::Link => "link",
+            TokenType::Macro => "macro",
+            TokenType::Number => "number",
+            TokenType::Operator => "operator",
+            TokenType::Parameter => "parameter",
+            TokenType::Property => "property",
+            TokenType::Punctuation => "punctuation",
+            TokenType::String => "string",
+            TokenType::Tag => "tag",
+            TokenType::Type => "type",
+            TokenType::Variable => "variable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|token| token.name() == name)
+    }
+
+    /// Returns the color for a token of `language`, preferring its `[syntax.<language>]` overrides.
+    pub fn color_for(&self, theme: &Theme, language: &str) -> Color {
+        self.resolve_color(theme, theme.syntax_languages.get(language))
+    }
+
+    // Language overrides win over theme colors, and finer tokens the theme leaves unset
+    // fall back to the one they refine
+    fn resolve_color(&self, theme: &Theme, language: Option<&HashMap<TokenType, Color>>) -> Color {
+        if let Some(color) = language.and_then(|colors| colors.get(self)) {
+            return *color;
+        }
+
+        let refined = |color: Option<Color>, base: TokenType| {
+            color.unwrap_or_else(|| base.resolve_color(theme, language))
+        };
         match self {
             TokenType::Comment => theme.syntax_comment,
             TokenType::Constant => theme.syntax_constant,
@@ -41,24 +129,46 @@ impl TokenType {
             TokenType::String => theme.syntax_string,
             TokenType::Type => theme.syntax_type,
             TokenType::Variable => theme.syntax_variable,
+            TokenType::DiffAdded => theme.syntax_diff_added.unwrap_or(theme.file_tree_added),
+            TokenType::DiffRemoved => theme.syntax_diff_removed.unwrap_or(theme.file_tree_deleted),
+            TokenType::Attribute => refined(theme.syntax_attribute, TokenType::Keyword),
+            TokenType::Builtin => refined(theme.syntax_builtin, TokenType::Constant),
+            TokenType::Escape => refined(theme.syntax_escape, TokenType::Operator),
+            TokenType::Heading => refined(theme.syntax_heading, TokenType::String),
+            TokenType::Link => refined(theme.syntax_link, TokenType::String),
+            TokenType::Macro => refined(theme.syntax_macro, TokenType::Function),
+            TokenType::Tag => refined(theme.syntax_tag, TokenType::Type),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HighlightSpan {
     pub start: usize,
     pub end: usize,
     pub token_type: TokenType,
+    /// Language the span was highlighted as, which differs from the file's inside injections
+    pub language: &'static str,
 }
 
 pub struct Highlighter {
     parser: Parser,
     language: Option<Language>,
+    language_name: Option<&'static str>,
     query: Option<Query>,
     query_source: Option<String>,
+    injection_query: Option<Query>,
+    /// Parsers for embedded languages, created on first use (`None` if unsupported)
+    injected_languages: HashMap<&'static str, Option<InjectedLanguage>>,
+    language_rules: Vec<LanguageRule>,
     cached_tree: Option<tree_sitter::Tree>,
     cached_source: String,
+    /// Highlights of `cached_source`, shifted along with the edits applied since
+    cached_spans: Vec<HighlightSpan>,
+    /// Byte ranges of the next source touched by edits, which must be highlighted again
+    dirty_ranges: Vec<(usize, usize)>,
+    /// Whether `cached_tree` has been edited to match the next source to highlight
+    tree_edited: bool,
 }
 
 impl Clone for Highlighter {
@@ -71,14 +181,26 @@ impl Clone for Highlighter {
         } else {
             None
         };
+        let injection_query = self
+            .language
+            .as_ref()
+            .zip(self.language_name)
+            .and_then(|(lang, name)| load_injection_query(lang, name));
 
         Self {
             parser: new_parser,
             language: self.language.clone(),
+            language_name: self.language_name,
             query,
             query_source: self.query_source.clone(),
+            injection_query,
+            injected_languages: HashMap::new(),
+            language_rules: self.language_rules.clone(),
             cached_tree: None,
             cached_source: String::new(),
+            cached_spans: Vec::new(),
+            dirty_ranges: Vec::new(),
+            tree_edited: false,
         }
     }
 }
@@ -88,120 +210,565 @@ impl Highlighter {
         Self {
             parser: Parser::new(),
             language: None,
+            language_name: None,
             query: None,
             query_source: None,
+            injection_query: None,
+            injected_languages: HashMap::new(),
+            language_rules: Vec::new(),
             cached_tree: None,
             cached_source: String::new(),
+            cached_spans: Vec::new(),
+            dirty_ranges: Vec::new(),
+            tree_edited: false,
         }
     }
 
-    pub fn set_language_from_path(&mut self, path: &str) -> bool {
-        if let Some((language, query_source)) = get_language(Path::new(path)) {
-            if self.parser.set_language(&language).is_ok() {
-                if let Ok(query) = Query::new(&language, query_source) {
-                    self.language = Some(language);
-                    self.query = Some(query);
-                    self.query_source = Some(query_source.to_string());
-                    self.cached_tree = None;
-                    self.cached_source = String::new();
-                    return true;
+    /// Sets user rules mapping path globs to languages. When several match, the longest pattern wins.
+    pub fn set_language_rules(&mut self, mut rules: Vec<LanguageRule>) {
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.matcher.glob().glob().len()));
+        self.language_rules = rules;
+    }
+
+    /// Detects the language from the path and content (shebang, modelines) and switches to it.
+    pub fn set_language_for_file(&mut self, path: &str, content: &str) -> bool {
+        if let Some(name) = detect_language(path, content, &self.language_rules) {
+            if let Some((language, query_source)) = get_language_by_name(name) {
+                if self.parser.set_language(&language).is_ok() {
+                    if let Ok(query) = Query::new(&language, query_source) {
+                        self.injection_query = load_injection_query(&language, name);
+                        self.language = Some(language);
+                        self.language_name = Some(name);
+                        self.query = Some(query);
+                        self.query_source = Some(query_source.to_string());
+                        self.reset_cache();
+                        return true;
+                    }
                 }
             }
         }
         // Language not supported - clear previous language settings
         self.language = None;
+        self.language_name = None;
         self.query = None;
         self.query_source = None;
+        self.injection_query = None;
+        self.reset_cache();
+        false
+    }
+
+    #[cfg(test)]
+    pub fn tree(&self) -> Option<&tree_sitter::Tree> {
+        self.cached_tree.as_ref()
+    }
+
+    fn reset_cache(&mut self) {
         self.cached_tree = None;
         self.cached_source = String::new();
-        false
+        self.cached_spans.clear();
+        self.dirty_ranges.clear();
+        self.tree_edited = false;
     }
 
-    pub fn highlight(&mut self, source: &str) -> Vec<HighlightSpan> {
-        let mut spans = Vec::new();
+    /// Applies an edit to the last parsed tree so the next `highlight` call,
+    /// given the edited source, only re-parses the changed region.
+    pub fn edit(&mut self, edit: &InputEdit) {
+        let Some(tree) = &mut self.cached_tree else {
+            return;
+        };
+        tree.edit(edit);
+        self.tree_edited = true;
 
-        let Some(query) = &self.query else {
-            return spans;
+        let (start, old_end, new_end) = (edit.start_byte, edit.old_end_byte, edit.new_end_byte);
+        // Spans after the edit move with the text, those touching it are highlighted again
+        self.cached_spans.retain_mut(|span| {
+            if span.end <= start {
+                true
+            } else if span.start >= old_end {
+                span.start = span.start - old_end + new_end;
+                span.end = span.end - old_end + new_end;
+                true
+            } else {
+                false
+            }
+        });
+        for range in &mut self.dirty_ranges {
+            if range.1 < start {
+                continue;
+            }
+            if range.0 > old_end {
+                range.0 = range.0 - old_end + new_end;
+            } else {
+                range.0 = range.0.min(start);
+            }
+            range.1 = if range.1 >= old_end {
+                range.1 - old_end + new_end
+            } else {
+                new_end
+            };
+        }
+        self.dirty_ranges.push((start, new_end));
+    }
+
+    /// Highlights `source`. After `edit`, only the lines touched by the edits and by the
+    /// syntax changes they cause are queried again.
+    pub fn highlight(&mut self, source: &str) -> Vec<HighlightSpan> {
+        let Some(language) = self.language_name.filter(|_| self.query.is_some()) else {
+            return Vec::new();
         };
 
-        // Use incremental parsing only if source hasn't changed
-        let old_tree = if self.cached_source == source {
-            self.cached_tree.as_ref()
+        if !self.tree_edited && self.cached_tree.is_some() && self.cached_source == source {
+            return self.cached_spans.clone();
+        }
+
+        // Use incremental parsing only if the tree was edited to match the new source
+        let old_tree = if self.tree_edited {
+            self.cached_tree.take()
         } else {
             None
         };
+        let Some(tree) = self.parser.parse(source, old_tree.as_ref()) else {
+            self.reset_cache();
+            return Vec::new();
+        };
+
+        let language = theme_language(language);
+        let spans = match old_tree {
+            Some(old_tree) => {
+                let mut dirty = std::mem::take(&mut self.dirty_ranges);
+                dirty.extend(
+                    old_tree
+                        .changed_ranges(&tree)
+                        .map(|range| (range.start_byte, range.end_byte)),
+                );
+                let dirty = line_ranges(&dirty, source);
+                let (mut spans, dirty) = self.highlight_ranges(language, &tree, source, dirty);
 
-        let Some(tree) = self.parser.parse(source, old_tree) else {
-            return spans;
+                // Keep the cached highlights outside the re-highlighted ranges
+                let mut kept = std::mem::take(&mut self.cached_spans);
+                kept.retain(|span| !dirty.iter().any(|range| overlaps(span, range)));
+                // Empty nodes at the end of a range are both kept and matched again
+                spans.retain(|span| {
+                    let first = kept.partition_point(|k| k.start < span.start);
+                    !kept[first..]
+                        .iter()
+                        .take_while(|k| k.start == span.start)
+                        .any(|k| k == span)
+                });
+                kept.extend(spans);
+                kept
+            }
+            None => {
+                let full = vec![(0, source.len())];
+                self.highlight_ranges(language, &tree, source, full).0
+            }
         };
 
-        // Cache the tree and source for next incremental parse (clone needed because matches borrows tree)
-        self.cached_tree = Some(tree.clone());
+        self.cached_tree = Some(tree);
         self.cached_source = source.to_string();
+        self.cached_spans = spans;
+        self.cached_spans.sort_by_key(|span| span.start);
+        self.dirty_ranges.clear();
+        self.tree_edited = false;
+        self.cached_spans.clone()
+    }
+
+    // Highlights the given byte ranges, widened until they cover every captured node and
+    // injection they touch, and returns the spans with the widened ranges
+    fn highlight_ranges(
+        &mut self,
+        language: &'static str,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        mut ranges: Vec<(usize, usize)>,
+    ) -> (Vec<HighlightSpan>, Vec<(usize, usize)>) {
+        let Some(query) = &self.query else {
+            return (Vec::new(), ranges);
+        };
 
-        let mut cursor = QueryCursor::new();
-        let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+        loop {
+            let mut injections: Vec<Injection> = Vec::new();
+            let mut spans = Vec::new();
+            for &(start, end) in &ranges {
+                if let Some(injection_query) = &self.injection_query {
+                    for injection in find_injections(injection_query, tree, source, start..end) {
+                        if !injections.iter().any(|i| i.range == injection.range) {
+                            injections.push(injection);
+                        }
+                    }
+                }
+                spans.extend(collect_highlights(
+                    query,
+                    tree,
+                    source,
+                    language,
+                    start..end,
+                ));
+            }
 
-        while let Some(query_match) = matches.next() {
-            for capture in query_match.captures {
-                let node = capture.node;
-                let capture_name = &query.capture_names()[capture.index as usize];
+            let touched = injections
+                .iter()
+                .map(|i| (i.range.start_byte, i.range.end_byte))
+                .chain(spans.iter().map(|span| (span.start, span.end)));
+            let widened = merge_ranges(ranges.iter().copied().chain(touched).collect());
+            if widened != ranges {
+                ranges = widened;
+                continue;
+            }
+
+            spans.dedup();
+            highlight_injections(
+                &mut self.injected_languages,
+                injections,
+                source,
+                1,
+                &mut spans,
+            );
+            return (spans, ranges);
+        }
+    }
+}
 
-                // Handle dotted capture names like "keyword.function" -> "keyword"
-                let base_name = capture_name.split('.').next().unwrap_or(capture_name);
+// Empty spans count as one byte wide, so those at the start of a range overlap it
+fn overlaps(span: &HighlightSpan, &(start, end): &(usize, usize)) -> bool {
+    span.start < end && start < span.end.max(span.start + 1)
+}
 
-                let token_type = match base_name {
-                    "annotation" | "attribute" | "decorator" => TokenType::Keyword,
-                    "boolean" => TokenType::Constant,
-                    "character" => TokenType::String,
-                    "class" | "constructor" | "enum" | "interface" | "struct" | "trait" => {
-                        TokenType::Type
-                    }
-                    "comment" => TokenType::Comment,
-                    "conditional" | "exception" | "include" | "repeat" | "storageclass" => {
-                        TokenType::Keyword
-                    }
-                    "constant" => TokenType::Constant,
-                    "delimiter" => TokenType::Punctuation,
-                    "escape" => TokenType::Operator,
-                    "field" => TokenType::Property,
-                    "float" => TokenType::Number,
-                    "function" => TokenType::Function,
-                    "identifier" => TokenType::Variable,
-                    "keyword" => TokenType::Keyword,
-                    "label" => TokenType::Label,
-                    "macro" | "method" => TokenType::Function,
-                    "module" | "namespace" => TokenType::Type,
-                    "number" => TokenType::Number,
-                    "operator" => TokenType::Operator,
-                    "parameter" => TokenType::Parameter,
-                    "property" => TokenType::Property,
-                    "punctuation" => TokenType::Punctuation,
-                    "regexp" => TokenType::String,
-                    "special" => TokenType::Operator,
-                    "string" => TokenType::String,
-                    "tag" => TokenType::Type,
-                    "text" => TokenType::String,
-                    "type" => TokenType::Type,
-                    "variable" => TokenType::Variable,
-                    // Skip internal/special markers
-                    "__name__" | "_name" | "_op" | "_type" | "embedded" | "none" | "spell" => {
-                        continue
-                    }
-                    _ => continue,
-                };
+// Widens byte ranges to whole lines of `source`, merging the ones that overlap
+fn line_ranges(ranges: &[(usize, usize)], source: &str) -> Vec<(usize, usize)> {
+    let widened = ranges
+        .iter()
+        .map(|&(start, end)| {
+            let start = start.min(source.len());
+            let end = end.clamp(start, source.len());
+            let bytes = source.as_bytes();
+            let line_start = bytes[..start]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            let line_end = bytes[end..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(source.len(), |i| end + i + 1);
+            (line_start, line_end)
+        })
+        .collect();
+    merge_ranges(widened)
+}
 
-                spans.push(HighlightSpan {
-                    start: node.start_byte(),
-                    end: node.end_byte(),
-                    token_type,
-                });
+fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// A language embedded in another one (e.g. a fenced code block in Markdown)
+struct InjectedLanguage {
+    name: &'static str,
+    parser: Parser,
+    query: Query,
+    injection_query: Option<Query>,
+}
+
+impl InjectedLanguage {
+    fn new(name: &'static str) -> Option<Self> {
+        let (language, query_source) = get_language_by_name(name)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        let query = Query::new(&language, query_source).ok()?;
+        Some(Self {
+            name: theme_language(name),
+            parser,
+            injection_query: load_injection_query(&language, name),
+            query,
+        })
+    }
+
+    // Parse only `range` of the source so that byte offsets stay relative to the whole file
+    fn highlight(
+        &mut self,
+        source: &str,
+        range: Range,
+    ) -> Option<(Vec<HighlightSpan>, Vec<Injection>)> {
+        self.parser.set_included_ranges(&[range]).ok()?;
+        let tree = self.parser.parse(source, None)?;
+        let bytes = range.start_byte..range.end_byte;
+        let spans = collect_highlights(&self.query, &tree, source, self.name, bytes.clone());
+        let injections = self
+            .injection_query
+            .as_ref()
+            .map(|query| find_injections(query, &tree, source, bytes))
+            .unwrap_or_default();
+        Some((spans, injections))
+    }
+}
+
+struct Injection {
+    language: &'static str,
+    range: Range,
+}
+
+// A broken injection query only disables injections, not highlighting
+fn load_injection_query(language: &Language, name: &str) -> Option<Query> {
+    Query::new(language, languages::get_injection_query(name)?).ok()
+}
+
+// Highlight injected ranges with their own grammar and layer the result on top of `spans`
+fn highlight_injections(
+    injected_languages: &mut HashMap<&'static str, Option<InjectedLanguage>>,
+    injections: Vec<Injection>,
+    source: &str,
+    depth: usize,
+    spans: &mut Vec<HighlightSpan>,
+) {
+    if depth > MAX_INJECTION_DEPTH || injections.is_empty() {
+        return;
+    }
+
+    let mut injected_spans = Vec::new();
+    for injection in injections {
+        // Take the parser out of the cache so nested injections can use the cache too
+        let mut injected = injected_languages
+            .remove(injection.language)
+            .unwrap_or_else(|| InjectedLanguage::new(injection.language));
+
+        if let Some((mut layer, nested)) = injected
+            .as_mut()
+            .and_then(|injected| injected.highlight(source, injection.range))
+        {
+            highlight_injections(injected_languages, nested, source, depth + 1, &mut layer);
+            injected_spans.extend(layer);
+        }
+
+        injected_languages.insert(injection.language, injected);
+    }
+
+    let covered: Vec<(usize, usize)> = injected_spans.iter().map(|s| (s.start, s.end)).collect();
+    *spans = subtract_ranges(std::mem::take(spans), &covered, |_, _| true);
+    spans.extend(injected_spans);
+}
+
+// Finds the injections that intersect `bytes`
+fn find_injections(
+    query: &Query,
+    tree: &tree_sitter::Tree,
+    source: &str,
+    bytes: std::ops::Range<usize>,
+) -> Vec<Injection> {
+    let content_index = query.capture_index_for_name("injection.content");
+    let language_index = query.capture_index_for_name("injection.language");
+
+    // Later patterns are more specific, so they win for the same content node
+    let mut by_range: HashMap<(usize, usize), (usize, Injection)> = HashMap::new();
+
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(bytes);
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let captured = |index: Option<u32>| {
+            query_match
+                .captures
+                .iter()
+                .find(|capture| Some(capture.index) == index)
+                .map(|capture| capture.node)
+        };
+        let Some(content) = captured(content_index) else {
+            continue;
+        };
+
+        let language_name = captured(language_index)
+            .and_then(|node| node.utf8_text(source.as_bytes()).ok())
+            .map(str::to_string)
+            .or_else(|| {
+                query
+                    .property_settings(query_match.pattern_index)
+                    .iter()
+                    .find(|setting| &*setting.key == "injection.language")
+                    .and_then(|setting| setting.value.as_deref().map(str::to_string))
+            });
+        let Some(language) = language_name.as_deref().and_then(resolve_language_name) else {
+            continue;
+        };
+
+        let key = (content.start_byte(), content.end_byte());
+        let pattern = query_match.pattern_index;
+        if by_range
+            .get(&key)
+            .is_none_or(|(existing, _)| pattern >= *existing)
+        {
+            let range = content.range();
+            by_range.insert(key, (pattern, Injection { language, range }));
+        }
+    }
+
+    let mut injections: Vec<Injection> = by_range.into_values().map(|(_, i)| i).collect();
+    injections.sort_by_key(|injection| injection.range.start_byte);
+    injections
+}
+
+// Collects the highlights of the nodes that intersect `bytes`
+fn collect_highlights(
+    query: &Query,
+    tree: &tree_sitter::Tree,
+    source: &str,
+    language: &'static str,
+    bytes: std::ops::Range<usize>,
+) -> Vec<HighlightSpan> {
+    let mut spans = Vec::new();
+    // Nodes captured as `@none` clear the highlight of enclosing nodes
+    let mut cleared = Vec::new();
+
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(bytes);
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+
+    while let Some(query_match) = matches.next() {
+        for capture in query_match.captures {
+            let node = capture.node;
+            let capture_name = &query.capture_names()[capture.index as usize];
+
+            if capture_name.split('.').next() == Some("none") {
+                cleared.push((node.start_byte(), node.end_byte()));
+                continue;
             }
+            let Some(token_type) = token_type_for_capture(capture_name) else {
+                continue;
+            };
+
+            spans.push(HighlightSpan {
+                start: node.start_byte(),
+                end: node.end_byte(),
+                token_type,
+                language,
+            });
         }
+    }
 
-        spans.sort_by_key(|span| span.start);
+    if cleared.is_empty() {
         spans
+    } else {
+        // Only enclosing spans are cleared, highlights inside the node are kept
+        subtract_ranges(spans, &cleared, |span, (start, end)| {
+            span.start <= start && end <= span.end && (span.start, span.end) != (start, end)
+        })
+    }
+}
+
+fn token_type_for_capture(capture_name: &str) -> Option<TokenType> {
+    // Handle dotted capture names like "keyword.function" -> "keyword"
+    let mut parts = capture_name.split('.');
+    let base_name = parts.next().unwrap_or(capture_name);
+    let modifiers: Vec<&str> = parts.collect();
+
+    // Captures refined by a modifier get a token of their own
+    let refined = match (base_name, modifiers.as_slice()) {
+        ("tag", ["attribute", ..]) => Some(TokenType::Attribute),
+        ("tag", ["delimiter", ..]) => Some(TokenType::Punctuation),
+        ("text", ["title", ..]) | ("markup", ["heading", ..]) => Some(TokenType::Heading),
+        ("text", ["uri" | "reference", ..])
+        | ("markup", ["link", ..])
+        | ("string", ["special", "url", ..]) => Some(TokenType::Link),
+        ("diff", ["plus" | "add" | "addition", ..])
+        | ("text", ["diff", "add", ..])
+        | ("markup", ["inserted", ..]) => Some(TokenType::DiffAdded),
+        ("diff", ["minus" | "delete" | "deletion", ..])
+        | ("text", ["diff", "delete", ..])
+        | ("markup", ["deleted", ..]) => Some(TokenType::DiffRemoved),
+        _ if modifiers.contains(&"escape") => Some(TokenType::Escape),
+        // Builtin types and constants keep their base token, e.g. `i32` or `true`
+        ("type" | "constant", _) => None,
+        _ if modifiers.contains(&"macro") => Some(TokenType::Macro),
+        _ if modifiers.contains(&"builtin") => Some(TokenType::Builtin),
+        _ => None,
+    };
+    if refined.is_some() {
+        return refined;
+    }
+
+    let token_type = match base_name {
+        "annotation" | "attribute" | "decorator" => TokenType::Attribute,
+        "boolean" => TokenType::Constant,
+        "character" => TokenType::String,
+        "class" | "constructor" | "enum" | "interface" | "struct" | "trait" => TokenType::Type,
+        "comment" => TokenType::Comment,
+        "conditional" | "exception" | "include" | "repeat" | "storageclass" => TokenType::Keyword,
+        "constant" => TokenType::Constant,
+        "delimiter" => TokenType::Punctuation,
+        "escape" => TokenType::Escape,
+        "field" => TokenType::Property,
+        "float" => TokenType::Number,
+        "function" => TokenType::Function,
+        "identifier" => TokenType::Variable,
+        "keyword" => TokenType::Keyword,
+        "label" => TokenType::Label,
+        "macro" => TokenType::Macro,
+        "method" => TokenType::Function,
+        "module" | "namespace" => TokenType::Type,
+        "number" => TokenType::Number,
+        "operator" => TokenType::Operator,
+        "parameter" => TokenType::Parameter,
+        "property" => TokenType::Property,
+        "punctuation" => TokenType::Punctuation,
+        "regexp" => TokenType::String,
+        "special" => TokenType::Operator,
+        "string" => TokenType::String,
+        "tag" => TokenType::Tag,
+        "text" => TokenType::String,
+        "type" => TokenType::Type,
+        "variable" => TokenType::Variable,
+        // Skip internal/special markers
+        "__name__" | "_name" | "_op" | "_type" | "embedded" | "spell" => return None,
+        _ => return None,
+    };
+    Some(token_type)
+}
+
+// Remove `ranges` from the spans for which `applies` returns true, splitting spans as needed
+fn subtract_ranges<F>(
+    spans: Vec<HighlightSpan>,
+    ranges: &[(usize, usize)],
+    applies: F,
+) -> Vec<HighlightSpan>
+where
+    F: Fn(&HighlightSpan, (usize, usize)) -> bool,
+{
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable();
+
+    let mut result = Vec::with_capacity(spans.len());
+    for span in spans {
+        let mut pieces = vec![(span.start, span.end)];
+        let first = ranges.partition_point(|&(_, end)| end <= span.start);
+        for &range in ranges[first..]
+            .iter()
+            .take_while(|(start, _)| *start < span.end)
+        {
+            if !applies(&span, range) {
+                continue;
+            }
+            pieces = pieces
+                .into_iter()
+                .flat_map(|(start, end)| {
+                    [(start, end.min(range.0)), (start.max(range.1), end)]
+                        .into_iter()
+                        .filter(|(start, end)| start < end)
+                })
+                .collect();
+        }
+        result.extend(pieces.into_iter().map(|(start, end)| HighlightSpan {
+            start,
+            end,
+            token_type: span.token_type,
+            language: span.language,
+        }));
     }
+    result
 }
 
 impl Default for Highlighter {
@@ -0,0 +1,322 @@
+use libloading::Library;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Query};
+use tree_sitter_language::LanguageFn;
+
+const HIGHLIGHTS_FILE: &str = "highlights.scm";
+const INJECTIONS_FILE: &str = "injections.scm";
+// Maps languages to the file extensions that use them, beside the grammars
+const MANIFEST_FILE: &str = "languages.toml";
+
+/// A grammar loaded from a shared library at runtime
+struct RuntimeGrammar {
+    language: Language,
+    highlight_query: &'static str,
+    injection_query: Option<&'static str>,
+}
+
+/// Grammars and query overrides loaded from the user config directory
+#[derive(Default)]
+struct RuntimeLanguages {
+    grammars: HashMap<&'static str, RuntimeGrammar>,
+    highlight_overrides: HashMap<String, &'static str>,
+    injection_overrides: HashMap<String, &'static str>,
+    // File extension to language, from the manifest
+    extensions: HashMap<String, &'static str>,
+    // Languages point into these libraries, so they must never be unloaded
+    _libraries: Vec<Library>,
+}
+
+static RUNTIME: OnceLock<RuntimeLanguages> = OnceLock::new();
+
+/// Loads grammar libraries from `grammars_dir` and query overrides from `queries_dir`.
+///
+/// A grammar `<lang>.so` (`.dylib` on macOS, `.dll` on Windows) must export `tree_sitter_<lang>`
+/// and have its queries in `<lang>/highlights.scm` (and optionally `<lang>/injections.scm`)
+/// beside it. `languages.toml` there maps languages to file extensions, e.g. `hcl = ["tf"]`.
+/// Overrides live in `queries_dir/<lang>/highlights.scm` and `injections.scm`.
+///
+/// Only the first call has an effect. Returns a warning for every entry that could not be loaded.
+pub fn load_runtime_languages(grammars_dir: &Path, queries_dir: &Path) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if RUNTIME.get().is_some() {
+        return warnings;
+    }
+
+    let runtime = RuntimeLanguages::load(grammars_dir, queries_dir, &mut warnings);
+    let _ = RUNTIME.set(runtime);
+    warnings
+}
+
+impl RuntimeLanguages {
+    fn load(grammars_dir: &Path, queries_dir: &Path, warnings: &mut Vec<String>) -> Self {
+        let mut runtime = Self::default();
+        load_grammars(grammars_dir, &mut runtime, warnings);
+        load_manifest(grammars_dir, &mut runtime, warnings);
+        load_query_overrides(queries_dir, &mut runtime, warnings);
+        runtime
+    }
+}
+
+fn load_grammars(dir: &Path, runtime: &mut RuntimeLanguages, warnings: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    for path in paths {
+        if path.extension().and_then(|ext| ext.to_str()) != Some(std::env::consts::DLL_EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let name = name.to_ascii_lowercase();
+
+        match load_grammar(&path, &name, &dir.join(&name)) {
+            Ok((library, grammar)) => {
+                runtime.grammars.insert(leak(name), grammar);
+                runtime._libraries.push(library);
+            }
+            Err(message) => warnings.push(format!("{}: {}", path.display(), message)),
+        }
+    }
+}
+
+fn load_grammar(
+    path: &Path,
+    name: &str,
+    query_dir: &Path,
+) -> Result<(Library, RuntimeGrammar), String> {
+    let symbol = format!("tree_sitter_{}", name.replace('-', "_"));
+
+    // SAFETY: loading a user-provided grammar library runs its initializers, and its
+    // language function takes no arguments and returns a static TSLanguage pointer.
+    let library = unsafe { Library::new(path) }.map_err(|e| e.to_string())?;
+    let function = unsafe { library.get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes()) }
+        .map(|function| *function)
+        .map_err(|_| format!("missing symbol `{}`", symbol))?;
+    let language = Language::new(unsafe { LanguageFn::from_raw(function) });
+
+    let highlight_query = fs::read_to_string(query_dir.join(HIGHLIGHTS_FILE))
+        .map_err(|_| format!("missing {}", query_dir.join(HIGHLIGHTS_FILE).display()))?;
+    Query::new(&language, &highlight_query)
+        .map_err(|e| format!("invalid {}: {}", HIGHLIGHTS_FILE, e))?;
+    let injection_query = fs::read_to_string(query_dir.join(INJECTIONS_FILE)).ok();
+
+    Ok((
+        library,
+        RuntimeGrammar {
+            language,
+            highlight_query: leak(highlight_query),
+            injection_query: injection_query.map(leak),
+        },
+    ))
+}
+
+fn load_manifest(dir: &Path, runtime: &mut RuntimeLanguages, warnings: &mut Vec<String>) {
+    let path = dir.join(MANIFEST_FILE);
+    let Ok(source) = fs::read_to_string(&path) else {
+        return;
+    };
+    let manifest: HashMap<String, Vec<String>> = match toml::from_str(&source) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            warnings.push(format!("{}: {}", path.display(), e.message()));
+            return;
+        }
+    };
+
+    for (name, extensions) in manifest {
+        let name = name.to_ascii_lowercase();
+        let language = match runtime.grammars.get_key_value(name.as_str()) {
+            Some((name, _)) => *name,
+            None if super::languages::builtin_language(&name).is_some() => leak(name),
+            None => {
+                warnings.push(format!("{}: unknown language '{}'", path.display(), name));
+                continue;
+            }
+        };
+        for extension in extensions {
+            runtime.extensions.insert(extension, language);
+        }
+    }
+}
+
+fn load_query_overrides(dir: &Path, runtime: &mut RuntimeLanguages, warnings: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let name = name.to_ascii_lowercase();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let language = runtime
+            .grammars
+            .get(name.as_str())
+            .map(|grammar| grammar.language.clone())
+            .or_else(|| super::languages::builtin_language(&name).map(|(language, _)| language));
+        let Some(language) = language else {
+            warnings.push(format!("{}: unknown language", path.display()));
+            continue;
+        };
+
+        for (file, overrides) in [
+            (HIGHLIGHTS_FILE, &mut runtime.highlight_overrides),
+            (INJECTIONS_FILE, &mut runtime.injection_overrides),
+        ] {
+            let query_path = path.join(file);
+            let Ok(source) = fs::read_to_string(&query_path) else {
+                continue;
+            };
+            match Query::new(&language, &source) {
+                Ok(_) => {
+                    overrides.insert(name.clone(), leak(source));
+                }
+                Err(e) => warnings.push(format!("{}: {}", query_path.display(), e)),
+            }
+        }
+    }
+}
+
+// Loaded once per process, so the queries can live for the rest of it
+fn leak(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
+
+/// Returns the canonical name of a runtime grammar.
+pub(super) fn runtime_language_name(name: &str) -> Option<&'static str> {
+    let (name, _) = RUNTIME.get()?.grammars.get_key_value(name)?;
+    Some(name)
+}
+
+/// Returns the language the manifest maps a file extension to.
+pub(super) fn manifest_language(extension: &str) -> Option<&'static str> {
+    RUNTIME.get()?.extensions.get(extension).copied()
+}
+
+/// Returns a runtime grammar and its highlight query.
+pub(super) fn runtime_language(name: &str) -> Option<(Language, &'static str)> {
+    let grammar = RUNTIME.get()?.grammars.get(name)?;
+    Some((grammar.language.clone(), grammar.highlight_query))
+}
+
+pub(super) fn runtime_injection_query(name: &str) -> Option<&'static str> {
+    RUNTIME.get()?.grammars.get(name)?.injection_query
+}
+
+/// Returns the user's highlight query for a language, if overridden.
+pub(super) fn highlight_query_override(name: &str) -> Option<&'static str> {
+    RUNTIME.get()?.highlight_overrides.get(name).copied()
+}
+
+/// Returns the user's injection query for a language, if overridden.
+pub(super) fn injection_query_override(name: &str) -> Option<&'static str> {
+    RUNTIME.get()?.injection_overrides.get(name).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Empty directory removed at the end of the test
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "gitlogue_runtime_{}_{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            let path = self.0.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn load(grammars: &TempDir, queries: &TempDir) -> (RuntimeLanguages, Vec<String>) {
+        let mut warnings = Vec::new();
+        let runtime = RuntimeLanguages::load(&grammars.0, &queries.0, &mut warnings);
+        (runtime, warnings)
+    }
+
+    #[test]
+    fn test_manifest_maps_extensions() {
+        let grammars = TempDir::new("manifest");
+        let queries = TempDir::new("manifest_queries");
+        grammars.write(MANIFEST_FILE, "ruby = [\"cr\", \"rbi\"]\nnope = [\"x\"]\n");
+
+        let (runtime, warnings) = load(&grammars, &queries);
+        assert_eq!(runtime.extensions.get("cr"), Some(&"ruby"));
+        assert_eq!(runtime.extensions.get("rbi"), Some(&"ruby"));
+        assert!(!runtime.extensions.contains_key("x"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].ends_with("unknown language 'nope'"));
+    }
+
+    #[test]
+    fn test_invalid_query_override_is_skipped_with_a_warning() {
+        let grammars = TempDir::new("queries_grammars");
+        let queries = TempDir::new("queries");
+        queries.write("rust/highlights.scm", "(not_a_rust_node) @keyword");
+        queries.write("python/highlights.scm", "(identifier) @variable");
+        queries.write("klingon/highlights.scm", "(identifier) @variable");
+
+        let (runtime, mut warnings) = load(&grammars, &queries);
+        assert!(!runtime.highlight_overrides.contains_key("rust"));
+        assert!(runtime.highlight_overrides.contains_key("python"));
+        warnings.sort();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].ends_with("klingon: unknown language"));
+        assert!(warnings[1].contains("rust/highlights.scm"));
+    }
+
+    // Any shared library without a tree-sitter grammar in it, such as libc
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_library_without_grammar_symbol_is_skipped_with_a_warning() {
+        let maps = fs::read_to_string("/proc/self/maps").unwrap();
+        let Some(libc) = maps
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(5))
+            .find(|path| path.contains("/libc.so") || path.contains("/libc-"))
+        else {
+            return;
+        };
+
+        let grammars = TempDir::new("symbol");
+        let queries = TempDir::new("symbol_queries");
+        std::os::unix::fs::symlink(libc, grammars.0.join("nosymbol.so")).unwrap();
+
+        let (runtime, warnings) = load(&grammars, &queries);
+        assert!(runtime.grammars.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].ends_with("missing symbol `tree_sitter_nosymbol`"));
+    }
+}
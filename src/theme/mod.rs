@@ -2,6 +2,17 @@ mod themes;
 
 use anyhow::{Context, Result};
 use ratatui::style::Color;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::syntax::{languages::resolve_language_name, TokenType};
+
+const DEFAULT_BASE_THEME: &str = "tokyo-night";
+
+// Descriptive keys of theme files, which do not affect colors
+const THEME_FILE_INFO: [&str; 4] = ["name", "description", "author", "variant"];
 
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -16,6 +27,10 @@ pub struct Theme {
     pub editor_cursor_char_bg: Color,
     pub editor_cursor_char_fg: Color,
     pub editor_cursor_line_bg: Color,
+    pub editor_gutter_added: Color,
+    pub editor_gutter_modified: Color,
+    pub editor_gutter_deleted: Color,
+    pub editor_typed_line_bg: Color, // Faded over the background of freshly typed lines
 
     // File tree colors
     pub file_tree_added: Color,
@@ -59,6 +74,20 @@ pub struct Theme {
     pub syntax_parameter: Color,
     pub syntax_property: Color,
     pub syntax_label: Color,
+
+    // Finer syntax colors, falling back to the color noted beside them when unset
+    pub syntax_tag: Option<Color>,          // type
+    pub syntax_attribute: Option<Color>,    // keyword
+    pub syntax_builtin: Option<Color>,      // constant
+    pub syntax_macro: Option<Color>,        // function
+    pub syntax_heading: Option<Color>,      // string
+    pub syntax_link: Option<Color>,         // string
+    pub syntax_escape: Option<Color>,       // operator
+    pub syntax_diff_added: Option<Color>,   // file_tree_added
+    pub syntax_diff_removed: Option<Color>, // file_tree_deleted
+
+    // Token colors for a single language (`[syntax.<language>]` in theme files)
+    pub syntax_languages: HashMap<String, HashMap<TokenType, Color>>,
 }
 
 impl Default for Theme {
@@ -92,6 +121,162 @@ impl Theme {
         }
     }
 
+    /// Load theme by name, preferring a user theme file `<name>.toml` in `themes_dir`
+    pub fn load_from_dir(name: &str, themes_dir: &Path) -> Result<Self> {
+        let path = themes_dir.join(format!("{}.toml", name));
+        if !path.is_file() {
+            return Self::load(name);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read theme file: {}", path.display()))?;
+        Self::from_toml(&content)
+            .with_context(|| format!("Failed to parse theme file: {}", path.display()))
+    }
+
+    /// Parse a theme file, applying its colors on top of the built-in theme it extends
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let mut file: toml::Table = toml::from_str(content)?;
+        let base = match file.remove("extends") {
+            Some(toml::Value::String(name)) => name,
+            Some(_) => anyhow::bail!("Expected a theme name for extends"),
+            None => DEFAULT_BASE_THEME.to_string(),
+        };
+        let mut theme = Self::load(&base)?;
+
+        for (key, value) in &file {
+            match (key.as_str(), value) {
+                (key, _) if THEME_FILE_INFO.contains(&key) => {}
+                ("separator", color) => theme.separator = parse_color(color, key)?,
+                ("syntax", toml::Value::Table(tokens)) => theme.set_syntax_colors(tokens)?,
+                (section, toml::Value::Table(colors)) => {
+                    for (name, color) in colors {
+                        let entry = format!("{}.{}", section, name);
+                        let field = theme
+                            .color_field(section, name)
+                            .with_context(|| format!("Unknown theme color: {}", entry))?;
+                        *field = parse_color(color, &entry)?;
+                    }
+                }
+                _ => anyhow::bail!("Unknown theme entry: {}", key),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    // Applies `[syntax]` token colors and `[syntax.<language>]` tables
+    fn set_syntax_colors(&mut self, tokens: &toml::Table) -> Result<()> {
+        for (key, value) in tokens {
+            // Colors can be tables too, so tables named after a token are colors
+            let colors = match value {
+                toml::Value::Table(colors) if TokenType::from_name(key).is_none() => colors,
+                color => {
+                    let color = parse_color(color, &format!("syntax.{}", key))?;
+                    self.set_token_color(parse_token(key)?, color);
+                    continue;
+                }
+            };
+
+            let language = resolve_language_name(key)
+                .with_context(|| format!("Unknown language: [syntax.{}]", key))?;
+            let overrides = self
+                .syntax_languages
+                .entry(language.to_string())
+                .or_default();
+            for (token, color) in colors {
+                let color = parse_color(color, &format!("syntax.{}.{}", key, token))?;
+                overrides.insert(parse_token(token)?, color);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_token_color(&mut self, token: TokenType, color: Color) {
+        match token {
+            TokenType::Comment => self.syntax_comment = color,
+            TokenType::Constant => self.syntax_constant = color,
+            TokenType::Function => self.syntax_function = color,
+            TokenType::Keyword => self.syntax_keyword = color,
+            TokenType::Label => self.syntax_label = color,
+            TokenType::Number => self.syntax_number = color,
+            TokenType::Operator => self.syntax_operator = color,
+            TokenType::Parameter => self.syntax_parameter = color,
+            TokenType::Property => self.syntax_property = color,
+            TokenType::Punctuation => self.syntax_punctuation = color,
+            TokenType::String => self.syntax_string = color,
+            TokenType::Type => self.syntax_type = color,
+            TokenType::Variable => self.syntax_variable = color,
+            TokenType::Attribute => self.syntax_attribute = Some(color),
+            TokenType::Builtin => self.syntax_builtin = Some(color),
+            TokenType::DiffAdded => self.syntax_diff_added = Some(color),
+            TokenType::DiffRemoved => self.syntax_diff_removed = Some(color),
+            TokenType::Escape => self.syntax_escape = Some(color),
+            TokenType::Heading => self.syntax_heading = Some(color),
+            TokenType::Link => self.syntax_link = Some(color),
+            TokenType::Macro => self.syntax_macro = Some(color),
+            TokenType::Tag => self.syntax_tag = Some(color),
+        }
+    }
+
+    // The UI color set by `<name>` in the `[<section>]` table of a theme file
+    fn color_field(&mut self, section: &str, name: &str) -> Option<&mut Color> {
+        let field = match (section, name) {
+            ("background", "left") => &mut self.background_left,
+            ("background", "right") => &mut self.background_right,
+
+            ("editor", "line_number") => &mut self.editor_line_number,
+            ("editor", "line_number_cursor") => &mut self.editor_line_number_cursor,
+            ("editor", "separator") => &mut self.editor_separator,
+            ("editor", "cursor_char_bg") => &mut self.editor_cursor_char_bg,
+            ("editor", "cursor_char_fg") => &mut self.editor_cursor_char_fg,
+            ("editor", "cursor_line_bg") => &mut self.editor_cursor_line_bg,
+            ("editor", "gutter_added") => &mut self.editor_gutter_added,
+            ("editor", "gutter_modified") => &mut self.editor_gutter_modified,
+            ("editor", "gutter_deleted") => &mut self.editor_gutter_deleted,
+            ("editor", "typed_line_bg") => &mut self.editor_typed_line_bg,
+
+            ("file_tree", "added") => &mut self.file_tree_added,
+            ("file_tree", "deleted") => &mut self.file_tree_deleted,
+            ("file_tree", "modified") => &mut self.file_tree_modified,
+            ("file_tree", "renamed") => &mut self.file_tree_renamed,
+            ("file_tree", "directory") => &mut self.file_tree_directory,
+            ("file_tree", "current_file_bg") => &mut self.file_tree_current_file_bg,
+            ("file_tree", "current_file_fg") => &mut self.file_tree_current_file_fg,
+            ("file_tree", "default") => &mut self.file_tree_default,
+            ("file_tree", "stats_added") => &mut self.file_tree_stats_added,
+            ("file_tree", "stats_deleted") => &mut self.file_tree_stats_deleted,
+
+            ("terminal", "command_input") => &mut self.terminal_command,
+            ("terminal", "command_output") => &mut self.terminal_output,
+            ("terminal", "cursor") => &mut self.terminal_cursor_bg,
+            ("terminal", "cursor_fg") => &mut self.terminal_cursor_fg,
+
+            ("status_bar", "hash") => &mut self.status_hash,
+            ("status_bar", "author") => &mut self.status_author,
+            ("status_bar", "date") => &mut self.status_date,
+            ("status_bar", "message") => &mut self.status_message,
+            ("status_bar", "no_commit") => &mut self.status_no_commit,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// List user themes (`*.toml`) in `themes_dir`
+    pub fn user_themes(themes_dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(themes_dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+
     /// Remove background colors for transparent terminal background
     pub fn with_transparent_background(mut self) -> Self {
         self.background_left = Color::Reset;
@@ -121,3 +306,185 @@ impl Theme {
         ]
     }
 }
+
+fn parse_token(name: &str) -> Result<TokenType> {
+    TokenType::from_name(name).with_context(|| {
+        let names: Vec<&str> = TokenType::ALL.iter().map(TokenType::name).collect();
+        format!(
+            "Unknown syntax token: {} (expected one of: {})",
+            name,
+            names.join(", ")
+        )
+    })
+}
+
+// Colors are `{ r, g, b }` tables, hex values, ANSI color names or 256-color indices
+fn parse_color(value: &toml::Value, entry: &str) -> Result<Color> {
+    let invalid = || anyhow::anyhow!("Invalid color for {}: {}", entry, value);
+    match value {
+        toml::Value::String(color) => Color::from_str(color).map_err(|_| invalid()),
+        toml::Value::Table(rgb) => {
+            let component = |name: &str| {
+                rgb.get(name)
+                    .and_then(toml::Value::as_integer)
+                    .and_then(|c| u8::try_from(c).ok())
+                    .ok_or_else(invalid)
+            };
+            if rgb.len() != 3 {
+                return Err(invalid());
+            }
+            Ok(Color::Rgb(
+                component("r")?,
+                component("g")?,
+                component("b")?,
+            ))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes_color_finer_tokens() {
+        for name in Theme::available_themes() {
+            let theme = Theme::load(name).unwrap();
+            for color in [
+                theme.syntax_tag,
+                theme.syntax_attribute,
+                theme.syntax_builtin,
+                theme.syntax_macro,
+                theme.syntax_heading,
+                theme.syntax_link,
+                theme.syntax_escape,
+            ] {
+                assert!(color.is_some(), "{} leaves a finer token unset", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unset_finer_tokens_fall_back_within_the_language() {
+        let mut theme = Theme::load("dracula").unwrap();
+        theme.syntax_tag = None;
+        theme.syntax_macro = None;
+        theme.syntax_languages.insert(
+            "html".to_string(),
+            HashMap::from([(TokenType::Type, Color::Red)]),
+        );
+
+        assert_eq!(TokenType::Tag.color_for(&theme, "html"), Color::Red);
+        assert_eq!(TokenType::Tag.color_for(&theme, "xml"), theme.syntax_type);
+        assert_eq!(
+            TokenType::Macro.color_for(&theme, "rust"),
+            theme.syntax_function
+        );
+        assert_eq!(
+            TokenType::Heading.color_for(&theme, "markdown"),
+            theme.syntax_heading.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_theme_file_sets_documented_sections() {
+        let theme = Theme::from_toml(
+            r##"
+            name = "My Custom Theme"
+            description = "A beautiful custom theme"
+            author = "Your Name"
+            variant = "dark"
+            separator = "#000000"
+
+            [background]
+            left = { r = 30, g = 34, b = 54 }
+
+            [terminal]
+            cursor = "red"
+
+            [status_bar]
+            hash = "208"
+
+            [syntax]
+            keyword = { r = 1, g = 2, b = 3 }
+            tag = "#8be9fd"
+
+            [syntax.md]
+            heading = "#bd93f9"
+            "##,
+        )
+        .unwrap();
+        let base = Theme::load(DEFAULT_BASE_THEME).unwrap();
+
+        assert_eq!(theme.separator, Color::Rgb(0, 0, 0));
+        assert_eq!(theme.background_left, Color::Rgb(30, 34, 54));
+        assert_eq!(theme.background_right, base.background_right);
+        assert_eq!(theme.terminal_cursor_bg, Color::Red);
+        assert_eq!(theme.status_hash, Color::Indexed(208));
+        assert_eq!(theme.syntax_keyword, Color::Rgb(1, 2, 3));
+        assert_eq!(theme.syntax_tag, Some(Color::Rgb(139, 233, 253)));
+        assert_eq!(
+            TokenType::Heading.color_for(&theme, "markdown"),
+            Color::Rgb(189, 147, 249)
+        );
+        assert_eq!(
+            TokenType::Heading.color_for(&theme, "rust"),
+            base.syntax_heading.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_theme_file_extends_a_builtin_theme() {
+        let theme =
+            Theme::from_toml("extends = \"nord\"\n[editor]\nline_number = \"blue\"\n").unwrap();
+        let nord = Theme::load("nord").unwrap();
+
+        assert_eq!(theme.editor_line_number, Color::Blue);
+        assert_eq!(theme.background_right, nord.background_right);
+        assert_eq!(theme.syntax_keyword, nord.syntax_keyword);
+        assert_eq!(theme.syntax_macro, nord.syntax_macro);
+
+        let error = Theme::from_toml("extends = \"nope\"").unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown theme: nope"));
+    }
+
+    #[test]
+    fn test_theme_file_errors_name_the_entry() {
+        for (content, message) in [
+            (
+                "[syntax]\nsparkle = \"red\"",
+                "Unknown syntax token: sparkle",
+            ),
+            (
+                "[syntax.klingon]\nkeyword = \"red\"",
+                "Unknown language: [syntax.klingon]",
+            ),
+            (
+                "[syntax]\nkeyword = \"reddish\"",
+                "Invalid color for syntax.keyword",
+            ),
+            (
+                "[editor]\ncursor = \"red\"",
+                "Unknown theme color: editor.cursor",
+            ),
+            (
+                "[editor]\nseparator = { r = 256, g = 0, b = 0 }",
+                "Invalid color for editor.separator",
+            ),
+            (
+                "[editor]\nseparator = { r = 1, g = 0 }",
+                "Invalid color for editor.separator",
+            ),
+            ("colour = \"red\"", "Unknown theme entry: colour"),
+        ] {
+            let error = Theme::from_toml(content).unwrap_err();
+            assert!(
+                error.to_string().contains(message),
+                "{:?} failed with {:?}",
+                content,
+                error.to_string()
+            );
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Ayu Dark inspired color scheme
 pub fn ayu_dark() -> Theme {
@@ -13,6 +14,10 @@ pub fn ayu_dark() -> Theme {
         editor_cursor_char_bg: Color::Rgb(255, 180, 84),
         editor_cursor_char_fg: Color::Rgb(15, 20, 25),
         editor_cursor_line_bg: Color::Rgb(22, 29, 37),
+        editor_gutter_added: Color::Rgb(186, 230, 126),
+        editor_gutter_modified: Color::Rgb(255, 180, 84),
+        editor_gutter_deleted: Color::Rgb(242, 97, 103),
+        editor_typed_line_bg: Color::Rgb(186, 230, 126),
 
         file_tree_added: Color::Rgb(186, 230, 126),
         file_tree_deleted: Color::Rgb(242, 97, 103),
@@ -51,5 +56,17 @@ pub fn ayu_dark() -> Theme {
         syntax_parameter: Color::Rgb(255, 214, 111),
         syntax_property: Color::Rgb(115, 184, 205),
         syntax_label: Color::Rgb(255, 140, 99),
+
+        syntax_tag: Some(Color::Rgb(57, 186, 230)),
+        syntax_attribute: Some(Color::Rgb(255, 180, 84)),
+        syntax_builtin: Some(Color::Rgb(242, 151, 24)),
+        syntax_macro: Some(Color::Rgb(149, 230, 203)),
+        syntax_heading: Some(Color::Rgb(240, 113, 120)),
+        syntax_link: Some(Color::Rgb(57, 186, 230)),
+        syntax_escape: Some(Color::Rgb(149, 230, 203)),
+        syntax_diff_added: Some(Color::Rgb(186, 230, 126)),
+        syntax_diff_removed: Some(Color::Rgb(242, 97, 103)),
+
+        syntax_languages: HashMap::new(),
     }
 }
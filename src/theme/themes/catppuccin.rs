@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Catppuccin Mocha inspired color scheme
 pub fn catppuccin() -> Theme {
@@ -13,6 +14,10 @@ pub fn catppuccin() -> Theme {
         editor_cursor_char_bg: Color::Rgb(245, 194, 231),
         editor_cursor_char_fg: Color::Rgb(30, 30, 46),
         editor_cursor_line_bg: Color::Rgb(49, 50, 68),
+        editor_gutter_added: Color::Rgb(166, 227, 161),
+        editor_gutter_modified: Color::Rgb(250, 179, 135),
+        editor_gutter_deleted: Color::Rgb(243, 139, 168),
+        editor_typed_line_bg: Color::Rgb(166, 227, 161),
 
         file_tree_added: Color::Rgb(166, 227, 161),
         file_tree_deleted: Color::Rgb(243, 139, 168),
@@ -51,5 +56,17 @@ pub fn catppuccin() -> Theme {
         syntax_parameter: Color::Rgb(245, 194, 231),
         syntax_property: Color::Rgb(166, 227, 161),
         syntax_label: Color::Rgb(203, 166, 247),
+
+        syntax_tag: Some(Color::Rgb(137, 180, 250)),
+        syntax_attribute: Some(Color::Rgb(249, 226, 175)),
+        syntax_builtin: Some(Color::Rgb(243, 139, 168)),
+        syntax_macro: Some(Color::Rgb(235, 160, 172)),
+        syntax_heading: Some(Color::Rgb(243, 139, 168)),
+        syntax_link: Some(Color::Rgb(180, 190, 254)),
+        syntax_escape: Some(Color::Rgb(245, 194, 231)),
+        syntax_diff_added: Some(Color::Rgb(166, 227, 161)),
+        syntax_diff_removed: Some(Color::Rgb(243, 139, 168)),
+
+        syntax_languages: HashMap::new(),
     }
 }
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Dracula inspired color scheme
 pub fn dracula() -> Theme {
@@ -13,6 +14,10 @@ pub fn dracula() -> Theme {
         editor_cursor_char_bg: Color::Rgb(255, 121, 198),
         editor_cursor_char_fg: Color::Rgb(40, 42, 54),
         editor_cursor_line_bg: Color::Rgb(68, 71, 90),
+        editor_gutter_added: Color::Rgb(80, 250, 123),
+        editor_gutter_modified: Color::Rgb(255, 184, 108),
+        editor_gutter_deleted: Color::Rgb(255, 85, 85),
+        editor_typed_line_bg: Color::Rgb(80, 250, 123),
 
         file_tree_added: Color::Rgb(80, 250, 123),
         file_tree_deleted: Color::Rgb(255, 85, 85),
@@ -51,5 +56,17 @@ pub fn dracula() -> Theme {
         syntax_parameter: Color::Rgb(255, 184, 108),
         syntax_property: Color::Rgb(80, 250, 123),
         syntax_label: Color::Rgb(255, 121, 198),
+
+        syntax_tag: Some(Color::Rgb(255, 121, 198)),
+        syntax_attribute: Some(Color::Rgb(80, 250, 123)),
+        syntax_builtin: Some(Color::Rgb(139, 233, 253)),
+        syntax_macro: Some(Color::Rgb(255, 184, 108)),
+        syntax_heading: Some(Color::Rgb(189, 147, 249)),
+        syntax_link: Some(Color::Rgb(139, 233, 253)),
+        syntax_escape: Some(Color::Rgb(255, 121, 198)),
+        syntax_diff_added: Some(Color::Rgb(80, 250, 123)),
+        syntax_diff_removed: Some(Color::Rgb(255, 85, 85)),
+
+        syntax_languages: HashMap::new(),
     }
 }
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Everforest Dark inspired color scheme
 pub fn everforest() -> Theme {
@@ -13,6 +14,10 @@ pub fn everforest() -> Theme {
         editor_cursor_char_bg: Color::Rgb(131, 192, 146),
         editor_cursor_char_fg: Color::Rgb(45, 52, 46),
         editor_cursor_line_bg: Color::Rgb(57, 64, 58),
+        editor_gutter_added: Color::Rgb(131, 192, 146),
+        editor_gutter_modified: Color::Rgb(219, 188, 127),
+        editor_gutter_deleted: Color::Rgb(230, 126, 128),
+        editor_typed_line_bg: Color::Rgb(131, 192, 146),
 
         file_tree_added: Color::Rgb(131, 192, 146),
         file_tree_deleted: Color::Rgb(230, 126, 128),
@@ -51,5 +56,17 @@ pub fn everforest() -> Theme {
         syntax_parameter: Color::Rgb(219, 188, 127),
         syntax_property: Color::Rgb(125, 192, 192),
         syntax_label: Color::Rgb(230, 126, 128),
+
+        syntax_tag: Some(Color::Rgb(230, 152, 117)),
+        syntax_attribute: Some(Color::Rgb(219, 188, 127)),
+        syntax_builtin: Some(Color::Rgb(127, 187, 179)),
+        syntax_macro: Some(Color::Rgb(214, 153, 182)),
+        syntax_heading: Some(Color::Rgb(230, 126, 128)),
+        syntax_link: Some(Color::Rgb(127, 187, 179)),
+        syntax_escape: Some(Color::Rgb(230, 152, 117)),
+        syntax_diff_added: Some(Color::Rgb(131, 192, 146)),
+        syntax_diff_removed: Some(Color::Rgb(230, 126, 128)),
+
+        syntax_languages: HashMap::new(),
     }
 }
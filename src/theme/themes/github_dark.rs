@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// GitHub Dark inspired color scheme
 pub fn github_dark() -> Theme {
@@ -13,6 +14,10 @@ pub fn github_dark() -> Theme {
         editor_cursor_char_bg: Color::Rgb(88, 166, 255),
         editor_cursor_char_fg: Color::Rgb(22, 27, 34),
         editor_cursor_line_bg: Color::Rgb(33, 38, 45),
+        editor_gutter_added: Color::Rgb(63, 185, 80),
+        editor_gutter_modified: Color::Rgb(219, 109, 40),
+        editor_gutter_deleted: Color::Rgb(248, 81, 73),
+        editor_typed_line_bg: Color::Rgb(63, 185, 80),
 
         file_tree_added: Color::Rgb(63, 185, 80),
         file_tree_deleted: Color::Rgb(248, 81, 73),
@@ -51,5 +56,17 @@ pub fn github_dark() -> Theme {
         syntax_parameter: Color::Rgb(255, 186, 77),
         syntax_property: Color::Rgb(121, 192, 255),
         syntax_label: Color::Rgb(210, 153, 255),
+
+        syntax_tag: Some(Color::Rgb(126, 231, 135)),
+        syntax_attribute: Some(Color::Rgb(121, 192, 255)),
+        syntax_builtin: Some(Color::Rgb(255, 166, 87)),
+        syntax_macro: Some(Color::Rgb(121, 192, 255)),
+        syntax_heading: Some(Color::Rgb(121, 192, 255)),
+        syntax_link: Some(Color::Rgb(165, 214, 255)),
+        syntax_escape: Some(Color::Rgb(126, 231, 135)),
+        syntax_diff_added: Some(Color::Rgb(63, 185, 80)),
+        syntax_diff_removed: Some(Color::Rgb(248, 81, 73)),
+
+        syntax_languages: HashMap::new(),
     }
 }
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Gruvbox Dark inspired color scheme
 pub fn gruvbox() -> Theme {
@@ -13,6 +14,10 @@ pub fn gruvbox() -> Theme {
         editor_cursor_char_bg: Color::Rgb(254, 128, 25),
         editor_cursor_char_fg: Color::Rgb(40, 40, 40),
         editor_cursor_line_bg: Color::Rgb(60, 56, 54),
+        editor_gutter_added: Color::Rgb(184, 187, 38),
+        editor_gutter_modified: Color::Rgb(254, 128, 25),
+        editor_gutter_deleted: Color::Rgb(251, 73, 52),
+        editor_typed_line_bg: Color::Rgb(184, 187, 38),
 
         file_tree_added: Color::Rgb(184, 187, 38),
         file_tree_deleted: Color::Rgb(251, 73, 52),
@@ -51,5 +56,17 @@ pub fn gruvbox() -> Theme {
         syntax_parameter: Color::Rgb(254, 128, 25),
         syntax_property: Color::Rgb(184, 187, 38),
         syntax_label: Color::Rgb(251, 73, 52),
+
+        syntax_tag: Some(Color::Rgb(142, 192, 124)),
+        syntax_attribute: Some(Color::Rgb(250, 189, 47)),
+        syntax_builtin: Some(Color::Rgb(254, 128, 25)),
+        syntax_macro: Some(Color::Rgb(142, 192, 124)),
+        syntax_heading: Some(Color::Rgb(131, 165, 152)),
+        syntax_link: Some(Color::Rgb(142, 192, 124)),
+        syntax_escape: Some(Color::Rgb(254, 128, 25)),
+        syntax_diff_added: Some(Color::Rgb(184, 187, 38)),
+        syntax_diff_removed: Some(Color::Rgb(251, 73, 52)),
+
+        syntax_languages: HashMap::new(),
     }
 }
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Material Theme inspired color scheme
 pub fn material() -> Theme {
@@ -13,6 +14,10 @@ pub fn material() -> Theme {
         editor_cursor_char_bg: Color::Rgb(255, 203, 107),
         editor_cursor_char_fg: Color::Rgb(38, 50, 56),
         editor_cursor_line_bg: Color::Rgb(55, 71, 79),
+        editor_gutter_added: Color::Rgb(195, 232, 141),
+        editor_gutter_modified: Color::Rgb(255, 203, 107),
+        editor_gutter_deleted: Color::Rgb(255, 83, 112),
+        editor_typed_line_bg: Color::Rgb(195, 232, 141),
 
         file_tree_added: Color::Rgb(195, 232, 141),
         file_tree_deleted: Color::Rgb(255, 83, 112),
@@ -51,5 +56,17 @@ pub fn material() -> Theme {
         syntax_parameter: Color::Rgb(255, 203, 107),
         syntax_property: Color::Rgb(128, 203, 196),
         syntax_label: Color::Rgb(199, 146, 234),
+
+        syntax_tag: Some(Color::Rgb(240, 113, 120)),
+        syntax_attribute: Some(Color::Rgb(199, 146, 234)),
+        syntax_builtin: Some(Color::Rgb(255, 83, 112)),
+        syntax_macro: Some(Color::Rgb(137, 221, 255)),
+        syntax_heading: Some(Color::Rgb(130, 170, 255)),
+        syntax_link: Some(Color::Rgb(128, 203, 196)),
+        syntax_escape: Some(Color::Rgb(247, 140, 108)),
+        syntax_diff_added: Some(Color::Rgb(195, 232, 141)),
+        syntax_diff_removed: Some(Color::Rgb(255, 83, 112)),
+
+        syntax_languages: HashMap::new(),
     }
 }
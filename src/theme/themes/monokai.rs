@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Monokai inspired color scheme
 pub fn monokai() -> Theme {
@@ -13,6 +14,10 @@ pub fn monokai() -> Theme {
         editor_cursor_char_bg: Color::Rgb(253, 151, 31),
         editor_cursor_char_fg: Color::Rgb(39, 40, 34),
         editor_cursor_line_bg: Color::Rgb(51, 51, 45),
+        editor_gutter_added: Color::Rgb(166, 226, 46),
+        editor_gutter_modified: Color::Rgb(253, 151, 31),
+        editor_gutter_deleted: Color::Rgb(249, 38, 114),
+        editor_typed_line_bg: Color::Rgb(166, 226, 46),
 
         file_tree_added: Color::Rgb(166, 226, 46),
         file_tree_deleted: Color::Rgb(249, 38, 114),
@@ -51,5 +56,17 @@ pub fn monokai() -> Theme {
         syntax_parameter: Color::Rgb(253, 151, 31),
         syntax_property: Color::Rgb(166, 226, 46),
         syntax_label: Color::Rgb(249, 38, 114),
+
+        syntax_tag: Some(Color::Rgb(249, 38, 114)),
+        syntax_attribute: Some(Color::Rgb(166, 226, 46)),
+        syntax_builtin: Some(Color::Rgb(102, 217, 239)),
+        syntax_macro: Some(Color::Rgb(253, 151, 31)),
+        syntax_heading: Some(Color::Rgb(166, 226, 46)),
+        syntax_link: Some(Color::Rgb(102, 217, 239)),
+        syntax_escape: Some(Color::Rgb(174, 129, 255)),
+        syntax_diff_added: Some(Color::Rgb(166, 226, 46)),
+        syntax_diff_removed: Some(Color::Rgb(249, 38, 114)),
+
+        syntax_languages: HashMap::new(),
     }
 }
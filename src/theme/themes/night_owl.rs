@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Night Owl inspired color scheme
 pub fn night_owl() -> Theme {
@@ -13,6 +14,10 @@ pub fn night_owl() -> Theme {
         editor_cursor_char_bg: Color::Rgb(122, 162, 247),
         editor_cursor_char_fg: Color::Rgb(1, 22, 39),
         editor_cursor_line_bg: Color::Rgb(1, 41, 72),
+        editor_gutter_added: Color::Rgb(173, 219, 103),
+        editor_gutter_modified: Color::Rgb(255, 213, 128),
+        editor_gutter_deleted: Color::Rgb(239, 83, 80),
+        editor_typed_line_bg: Color::Rgb(173, 219, 103),
 
         file_tree_added: Color::Rgb(173, 219, 103),
         file_tree_deleted: Color::Rgb(239, 83, 80),
@@ -51,5 +56,17 @@ pub fn night_owl() -> Theme {
         syntax_parameter: Color::Rgb(255, 203, 107),
         syntax_property: Color::Rgb(122, 162, 247),
         syntax_label: Color::Rgb(255, 88, 116),
+
+        syntax_tag: Some(Color::Rgb(127, 219, 202)),
+        syntax_attribute: Some(Color::Rgb(173, 219, 103)),
+        syntax_builtin: Some(Color::Rgb(247, 140, 108)),
+        syntax_macro: Some(Color::Rgb(128, 203, 196)),
+        syntax_heading: Some(Color::Rgb(130, 170, 255)),
+        syntax_link: Some(Color::Rgb(127, 219, 202)),
+        syntax_escape: Some(Color::Rgb(247, 140, 108)),
+        syntax_diff_added: Some(Color::Rgb(173, 219, 103)),
+        syntax_diff_removed: Some(Color::Rgb(239, 83, 80)),
+
+        syntax_languages: HashMap::new(),
     }
 }
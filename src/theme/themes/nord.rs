@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Nord inspired color scheme
 pub fn nord() -> Theme {
@@ -13,6 +14,10 @@ pub fn nord() -> Theme {
         editor_cursor_char_bg: Color::Rgb(136, 192, 208),
         editor_cursor_char_fg: Color::Rgb(46, 52, 64),
         editor_cursor_line_bg: Color::Rgb(59, 66, 82),
+        editor_gutter_added: Color::Rgb(163, 190, 140),
+        editor_gutter_modified: Color::Rgb(235, 203, 139),
+        editor_gutter_deleted: Color::Rgb(191, 97, 106),
+        editor_typed_line_bg: Color::Rgb(163, 190, 140),
 
         file_tree_added: Color::Rgb(163, 190, 140),
         file_tree_deleted: Color::Rgb(191, 97, 106),
@@ -51,5 +56,17 @@ pub fn nord() -> Theme {
         syntax_parameter: Color::Rgb(235, 203, 139),
         syntax_property: Color::Rgb(163, 190, 140),
         syntax_label: Color::Rgb(180, 142, 173),
+
+        syntax_tag: Some(Color::Rgb(129, 161, 193)),
+        syntax_attribute: Some(Color::Rgb(143, 188, 187)),
+        syntax_builtin: Some(Color::Rgb(129, 161, 193)),
+        syntax_macro: Some(Color::Rgb(208, 135, 112)),
+        syntax_heading: Some(Color::Rgb(136, 192, 208)),
+        syntax_link: Some(Color::Rgb(143, 188, 187)),
+        syntax_escape: Some(Color::Rgb(235, 203, 139)),
+        syntax_diff_added: Some(Color::Rgb(163, 190, 140)),
+        syntax_diff_removed: Some(Color::Rgb(191, 97, 106)),
+
+        syntax_languages: HashMap::new(),
     }
 }
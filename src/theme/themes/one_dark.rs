@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// One Dark inspired color scheme
 pub fn one_dark() -> Theme {
@@ -13,6 +14,10 @@ pub fn one_dark() -> Theme {
         editor_cursor_char_bg: Color::Rgb(97, 175, 239),
         editor_cursor_char_fg: Color::Rgb(40, 44, 52),
         editor_cursor_line_bg: Color::Rgb(47, 52, 61),
+        editor_gutter_added: Color::Rgb(152, 195, 121),
+        editor_gutter_modified: Color::Rgb(209, 154, 102),
+        editor_gutter_deleted: Color::Rgb(224, 108, 117),
+        editor_typed_line_bg: Color::Rgb(152, 195, 121),
 
         file_tree_added: Color::Rgb(152, 195, 121),
         file_tree_deleted: Color::Rgb(224, 108, 117),
@@ -51,5 +56,17 @@ pub fn one_dark() -> Theme {
         syntax_parameter: Color::Rgb(229, 192, 123),
         syntax_property: Color::Rgb(152, 195, 121),
         syntax_label: Color::Rgb(198, 120, 221),
+
+        syntax_tag: Some(Color::Rgb(224, 108, 117)),
+        syntax_attribute: Some(Color::Rgb(209, 154, 102)),
+        syntax_builtin: Some(Color::Rgb(86, 182, 194)),
+        syntax_macro: Some(Color::Rgb(86, 182, 194)),
+        syntax_heading: Some(Color::Rgb(224, 108, 117)),
+        syntax_link: Some(Color::Rgb(86, 182, 194)),
+        syntax_escape: Some(Color::Rgb(86, 182, 194)),
+        syntax_diff_added: Some(Color::Rgb(152, 195, 121)),
+        syntax_diff_removed: Some(Color::Rgb(224, 108, 117)),
+
+        syntax_languages: HashMap::new(),
     }
 }
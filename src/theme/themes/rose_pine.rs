@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Rose Pine inspired color scheme
 pub fn rose_pine() -> Theme {
@@ -13,6 +14,10 @@ pub fn rose_pine() -> Theme {
         editor_cursor_char_bg: Color::Rgb(235, 188, 186),
         editor_cursor_char_fg: Color::Rgb(35, 33, 54),
         editor_cursor_line_bg: Color::Rgb(42, 39, 63),
+        editor_gutter_added: Color::Rgb(156, 207, 216),
+        editor_gutter_modified: Color::Rgb(246, 193, 119),
+        editor_gutter_deleted: Color::Rgb(235, 111, 146),
+        editor_typed_line_bg: Color::Rgb(156, 207, 216),
 
         file_tree_added: Color::Rgb(156, 207, 216),
         file_tree_deleted: Color::Rgb(235, 111, 146),
@@ -51,5 +56,17 @@ pub fn rose_pine() -> Theme {
         syntax_parameter: Color::Rgb(246, 193, 119),
         syntax_property: Color::Rgb(156, 207, 216),
         syntax_label: Color::Rgb(196, 167, 231),
+
+        syntax_tag: Some(Color::Rgb(156, 207, 216)),
+        syntax_attribute: Some(Color::Rgb(196, 167, 231)),
+        syntax_builtin: Some(Color::Rgb(235, 111, 146)),
+        syntax_macro: Some(Color::Rgb(196, 167, 231)),
+        syntax_heading: Some(Color::Rgb(196, 167, 231)),
+        syntax_link: Some(Color::Rgb(235, 188, 186)),
+        syntax_escape: Some(Color::Rgb(235, 111, 146)),
+        syntax_diff_added: Some(Color::Rgb(156, 207, 216)),
+        syntax_diff_removed: Some(Color::Rgb(235, 111, 146)),
+
+        syntax_languages: HashMap::new(),
     }
 }
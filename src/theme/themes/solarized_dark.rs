@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Solarized Dark color scheme
 pub fn solarized_dark() -> Theme {
@@ -13,6 +14,10 @@ pub fn solarized_dark() -> Theme {
         editor_cursor_char_bg: Color::Rgb(38, 139, 210),
         editor_cursor_char_fg: Color::Rgb(0, 43, 54),
         editor_cursor_line_bg: Color::Rgb(7, 54, 66),
+        editor_gutter_added: Color::Rgb(133, 153, 0),
+        editor_gutter_modified: Color::Rgb(181, 137, 0),
+        editor_gutter_deleted: Color::Rgb(220, 50, 47),
+        editor_typed_line_bg: Color::Rgb(133, 153, 0),
 
         file_tree_added: Color::Rgb(133, 153, 0),
         file_tree_deleted: Color::Rgb(220, 50, 47),
@@ -51,5 +56,17 @@ pub fn solarized_dark() -> Theme {
         syntax_parameter: Color::Rgb(181, 137, 0),
         syntax_property: Color::Rgb(42, 161, 152),
         syntax_label: Color::Rgb(211, 54, 130),
+
+        syntax_tag: Some(Color::Rgb(38, 139, 210)),
+        syntax_attribute: Some(Color::Rgb(133, 153, 0)),
+        syntax_builtin: Some(Color::Rgb(220, 50, 47)),
+        syntax_macro: Some(Color::Rgb(211, 54, 130)),
+        syntax_heading: Some(Color::Rgb(38, 139, 210)),
+        syntax_link: Some(Color::Rgb(108, 113, 196)),
+        syntax_escape: Some(Color::Rgb(220, 50, 47)),
+        syntax_diff_added: Some(Color::Rgb(133, 153, 0)),
+        syntax_diff_removed: Some(Color::Rgb(220, 50, 47)),
+
+        syntax_languages: HashMap::new(),
     }
 }
@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Telemetry - "Encrypt the signal; the void is listening."
 /// Color palette by bjarneo (https://github.com/bjarneo) from Aether (https://aethr.no)
@@ -14,6 +15,10 @@ pub fn telemetry() -> Theme {
         editor_cursor_char_bg: Color::Rgb(244, 174, 89),
         editor_cursor_char_fg: Color::Rgb(15, 27, 29),
         editor_cursor_line_bg: Color::Rgb(29, 46, 49),
+        editor_gutter_added: Color::Rgb(141, 172, 139),
+        editor_gutter_modified: Color::Rgb(244, 174, 89),
+        editor_gutter_deleted: Color::Rgb(194, 113, 102),
+        editor_typed_line_bg: Color::Rgb(141, 172, 139),
 
         file_tree_added: Color::Rgb(141, 172, 139),
         file_tree_deleted: Color::Rgb(194, 113, 102),
@@ -52,5 +57,17 @@ pub fn telemetry() -> Theme {
         syntax_parameter: Color::Rgb(122, 154, 153),
         syntax_property: Color::Rgb(154, 191, 190),
         syntax_label: Color::Rgb(194, 113, 102),
+
+        syntax_tag: Some(Color::Rgb(147, 191, 194)),
+        syntax_attribute: Some(Color::Rgb(212, 154, 79)),
+        syntax_builtin: Some(Color::Rgb(196, 132, 122)),
+        syntax_macro: Some(Color::Rgb(143, 181, 179)),
+        syntax_heading: Some(Color::Rgb(244, 174, 89)),
+        syntax_link: Some(Color::Rgb(147, 191, 194)),
+        syntax_escape: Some(Color::Rgb(194, 113, 102)),
+        syntax_diff_added: Some(Color::Rgb(141, 172, 139)),
+        syntax_diff_removed: Some(Color::Rgb(194, 113, 102)),
+
+        syntax_languages: HashMap::new(),
     }
 }
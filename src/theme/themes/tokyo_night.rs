@@ -1,5 +1,6 @@
 use super::super::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 
 /// Tokyo Night inspired color scheme
 pub fn tokyo_night() -> Theme {
@@ -13,6 +14,10 @@ pub fn tokyo_night() -> Theme {
         editor_cursor_char_bg: Color::Rgb(122, 162, 247),
         editor_cursor_char_fg: Color::Rgb(26, 27, 38),
         editor_cursor_line_bg: Color::Rgb(42, 47, 68),
+        editor_gutter_added: Color::Rgb(158, 206, 106),
+        editor_gutter_modified: Color::Rgb(255, 158, 100),
+        editor_gutter_deleted: Color::Rgb(247, 118, 142),
+        editor_typed_line_bg: Color::Rgb(158, 206, 106),
 
         file_tree_added: Color::Rgb(158, 206, 106),
         file_tree_deleted: Color::Rgb(247, 118, 142),
@@ -51,5 +56,17 @@ pub fn tokyo_night() -> Theme {
         syntax_parameter: Color::Rgb(255, 213, 128),
         syntax_property: Color::Rgb(158, 206, 106),
         syntax_label: Color::Rgb(187, 154, 247),
+
+        syntax_tag: Some(Color::Rgb(247, 118, 142)),
+        syntax_attribute: Some(Color::Rgb(224, 175, 104)),
+        syntax_builtin: Some(Color::Rgb(42, 195, 222)),
+        syntax_macro: Some(Color::Rgb(125, 207, 255)),
+        syntax_heading: Some(Color::Rgb(122, 162, 247)),
+        syntax_link: Some(Color::Rgb(115, 218, 202)),
+        syntax_escape: Some(Color::Rgb(187, 154, 247)),
+        syntax_diff_added: Some(Color::Rgb(158, 206, 106)),
+        syntax_diff_removed: Some(Color::Rgb(247, 118, 142)),
+
+        syntax_languages: HashMap::new(),
     }
 }
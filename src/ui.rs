@@ -1,45 +1,72 @@
-use std::io;
+use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Padding, Paragraph},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
     Frame, Terminal,
 };
 use unicode_width::UnicodeWidthStr;
 
-use crate::animation::{AnimationEngine, SpeedRule};
+use crate::animation::{AnimationEngine, SpeedRule, Timing};
 use crate::git::{CommitMetadata, DiffMode, GitRepository};
-use crate::panes::{EditorPane, FileTreePane, StatusBarPane, TerminalPane};
+use crate::icons::FileIcons;
+use crate::narrative::{Narrative, LOG_LINES};
+use crate::panes::{
+    CommitGraphPane, EditorPane, FileTreeMode, FileTreePane, StatusBarPane, TerminalPane,
+};
+use crate::session::{Session, SessionLimits};
+use crate::syntax::LanguageRule;
 use crate::theme::Theme;
-use crate::PlaybackOrder;
+use crate::{PlaybackOrder, ViewMode};
+
+// Editor lines scrolled per mouse wheel step
+const SCROLL_LINES: isize = 3;
 
 #[derive(Debug, Clone, PartialEq)]
 enum UIState {
     Playing,
-    WaitingForNext { resume_at: Instant },
+    WaitingForNext {
+        resume_at: Instant,
+    },
+    /// The session budget ran out and its totals are shown
+    Summary,
     Finished,
 }
 
+/// Screen areas of the panes that react to the mouse, as of the last frame
+#[derive(Debug, Clone, Copy, Default)]
+struct PaneAreas {
+    file_tree: Rect,
+    editor: Rect,
+    terminal: Rect,
+    status_bar: Rect,
+}
+
 /// Main UI controller for the gitlogue terminal interface.
 pub struct UI<'a> {
     state: UIState,
-    speed_ms: u64,
     file_tree: FileTreePane,
     editor: EditorPane,
     terminal: TerminalPane,
     status_bar: StatusBarPane,
+    commit_graph: CommitGraphPane,
+    show_commit_graph: bool,
+    areas: PaneAreas,
     engine: AnimationEngine,
     repo: Option<&'a GitRepository>,
     should_exit: Arc<AtomicBool>,
@@ -49,6 +76,7 @@ pub struct UI<'a> {
     commit_spec: Option<String>,
     is_range_mode: bool,
     diff_mode: Option<DiffMode>,
+    session: Session,
 }
 
 impl<'a> UI<'a> {
@@ -72,11 +100,13 @@ impl<'a> UI<'a> {
 
         Self {
             state: UIState::Playing,
-            speed_ms,
             file_tree: FileTreePane::new(),
             editor: EditorPane,
             terminal: TerminalPane,
-            status_bar: StatusBarPane,
+            status_bar: StatusBarPane::new(),
+            commit_graph: CommitGraphPane::new(),
+            show_commit_graph: false,
+            areas: PaneAreas::default(),
             engine,
             repo,
             should_exit,
@@ -86,14 +116,80 @@ impl<'a> UI<'a> {
             commit_spec,
             is_range_mode,
             diff_mode: None,
+            session: Session::new(&SessionLimits::default()),
         }
     }
 
+    /// Limits the session to a duration, deadline or number of commits, starting now.
+    pub fn set_session_limits(&mut self, limits: &SessionLimits) {
+        self.session = Session::new(limits);
+    }
+
     /// Sets the diff mode for working tree diff playback.
     pub fn set_diff_mode(&mut self, mode: Option<DiffMode>) {
         self.diff_mode = mode;
     }
 
+    /// Enables opening deleted files and deleting their content in the editor.
+    pub fn set_animate_deletions(&mut self, enabled: bool) {
+        self.engine.set_animate_deletions(enabled);
+    }
+
+    /// Enables a fading background tint on freshly typed lines.
+    pub fn set_highlight_typed_lines(&mut self, enabled: bool) {
+        self.engine.set_highlight_typed_lines(enabled);
+    }
+
+    /// Shows an overview of the current file next to the editor.
+    pub fn set_minimap(&mut self, enabled: bool) {
+        self.engine.set_minimap(enabled);
+    }
+
+    /// Sets whether the file tree lists only changed files or the whole repository.
+    pub fn set_file_tree_mode(&mut self, mode: FileTreeMode) {
+        self.file_tree.set_mode(mode);
+    }
+
+    /// Sets the Nerd Font icons shown in the file tree, or none to hide them.
+    pub fn set_file_icons(&mut self, icons: Option<FileIcons>) {
+        self.file_tree.set_icons(icons);
+    }
+
+    /// Sets the strftime format of commit dates in the status bar.
+    pub fn set_date_format(&mut self, format: String) {
+        self.status_bar.set_date_format(format);
+    }
+
+    /// Shows the commit graph below the file tree; `g` toggles it while playing.
+    pub fn set_commit_graph(&mut self, enabled: bool) {
+        self.show_commit_graph = enabled;
+    }
+
+    /// Sets the number of terminal lines kept for scrolling back.
+    pub fn set_scrollback(&mut self, lines: usize) {
+        self.engine.set_scrollback(lines);
+    }
+
+    /// Sets the pause durations of the animation and between commits.
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.engine.set_timing(timing);
+    }
+
+    /// Sets the terminal commands and output played around each commit.
+    pub fn set_narrative(&mut self, narrative: Narrative) {
+        self.engine.set_narrative(narrative);
+    }
+
+    /// Sets whether changes are typed out or shown as a side-by-side diff.
+    pub fn set_view_mode(&mut self, mode: ViewMode) {
+        self.engine.set_view_mode(mode);
+    }
+
+    /// Sets user rules mapping file globs to languages for syntax highlighting.
+    pub fn set_language_rules(&mut self, rules: Vec<LanguageRule>) {
+        self.engine.set_language_rules(rules);
+    }
+
     fn setup_signal_handler(should_exit: Arc<AtomicBool>) {
         ctrlc::set_handler(move || {
             // Restore terminal state before exiting
@@ -113,10 +209,37 @@ impl<'a> UI<'a> {
 
     /// Loads a commit and starts the animation.
     pub fn load_commit(&mut self, metadata: CommitMetadata) {
-        self.engine.load_commit(&metadata);
+        let history = match self.repo {
+            Some(repo) if self.engine.narrative_uses_log() => repo
+                .recent_commits(&metadata, LOG_LINES)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        self.engine.load_commit(&metadata, &history);
+        self.session.record(&metadata);
+
+        // Fit the commit to the target duration, then fast-forward it if it would
+        // still run past the deadline so that it ends on time
+        self.engine.fit_to_target();
+        if let Some(remaining) = self.session.remaining() {
+            if self.engine.scaled_duration() > remaining {
+                let nominal = self.engine.nominal_duration().as_secs_f64();
+                self.engine
+                    .set_time_scale(remaining.as_secs_f64() / nominal);
+            }
+        }
         self.state = UIState::Playing;
     }
 
+    // Exit, or show the totals until a key is pressed
+    fn end_session(&mut self) {
+        self.state = if self.session.summary {
+            UIState::Summary
+        } else {
+            UIState::Finished
+        };
+    }
+
     /// Runs the main UI event loop.
     pub fn run(&mut self) -> Result<()> {
         enable_raw_mode()?;
@@ -159,8 +282,17 @@ impl<'a> UI<'a> {
             self.engine.set_viewport_height(viewport_height);
             self.engine.set_content_width(content_width);
 
-            // Tick the animation engine
-            let needs_redraw = self.engine.tick();
+            if self.session.is_expired()
+                && matches!(
+                    self.state,
+                    UIState::Playing | UIState::WaitingForNext { .. }
+                )
+            {
+                self.end_session();
+            }
+
+            // Tick the animation engine, which stays still behind the summary
+            let needs_redraw = self.state == UIState::Summary || self.engine.tick();
 
             if needs_redraw {
                 terminal.draw(|f| self.render(f))?;
@@ -168,16 +300,38 @@ impl<'a> UI<'a> {
 
             // Poll for keyboard events at frame rate
             if event::poll(std::time::Duration::from_millis(8))? {
-                if let Event::Key(key) = event::read()? {
-                    match key.code {
+                match event::read()? {
+                    Event::Key(key) => match key.code {
                         KeyCode::Esc | KeyCode::Char('q') => {
                             self.state = UIState::Finished;
                         }
                         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                             self.state = UIState::Finished;
                         }
+                        KeyCode::Char('g') => {
+                            self.show_commit_graph = !self.show_commit_graph;
+                            terminal.draw(|f| self.render(f))?;
+                        }
+                        KeyCode::Char(' ') => {
+                            if self.engine.is_held() {
+                                self.engine.resume();
+                            } else {
+                                self.engine.hold();
+                            }
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => self.scroll_terminal(-1),
+                        KeyCode::Down | KeyCode::Char('j') => self.scroll_terminal(1),
+                        KeyCode::PageUp => self.scroll_terminal(-(self.terminal_height() as isize)),
+                        KeyCode::PageDown => self.scroll_terminal(self.terminal_height() as isize),
                         _ => {}
+                    },
+                    Event::Mouse(mouse) if self.state != UIState::Summary => {
+                        let tree_changed = self.handle_mouse(mouse);
+                        if tree_changed {
+                            terminal.draw(|f| self.render(f))?;
+                        }
                     }
+                    _ => {}
                 }
             }
 
@@ -185,21 +339,25 @@ impl<'a> UI<'a> {
             match self.state {
                 UIState::Playing => {
                     if self.engine.is_finished() {
-                        if self.repo.is_some() {
-                            // Schedule next commit
-                            // Wait time proportional to speed (100x the typing speed)
+                        if self.repo.is_some() && !self.session.can_start_commit() {
+                            self.end_session();
+                        } else if self.repo.is_some() {
+                            // Schedule next commit, no later than the deadline
+                            let resume_at = Instant::now() + self.engine.commit_delay();
                             self.state = UIState::WaitingForNext {
-                                resume_at: Instant::now()
-                                    + Duration::from_millis(self.speed_ms * 100),
+                                resume_at: self
+                                    .session
+                                    .deadline()
+                                    .map_or(resume_at, |deadline| resume_at.min(deadline)),
                             };
                         } else {
                             // Single commit mode without loop - quit
-                            self.state = UIState::Finished;
+                            self.end_session();
                         }
                     }
                 }
                 UIState::WaitingForNext { resume_at } => {
-                    if Instant::now() >= resume_at {
+                    if Instant::now() >= resume_at && !self.engine.is_held() {
                         // Handle diff mode looping
                         if let Some(diff_mode) = self.diff_mode {
                             if let Some(repo) = self.repo {
@@ -210,11 +368,11 @@ impl<'a> UI<'a> {
                                     }
                                     _ => {
                                         // No more changes, finish
-                                        self.state = UIState::Finished;
+                                        self.end_session();
                                     }
                                 }
                             } else {
-                                self.state = UIState::Finished;
+                                self.end_session();
                             }
                         } else if let Some(repo) = self.repo {
                             let result = if self.is_range_mode {
@@ -259,19 +417,20 @@ impl<'a> UI<'a> {
                                                 self.load_commit(metadata);
                                             }
                                             Err(_) => {
-                                                self.state = UIState::Finished;
+                                                self.end_session();
                                             }
                                         }
                                     } else {
-                                        self.state = UIState::Finished;
+                                        self.end_session();
                                     }
                                 }
                             }
                         } else {
-                            self.state = UIState::Finished;
+                            self.end_session();
                         }
                     }
                 }
+                UIState::Summary => {}
                 UIState::Finished => {
                     break;
                 }
@@ -281,6 +440,72 @@ impl<'a> UI<'a> {
         Ok(())
     }
 
+    // Lines of the terminal pane inside its vertical padding
+    fn terminal_height(&self) -> usize {
+        self.areas.terminal.height.saturating_sub(2) as usize
+    }
+
+    fn scroll_terminal(&mut self, delta: isize) {
+        self.engine.scroll_terminal(delta, self.terminal_height());
+    }
+
+    // Returns whether the file tree changed, which needs a redraw even when playback is idle
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+        let position = Position::new(mouse.column, mouse.row);
+        let mut tree_changed = false;
+        match mouse.kind {
+            MouseEventKind::ScrollUp if self.areas.editor.contains(position) => {
+                self.engine.scroll_buffer(-SCROLL_LINES);
+            }
+            MouseEventKind::ScrollDown if self.areas.editor.contains(position) => {
+                self.engine.scroll_buffer(SCROLL_LINES);
+            }
+            MouseEventKind::ScrollUp if self.areas.terminal.contains(position) => {
+                self.scroll_terminal(-SCROLL_LINES);
+            }
+            MouseEventKind::ScrollDown if self.areas.terminal.contains(position) => {
+                self.scroll_terminal(SCROLL_LINES);
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let file = self.file_tree.file_at(
+                    self.areas.file_tree,
+                    mouse.column,
+                    mouse.row,
+                    &self.theme,
+                );
+                if let Some(index) = file {
+                    if self.engine.jump_to_file(index) {
+                        self.state = UIState::Playing;
+                    }
+                } else {
+                    tree_changed = self.file_tree.toggle_dir_at(
+                        self.areas.file_tree,
+                        mouse.column,
+                        mouse.row,
+                        &self.theme,
+                    );
+                }
+
+                let hash = self.status_bar.hash_at(
+                    self.areas.status_bar,
+                    mouse.column,
+                    mouse.row,
+                    &self.engine,
+                    &self.theme,
+                );
+                if let Some(hash) = hash {
+                    // Clipboard support depends on the terminal, so failures are ignored
+                    let _ = copy_to_clipboard(&hash);
+                }
+
+                // Any click continues playback held by scrolling
+                self.engine.resume();
+            }
+            _ => {}
+        }
+        tree_changed
+    }
+
     fn render(&mut self, f: &mut Frame) {
         let size = f.area();
 
@@ -295,17 +520,33 @@ impl<'a> UI<'a> {
             .spacing(0)
             .split(size);
 
-        // Split left column vertically: file tree | separator | commit info
-        let left_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
+        // Split left column vertically: file tree | separator | [graph | separator |] commit info
+        let left_constraints = if self.show_commit_graph {
+            vec![
+                Constraint::Percentage(50), // File tree
+                Constraint::Length(1),      // Horizontal separator
+                Constraint::Percentage(30), // Commit graph
+                Constraint::Length(1),      // Horizontal separator
+                Constraint::Percentage(20), // Commit info
+            ]
+        } else {
+            vec![
                 Constraint::Percentage(80), // File tree
                 Constraint::Length(1),      // Horizontal separator
                 Constraint::Percentage(20), // Commit info
-            ])
+            ]
+        };
+        let left_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(left_constraints)
             .margin(0)
             .spacing(0)
             .split(main_layout[0]);
+        let (left_separators, status_area) = if self.show_commit_graph {
+            (vec![left_layout[1], left_layout[3]], left_layout[4])
+        } else {
+            (vec![left_layout[1]], left_layout[2])
+        };
 
         // Split right column vertically: editor | separator | terminal
         let right_layout = Layout::default()
@@ -327,27 +568,42 @@ impl<'a> UI<'a> {
                 metadata,
                 self.engine.current_file_index,
                 &self.theme,
+                self.repo,
             );
+            if self.show_commit_graph {
+                self.commit_graph
+                    .set_commit_metadata(metadata, &self.theme, self.repo);
+            }
         }
 
+        self.areas = PaneAreas {
+            file_tree: left_layout[0],
+            editor: right_layout[0],
+            terminal: right_layout[2],
+            status_bar: status_area,
+        };
+
         // Render file tree
         self.file_tree.render(f, left_layout[0], &self.theme);
 
-        // Render horizontal separator between file tree and commit info (left column)
-        let left_sep = Paragraph::new(Line::from("─".repeat(left_layout[1].width as usize))).style(
-            Style::default()
-                .fg(separator_color)
-                .bg(self.theme.background_left),
-        );
-        f.render_widget(left_sep, left_layout[1]);
+        // Render commit graph
+        if self.show_commit_graph {
+            self.commit_graph.render(f, left_layout[2], &self.theme);
+        }
+
+        // Render horizontal separators between the panes of the left column
+        for area in left_separators {
+            let left_sep = Paragraph::new(Line::from("─".repeat(area.width as usize))).style(
+                Style::default()
+                    .fg(separator_color)
+                    .bg(self.theme.background_left),
+            );
+            f.render_widget(left_sep, area);
+        }
 
         // Render commit info
-        self.status_bar.render(
-            f,
-            left_layout[2],
-            self.engine.current_metadata(),
-            &self.theme,
-        );
+        self.status_bar
+            .render(f, status_area, &self.engine, &self.theme);
 
         // Render editor
         self.editor
@@ -412,5 +668,101 @@ impl<'a> UI<'a> {
             let dialog = Paragraph::new(dialog_text).block(block);
             f.render_widget(dialog, dialog_area);
         }
+
+        if self.state == UIState::Summary {
+            self.render_summary(f, size);
+        }
+    }
+
+    // Totals of the session in a box over the panes
+    fn render_summary(&self, f: &mut Frame, size: Rect) {
+        let rows = self.session.summary_rows();
+        let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let mut lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<label_width$}  ", label),
+                        Style::default().fg(self.theme.status_no_commit),
+                    ),
+                    Span::styled(value, Style::default().fg(self.theme.status_hash)),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Press q to exit",
+            Style::default().fg(self.theme.status_no_commit),
+        )));
+
+        let width = 40.min(size.width);
+        let height = (lines.len() as u16 + 4).min(size.height);
+        let area = Rect {
+            x: (size.width - width) / 2,
+            y: (size.height - height) / 2,
+            width,
+            height,
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Session complete")
+            .padding(Padding::uniform(1))
+            .style(
+                Style::default()
+                    .fg(self.theme.file_tree_current_file_fg)
+                    .bg(self.theme.editor_cursor_line_bg),
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+// Copy text to the system clipboard with an OSC 52 escape sequence
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_matches_rfc4648_vectors() {
+        // RFC 4648 section 10, covering two, one and no padding characters
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(base64(input.as_bytes()), expected, "input {:?}", input);
+        }
+        // High bits and the last two alphabet characters
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
     }
 }
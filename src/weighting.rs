@@ -0,0 +1,232 @@
+use git2::Oid;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+use crate::cache::IndexedCommit;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Which author field groups commits for author-balanced selection
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AuthorKey {
+    #[default]
+    Name,
+    Email,
+}
+
+/// Strategy used to pick the next commit in random playback
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CommitWeighting {
+    /// Every candidate is equally likely
+    #[default]
+    Uniform,
+    /// Favour commits whose diff is close to `target_lines` changed lines.
+    /// `spread` is the width of the preference on a log scale, and `sample`
+    /// is how many random candidates are measured per pick.
+    Size {
+        target_lines: usize,
+        spread: f64,
+        sample: usize,
+    },
+    /// Exponential decay over commit age, measured from the newest candidate:
+    /// a commit `half_life_days` older than it is half as likely
+    Recency { half_life_days: f64 },
+    /// Every author gets an equal share regardless of how many commits they made
+    AuthorBalance { key: AuthorKey },
+    /// Random order without repeats until the whole pool has been played
+    Shuffle { seed: Option<u64> },
+}
+
+/// Picks commits from a candidate pool according to a `CommitWeighting`.
+pub struct CommitSelector {
+    weighting: CommitWeighting,
+    rng: StdRng,
+    // Remaining candidate indices for shuffle mode, and the pool size they were drawn from
+    deck: Vec<usize>,
+    deck_pool_len: usize,
+    // Recency weights or author groups of the pool, and the pool size they were built from
+    prepared: Option<Prepared>,
+    prepared_pool_len: usize,
+}
+
+// Selection data computed once per candidate pool
+enum Prepared {
+    // `None` when every weight is zero
+    Recency(Option<WeightedIndex<f64>>),
+    Authors(Vec<Vec<usize>>),
+}
+
+impl CommitSelector {
+    pub fn new(weighting: CommitWeighting) -> Self {
+        let rng = match weighting {
+            CommitWeighting::Shuffle { seed: Some(seed) } => StdRng::seed_from_u64(seed),
+            _ => StdRng::from_rng(&mut rand::rng()),
+        };
+
+        Self {
+            weighting,
+            rng,
+            deck: Vec::new(),
+            deck_pool_len: 0,
+            prepared: None,
+            prepared_pool_len: 0,
+        }
+    }
+
+    /// Selects an index into `candidates`.
+    ///
+    /// `diff_size` returns the number of changed lines of a commit for size weighting.
+    pub fn select<F>(&mut self, candidates: &[IndexedCommit], mut diff_size: F) -> Option<usize>
+    where
+        F: FnMut(Oid) -> Option<usize>,
+    {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match self.weighting.clone() {
+            CommitWeighting::Uniform => Some(self.rng.random_range(0..candidates.len())),
+            CommitWeighting::Size {
+                target_lines,
+                spread,
+                sample,
+            } => {
+                let sample: Vec<usize> = (0..sample.max(1))
+                    .map(|_| self.rng.random_range(0..candidates.len()))
+                    .collect();
+                let weights: Vec<f64> = sample
+                    .iter()
+                    .map(|&index| {
+                        diff_size(candidates[index].oid)
+                            .map(|lines| size_weight(lines, target_lines, spread))
+                            .unwrap_or(0.0)
+                    })
+                    .collect();
+                self.pick_weighted(&weights)
+                    .map(|picked| sample[picked])
+                    .or_else(|| sample.first().copied())
+            }
+            CommitWeighting::Recency { .. } | CommitWeighting::AuthorBalance { .. } => {
+                self.prepare(candidates);
+                match &self.prepared {
+                    Some(Prepared::Recency(Some(dist))) => Some(dist.sample(&mut self.rng)),
+                    Some(Prepared::Authors(authors)) => {
+                        let commits = &authors[self.rng.random_range(0..authors.len())];
+                        Some(commits[self.rng.random_range(0..commits.len())])
+                    }
+                    _ => Some(self.rng.random_range(0..candidates.len())),
+                }
+            }
+            CommitWeighting::Shuffle { .. } => {
+                if self.deck.is_empty() || self.deck_pool_len != candidates.len() {
+                    self.deck = (0..candidates.len()).collect();
+                    self.deck.shuffle(&mut self.rng);
+                    self.deck_pool_len = candidates.len();
+                }
+                self.deck.pop()
+            }
+        }
+    }
+
+    /// Forgets a candidate removed from the pool, so that the remaining ones keep
+    /// their place in the shuffle
+    pub fn remove(&mut self, index: usize) {
+        if self.deck_pool_len > 0 {
+            self.deck.retain(|&i| i != index);
+            for i in &mut self.deck {
+                if *i > index {
+                    *i -= 1;
+                }
+            }
+            self.deck_pool_len -= 1;
+        }
+        self.prepared = None;
+    }
+
+    // Build the recency weights or author groups unless they match the pool already
+    fn prepare(&mut self, candidates: &[IndexedCommit]) {
+        if self.prepared.is_some() && self.prepared_pool_len == candidates.len() {
+            return;
+        }
+
+        self.prepared = match self.weighting {
+            CommitWeighting::Recency { half_life_days } => {
+                let now = candidates.iter().map(|commit| commit.time).max();
+                let weights = candidates.iter().map(|commit| {
+                    recency_weight(now.unwrap_or(commit.time) - commit.time, half_life_days)
+                });
+                Some(Prepared::Recency(WeightedIndex::new(weights).ok()))
+            }
+            CommitWeighting::AuthorBalance { key } => {
+                let mut by_author: HashMap<String, Vec<usize>> = HashMap::new();
+                for (index, commit) in candidates.iter().enumerate() {
+                    let author = match key {
+                        AuthorKey::Name => &commit.author_name,
+                        AuthorKey::Email => &commit.author_email,
+                    };
+                    by_author
+                        .entry(author.to_lowercase())
+                        .or_default()
+                        .push(index);
+                }
+                // Sort for a stable author order so seeded runs are reproducible
+                let mut authors: Vec<_> = by_author.into_values().collect();
+                authors.sort_unstable();
+                Some(Prepared::Authors(authors))
+            }
+            _ => None,
+        };
+        self.prepared_pool_len = candidates.len();
+    }
+
+    fn pick_weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        WeightedIndex::new(weights)
+            .ok()
+            .map(|dist| dist.sample(&mut self.rng))
+    }
+}
+
+// Log-normal shaped preference peaking at `target_lines` changed lines
+fn size_weight(lines: usize, target_lines: usize, spread: f64) -> f64 {
+    let distance = ((lines as f64) + 1.0).ln() - ((target_lines as f64) + 1.0).ln();
+    let spread = spread.max(0.01);
+    (-(distance * distance) / (2.0 * spread * spread)).exp()
+}
+
+// Halves the weight for every `half_life_days` of commit age
+fn recency_weight(age_seconds: i64, half_life_days: f64) -> f64 {
+    let age_days = (age_seconds.max(0) as f64) / SECONDS_PER_DAY;
+    0.5_f64.powf(age_days / half_life_days.max(0.01))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_weight_peaks_at_target() {
+        assert_eq!(size_weight(100, 100, 1.0), 1.0);
+        assert!(size_weight(10, 100, 1.0) < size_weight(50, 100, 1.0));
+        assert!(size_weight(1000, 100, 1.0) < size_weight(200, 100, 1.0));
+        // Symmetric on a log scale
+        let below = size_weight(49, 99, 1.0);
+        let above = size_weight(199, 99, 1.0);
+        assert!((below - above).abs() < 0.01);
+        // A wider spread is more tolerant
+        assert!(size_weight(10, 100, 2.0) > size_weight(10, 100, 0.5));
+    }
+
+    #[test]
+    fn test_recency_weight_halves_every_half_life() {
+        let day = SECONDS_PER_DAY as i64;
+        assert_eq!(recency_weight(0, 30.0), 1.0);
+        assert!((recency_weight(30 * day, 30.0) - 0.5).abs() < 1e-9);
+        assert!((recency_weight(60 * day, 30.0) - 0.25).abs() < 1e-9);
+        // Commits newer than the reference count as current
+        assert_eq!(recency_weight(-day, 30.0), 1.0);
+    }
+}
@@ -1,3 +1,3 @@
 pub mod selectable_paragraph;
 
-pub use selectable_paragraph::SelectableParagraph;
+pub use selectable_paragraph::{blend, SelectableParagraph};
@@ -7,6 +7,24 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
+/// Blends `foreground` over `background`: result = fg * opacity + bg * (1 - opacity).
+/// Non-RGB colors cannot be blended and are returned as-is.
+pub fn blend(foreground: Color, opacity: f32, background: Color) -> Color {
+    match (foreground, background) {
+        (Color::Rgb(fr, fg, fb), Color::Rgb(br, bg, bb)) => {
+            let r = (fr as f32 * opacity + br as f32 * (1.0 - opacity)) as u8;
+            let g = (fg as f32 * opacity + bg as f32 * (1.0 - opacity)) as u8;
+            let b = (fb as f32 * opacity + bb as f32 * (1.0 - opacity)) as u8;
+            Color::Rgb(r, g, b)
+        }
+        _ => foreground,
+    }
+}
+
+/// A wrapped part of a line: the original line index, the part, whether it is the first
+/// part and whether the line wraps at all
+type WrappedLine<'a> = (usize, Line<'a>, bool, bool);
+
 /// A paragraph widget that wraps at character boundaries and supports line selection
 pub struct SelectableParagraph<'a> {
     lines: Vec<Line<'a>>,
@@ -64,19 +82,6 @@ impl<'a> SelectableParagraph<'a> {
         self
     }
 
-    fn apply_opacity(&self, foreground: Color, opacity: f32, background: Color) -> Color {
-        match (foreground, background) {
-            (Color::Rgb(fr, fg, fb), Color::Rgb(br, bg, bb)) => {
-                // Blend foreground and background: result = fg * opacity + bg * (1 - opacity)
-                let r = (fr as f32 * opacity + br as f32 * (1.0 - opacity)) as u8;
-                let g = (fg as f32 * opacity + bg as f32 * (1.0 - opacity)) as u8;
-                let b = (fb as f32 * opacity + bb as f32 * (1.0 - opacity)) as u8;
-                Color::Rgb(r, g, b)
-            }
-            _ => foreground, // For non-RGB colors, return as-is
-        }
-    }
-
     fn calculate_dim_opacity(&self, line_index: usize) -> f32 {
         if let (Some(center_line), Some(max_distance)) = (self.selected_line, self.dim_max_distance)
         {
@@ -93,6 +98,107 @@ impl<'a> SelectableParagraph<'a> {
         }
     }
 
+    // The area inside the vertical padding, the wrapped lines and the scroll offset
+    fn layout(&self, area: Rect) -> Option<(Rect, Vec<WrappedLine<'a>>, usize)> {
+        // Apply padding
+        let inner_area = Rect {
+            x: area.x,
+            y: area.y.saturating_add(self.padding.top),
+            width: area.width,
+            height: area
+                .height
+                .saturating_sub(self.padding.top + self.padding.bottom),
+        };
+
+        if inner_area.width == 0 || inner_area.height == 0 {
+            return None;
+        }
+
+        // For wrapping: first line uses full width minus left padding (no right padding when wrapping)
+        let first_line_width = inner_area.width.saturating_sub(self.padding.left) as usize;
+        let continuation_width = inner_area.width as usize;
+        let height = inner_area.height as usize;
+
+        // Wrap all lines and track which wrapped line corresponds to which original line
+        // Also track if this is the first wrapped line and if wrapping occurred
+        let mut wrapped_lines_with_indices = Vec::new();
+        for (original_idx, line) in self.lines.iter().enumerate() {
+            let wrapped = Self::wrap_line(line, first_line_width, continuation_width);
+            let has_wrap = wrapped.len() > 1;
+            for (wrap_idx, wrapped_line) in wrapped.into_iter().enumerate() {
+                let is_first_wrap = wrap_idx == 0;
+                wrapped_lines_with_indices.push((
+                    original_idx,
+                    wrapped_line,
+                    is_first_wrap,
+                    has_wrap,
+                ));
+            }
+        }
+
+        // Calculate scroll offset to keep selected line centered
+        let scroll_offset = if let Some(selected_idx) = self.selected_line {
+            // Find the first display line of the selected original line
+            let selected_display_line = wrapped_lines_with_indices
+                .iter()
+                .position(|(orig_idx, _, _, _)| *orig_idx == selected_idx)
+                .unwrap_or(0);
+
+            let total_lines = wrapped_lines_with_indices.len();
+
+            if total_lines <= height {
+                // All lines fit, no scrolling needed
+                0
+            } else {
+                // Keep selected line in the middle of viewport
+                let preferred_position = height / 2;
+                let offset = selected_display_line.saturating_sub(preferred_position);
+                let max_offset = total_lines.saturating_sub(height);
+                offset.min(max_offset)
+            }
+        } else {
+            0
+        };
+
+        Some((inner_area, wrapped_lines_with_indices, scroll_offset))
+    }
+
+    /// Index of the line shown at a screen position inside `area` (the block's area), and
+    /// the column within that line's content (0 on the left padding), or None outside of any line.
+    pub fn line_at(&self, area: Rect, column: u16, row: u16) -> Option<(usize, usize)> {
+        let area = match self.block {
+            Some(ref block) => block.inner(area),
+            None => area,
+        };
+        if area.width == 0 || area.height == 0 {
+            return None;
+        }
+        let (inner_area, wrapped, scroll_offset) = self.layout(area)?;
+        if column < inner_area.x
+            || column >= inner_area.x + inner_area.width
+            || row < inner_area.y
+            || row >= inner_area.y + inner_area.height
+        {
+            return None;
+        }
+
+        let display_line = scroll_offset + (row - inner_area.y) as usize;
+        let (original_idx, _, is_first_wrap, _) = wrapped.get(display_line)?;
+        let x = (column - inner_area.x) as usize;
+        let offset = if *is_first_wrap {
+            x.saturating_sub(self.padding.left as usize)
+        } else {
+            // Continuation lines start at the left edge, after the earlier parts of the line
+            let earlier: usize = wrapped[..display_line]
+                .iter()
+                .filter(|(idx, _, _, _)| idx == original_idx)
+                .map(|(_, line, _, _)| line.width())
+                .sum();
+            earlier + x
+        };
+        Some((*original_idx, offset))
+    }
+
     fn wrap_line(
         line: &Line<'a>,
         first_line_width: usize,
@@ -131,7 +237,7 @@ impl<'a> SelectableParagraph<'a> {
                     }
 
                     // Start new line
-                    wrapped_lines.push(Line::from(current_line_spans.clone()));
+                    wrapped_lines.push(Line::from(current_line_spans.clone()).style(line.style));
                     current_line_spans.clear();
                     current_width = 0;
                     current_span_style = None;
@@ -167,11 +273,11 @@ impl<'a> SelectableParagraph<'a> {
 
         // Add the last line if it has content
         if !current_line_spans.is_empty() {
-            wrapped_lines.push(Line::from(current_line_spans));
+            wrapped_lines.push(Line::from(current_line_spans).style(line.style));
         }
 
         if wrapped_lines.is_empty() {
-            vec![Line::from(vec![])]
+            vec![Line::from(vec![]).style(line.style)]
         } else {
             wrapped_lines
         }
@@ -193,22 +299,12 @@ impl Widget for SelectableParagraph<'_> {
             return;
         }
 
-        // Apply padding
-        let inner_area = Rect {
-            x: area.x,
-            y: area.y.saturating_add(self.padding.top),
-            width: area.width,
-            height: area
-                .height
-                .saturating_sub(self.padding.top + self.padding.bottom),
-        };
-
-        if inner_area.width == 0 || inner_area.height == 0 {
+        let Some((inner_area, wrapped_lines_with_indices, scroll_offset)) = self.layout(area)
+        else {
             return;
-        }
+        };
 
-        // For wrapping: first line uses full width minus left padding (no right padding when wrapping)
-        let first_line_width = inner_area.width.saturating_sub(self.padding.left) as usize;
+        // Wrapped continuation lines use the full width, without padding
         let continuation_width = inner_area.width as usize;
         let no_wrap_content_width = inner_area
             .width
@@ -216,47 +312,6 @@ impl Widget for SelectableParagraph<'_> {
             as usize;
         let height = inner_area.height as usize;
 
-        // Wrap all lines and track which wrapped line corresponds to which original line
-        // Also track if this is the first wrapped line and if wrapping occurred
-        let mut wrapped_lines_with_indices = Vec::new();
-        for (original_idx, line) in self.lines.iter().enumerate() {
-            let wrapped = Self::wrap_line(line, first_line_width, continuation_width);
-            let has_wrap = wrapped.len() > 1;
-            for (wrap_idx, wrapped_line) in wrapped.into_iter().enumerate() {
-                let is_first_wrap = wrap_idx == 0;
-                wrapped_lines_with_indices.push((
-                    original_idx,
-                    wrapped_line,
-                    is_first_wrap,
-                    has_wrap,
-                ));
-            }
-        }
-
-        // Calculate scroll offset to keep selected line centered
-        let scroll_offset = if let Some(selected_idx) = self.selected_line {
-            // Find the first display line of the selected original line
-            let selected_display_line = wrapped_lines_with_indices
-                .iter()
-                .position(|(orig_idx, _, _, _)| *orig_idx == selected_idx)
-                .unwrap_or(0);
-
-            let total_lines = wrapped_lines_with_indices.len();
-
-            if total_lines <= height {
-                // All lines fit, no scrolling needed
-                0
-            } else {
-                // Keep selected line in the middle of viewport
-                let preferred_position = height / 2;
-                let offset = selected_display_line.saturating_sub(preferred_position);
-                let max_offset = total_lines.saturating_sub(height);
-                offset.min(max_offset)
-            }
-        } else {
-            0
-        };
-
         let visible_lines: Vec<_> = wrapped_lines_with_indices
             .into_iter()
             .skip(scroll_offset)
@@ -268,18 +323,16 @@ impl Widget for SelectableParagraph<'_> {
             let is_selected = self.selected_line == Some(*original_idx);
             let dim_opacity = self.calculate_dim_opacity(*original_idx);
 
-            let bg_color = if is_selected {
-                self.selected_style.bg
-            } else {
-                self.background_style.bg
-            }
-            .unwrap_or(Color::Reset);
-
+            // A line's own background shows through unless the line is selected
             let fill_style = if is_selected {
                 self.selected_style
             } else {
-                self.background_style
+                match line.style.bg {
+                    Some(bg) => self.background_style.bg(bg),
+                    None => self.background_style,
+                }
             };
+            let bg_color = fill_style.bg.unwrap_or(Color::Reset);
 
             if y >= height {
                 continue;
@@ -305,19 +358,17 @@ impl Widget for SelectableParagraph<'_> {
                 for span in &line.spans {
                     let mut style = span.style;
 
-                    // Apply selected style, but preserve span's own bg/fg (child elements take priority)
-                    if is_selected {
-                        if span.style.bg.is_none() {
-                            style.bg = self.selected_style.bg;
-                        }
-                        if span.style.fg.is_none() {
-                            style.fg = self.selected_style.fg;
-                        }
+                    // Apply the line's style, but preserve span's own bg/fg (child elements take priority)
+                    if span.style.bg.is_none() {
+                        style.bg = fill_style.bg;
+                    }
+                    if is_selected && span.style.fg.is_none() {
+                        style.fg = self.selected_style.fg;
                     }
 
                     // Apply dim to foreground color
                     if let Some(fg) = style.fg {
-                        style = style.fg(self.apply_opacity(fg, dim_opacity, bg_color));
+                        style = style.fg(blend(fg, dim_opacity, bg_color));
                     }
 
                     buf.set_string(
@@ -366,19 +417,17 @@ impl Widget for SelectableParagraph<'_> {
                 for span in &line.spans {
                     let mut style = span.style;
 
-                    // Apply selected style, but preserve span's own bg/fg (child elements take priority)
-                    if is_selected {
-                        if span.style.bg.is_none() {
-                            style.bg = self.selected_style.bg;
-                        }
-                        if span.style.fg.is_none() {
-                            style.fg = self.selected_style.fg;
-                        }
+                    // Apply the line's style, but preserve span's own bg/fg (child elements take priority)
+                    if span.style.bg.is_none() {
+                        style.bg = fill_style.bg;
+                    }
+                    if is_selected && span.style.fg.is_none() {
+                        style.fg = self.selected_style.fg;
                     }
 
                     // Apply dim to foreground color
                     if let Some(fg) = style.fg {
-                        style = style.fg(self.apply_opacity(fg, dim_opacity, bg_color));
+                        style = style.fg(blend(fg, dim_opacity, bg_color));
                     }
 
                     buf.set_string(
@@ -407,19 +456,17 @@ impl Widget for SelectableParagraph<'_> {
                 for span in &line.spans {
                     let mut style = span.style;
 
-                    // Apply selected style, but preserve span's own bg/fg (child elements take priority)
-                    if is_selected {
-                        if span.style.bg.is_none() {
-                            style.bg = self.selected_style.bg;
-                        }
-                        if span.style.fg.is_none() {
-                            style.fg = self.selected_style.fg;
-                        }
+                    // Apply the line's style, but preserve span's own bg/fg (child elements take priority)
+                    if span.style.bg.is_none() {
+                        style.bg = fill_style.bg;
+                    }
+                    if is_selected && span.style.fg.is_none() {
+                        style.fg = self.selected_style.fg;
                     }
 
                     // Apply dim to foreground color
                     if let Some(fg) = style.fg {
-                        style = style.fg(self.apply_opacity(fg, dim_opacity, bg_color));
+                        style = style.fg(blend(fg, dim_opacity, bg_color));
                     }
 
                     buf.set_string(